@@ -2,20 +2,30 @@
 import json
 import os
 import threading
-from http.server import BaseHTTPRequestHandler, HTTPServer
+from collections import deque
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
 from urllib.parse import urlparse, parse_qs
 
 
-def _display(value: float, unit: str, format_hint: str) -> str:
+def _display(value: float, unit: str, format_hint: str, rng=None) -> str:
     if format_hint == "decibel":
-        return f"{(value * 24.0 - 12.0):+.1f} dB"
+        lo, hi = rng or (-12.0, 12.0)
+        return f"{(lo + value * (hi - lo)):+.1f} dB"
     if format_hint == "frequency":
         return f"{(20.0 * ((20000.0 / 20.0) ** value)):.0f} Hz"
+    if format_hint == "time":
+        # Quadratic taper like most delay-time knobs.
+        lo, hi = rng or (0.0, 2000.0)
+        return f"{(lo + value * value * (hi - lo)):.1f} ms"
     if unit == "%":
         return f"{value * 100.0:.0f}%"
     return f"{value:.3f}"
 
 
+def _param_display(p: dict, value: float) -> str:
+    return _display(value, p.get("unit", ""), p.get("format_hint", "raw"), p.get("range"))
+
+
 def plugin_template(name: str):
     n = name.lower()
     if "reagate" in n or "gate" in n:
@@ -35,7 +45,7 @@ def plugin_template(name: str):
             "enabled": True,
             "params": [
                 {"name": "Compressor Bypass", "value": 0.0, "unit": "", "format_hint": "raw"},
-                {"name": "Threshold", "value": 0.5, "unit": "", "format_hint": "raw"},
+                {"name": "Threshold", "value": 0.5, "unit": "dB", "format_hint": "decibel", "range": [-60.0, 0.0]},
                 {"name": "Ratio", "value": 0.25, "unit": "", "format_hint": "raw"},
                 {"name": "Attack", "value": 0.2, "unit": "", "format_hint": "raw"},
                 {"name": "Release", "value": 0.5, "unit": "", "format_hint": "raw"},
@@ -84,7 +94,7 @@ def plugin_template(name: str):
             "enabled": True,
             "params": [
                 {"name": "Delay Bypass", "value": 0.0, "unit": "", "format_hint": "raw"},
-                {"name": "Delay Time", "value": 0.3, "unit": "", "format_hint": "raw"},
+                {"name": "Delay Time", "value": 0.3, "unit": "ms", "format_hint": "time"},
                 {"name": "Delay Feedback", "value": 0.2, "unit": "", "format_hint": "raw"},
                 {"name": "Delay Mix", "value": 0.1, "unit": "", "format_hint": "raw"},
             ],
@@ -140,6 +150,38 @@ def plugin_template(name: str):
     return {"name": name, "enabled": True, "params": []}
 
 
+LEVEL_PARAM_NAMES = ("output", "level", "volume", "master", "makeup")
+
+
+def _simulate_meter(track: dict) -> dict:
+    # Crude level model for /track/meter: a reference signal at the track's input level,
+    # shifted by every enabled plugin's level knob (+-12 dB around 0.5, or its dB display)
+    # and drive knobs, which also squash the crest factor.
+    rms = float(track.get("input_rms_db", -20.0))
+    crest = 14.0
+    for fx in track["fx"]:
+        if not fx.get("enabled", True):
+            continue
+        params = fx.get("params", [])
+        if any("bypass" in p["name"].lower() and float(p["value"]) >= 0.5 for p in params):
+            continue
+        for p in params:
+            name = p["name"].lower()
+            value = float(p["value"])
+            if name in LEVEL_PARAM_NAMES or name.endswith(" output"):
+                if p.get("format_hint") == "decibel":
+                    lo, hi = p.get("range") or (-12.0, 12.0)
+                    rms += lo + value * (hi - lo)
+                else:
+                    rms += (value - 0.5) * 24.0
+            elif name in ("drive", "gain"):
+                rms += (value - 0.5) * 12.0
+                crest *= 1.0 - 0.4 * value
+    if rms <= -150.0:
+        return {"peak_db": -150.0, "rms_db": -150.0}
+    return {"peak_db": round(rms + crest, 2), "rms_db": round(rms, 2)}
+
+
 def scenario_state(scenario: str):
     scenario = (scenario or "baseline").lower()
 
@@ -352,14 +394,29 @@ def scenario_state(scenario: str):
     }
 
 
+MAX_CHANGE_EVENTS = 512
+
+
 class State:
     def __init__(self):
         self.lock = threading.Lock()
+        self.changed = threading.Condition(self.lock)
         self.data = scenario_state(os.environ.get("MOCK_SCENARIO", "baseline"))
+        self.seq = 0
+        self.bpm = 120.0
+        self.events = deque(maxlen=MAX_CHANGE_EVENTS)
 
     def reset(self, scenario: str):
         with self.lock:
             self.data = scenario_state(scenario)
+            self.record({"kind": "tracks", "track_count": len(self.data["tracks"])})
+
+    def record(self, event: dict):
+        # Caller must hold self.lock. Mirrors the extension's GET /changes feed.
+        self.seq += 1
+        event["seq"] = self.seq
+        self.events.append(event)
+        self.changed.notify_all()
 
 
 STATE = State()
@@ -406,6 +463,48 @@ class Handler(BaseHTTPRequestHandler):
                     )
             return self._send(200, {"track_count": len(tracks), "tracks": tracks})
 
+        if path == "/transport/bpm":
+            with STATE.lock:
+                return self._send(200, {"bpm": STATE.bpm})
+
+        if path == "/project/info":
+            with STATE.lock:
+                project_path = STATE.data.get("project_path", "C:/Mock/ToneForge.RPP")
+            return self._send(200, {"project_path": project_path})
+
+        if path == "/changes":
+            since = int(qs.get("since", ["0"])[0])
+            timeout_ms = max(0, min(int(qs.get("timeout_ms", ["25000"])[0]), 60000))
+            with STATE.changed:
+                STATE.changed.wait_for(lambda: STATE.seq > since, timeout_ms / 1000.0)
+                oldest = STATE.events[0]["seq"] if STATE.events else STATE.seq + 1
+                changes = [e for e in STATE.events if e["seq"] > since]
+                seq = STATE.seq
+            return self._send(
+                200,
+                {"seq": seq, "changes": changes, "reset": since > 0 and since + 1 < oldest},
+            )
+
+        if path == "/track/mix":
+            track = int(qs.get("track", ["0"])[0])
+            with STATE.lock:
+                try:
+                    t = STATE.data["tracks"][track]
+                except Exception:
+                    return self._send(404, {"error": "Track not found"})
+                return self._send(
+                    200, {"track": track, "volume_db": t.get("volume_db", 0.0), "pan": t.get("pan", 0.0)}
+                )
+
+        if path == "/track/meter":
+            track = int(qs.get("track", ["0"])[0])
+            with STATE.lock:
+                try:
+                    reading = _simulate_meter(STATE.data["tracks"][track])
+                except Exception:
+                    return self._send(404, {"error": "Track not found"})
+            return self._send(200, {"track": track, **reading, "samples": 30})
+
         if path == "/fx/params":
             track = int(qs.get("track", ["0"])[0])
             fx = int(qs.get("fx", ["0"])[0])
@@ -421,13 +520,33 @@ class Handler(BaseHTTPRequestHandler):
                             "index": idx,
                             "name": p["name"],
                             "value": float(p["value"]),
-                            "display": _display(float(p["value"]), p.get("unit", ""), p.get("format_hint", "raw")),
+                            "display": _param_display(p, float(p["value"])),
                             "unit": p.get("unit", ""),
                             "format_hint": p.get("format_hint", "raw"),
                         }
                     )
             return self._send(200, {"track": track, "fx": fx, "params": params})
 
+        if path == "/fx/param_format":
+            track = int(qs.get("track", ["0"])[0])
+            fx = int(qs.get("fx", ["0"])[0])
+            indices = [int(v) for v in qs.get("params", [""])[0].split(",") if v.strip()]
+            values = [float(v) for v in qs.get("values", [""])[0].split(",") if v.strip()]
+            with STATE.lock:
+                try:
+                    plugin = STATE.data["tracks"][track]["fx"][fx]
+                    probed = [(i, plugin["params"][i]) for i in indices]
+                except Exception:
+                    return self._send(404, {"error": "Not found"})
+                params = [
+                    {
+                        "param_index": i,
+                        "samples": [{"value": v, "display": _param_display(p, v)} for v in values],
+                    }
+                    for i, p in probed
+                ]
+            return self._send(200, {"track": track, "fx": fx, "params": params})
+
         if path == "/fx/param_index":
             track = int(qs.get("track", ["0"])[0])
             fx = int(qs.get("fx", ["0"])[0])
@@ -456,6 +575,14 @@ class Handler(BaseHTTPRequestHandler):
         path = parsed.path
         body = self._read_json()
 
+        if path == "/transport/bpm":
+            bpm = float(body.get("bpm", 0))
+            if not 1.0 <= bpm <= 960.0:
+                return self._send(400, {"error": "Invalid BPM"})
+            with STATE.lock:
+                STATE.bpm = bpm
+            return self._send(200, {"success": True, "bpm": bpm})
+
         if path == "/fx/toggle":
             track = int(body.get("track", 0))
             fx = int(body.get("fx", 0))
@@ -465,9 +592,89 @@ class Handler(BaseHTTPRequestHandler):
                     plugin = STATE.data["tracks"][track]["fx"][fx]
                 except Exception:
                     return self._send(404, {"error": "Not found"})
+                if bool(plugin.get("enabled", True)) != enabled:
+                    STATE.record({"kind": "enabled", "track": track, "fx": fx, "enabled": enabled})
                 plugin["enabled"] = enabled
             return self._send(200, {"success": True, "track": track, "fx": fx, "enabled": enabled})
 
+        if path == "/fx/named_config":
+            track = int(body.get("track", 0))
+            fx = int(body.get("fx", 0))
+            key = str(body.get("key", ""))
+            if not key:
+                return self._send(400, {"error": "key is required"})
+            with STATE.lock:
+                try:
+                    plugin = STATE.data["tracks"][track]["fx"][fx]
+                except Exception:
+                    return self._send(404, {"error": "Not found"})
+                plugin.setdefault("named_config", {})[key] = str(body.get("value", ""))
+            return self._send(200, {"success": True, "track": track, "fx": fx, "key": key})
+
+        if path == "/fx/container":
+            track = int(body.get("track", 0))
+            indices = [int(i) for i in body.get("fx", [])]
+            parallel = bool(body.get("parallel", False))
+            with STATE.lock:
+                try:
+                    fx_list = STATE.data["tracks"][track]["fx"]
+                except Exception:
+                    return self._send(404, {"error": "Track not found"})
+                if len(set(indices)) != len(indices) or not all(0 <= i < len(fx_list) for i in indices):
+                    return self._send(400, {"error": "FX index out of range or repeated"})
+                children = [fx_list[i] for i in indices]
+                container_fx = min(indices)
+                for i in sorted(indices, reverse=True):
+                    fx_list.pop(i)
+                fx_list.insert(
+                    container_fx,
+                    {"name": "Container", "enabled": True, "params": [], "children": children, "parallel": parallel},
+                )
+                STATE.record({"kind": "chain", "track": track, "fx_count": len(fx_list)})
+            return self._send(
+                200, {"success": True, "track": track, "container_fx": container_fx, "count": len(children)}
+            )
+
+        if path == "/track/add":
+            name = str(body.get("name", ""))
+            with STATE.lock:
+                tracks = STATE.data["tracks"]
+                tracks.append({"name": name or f"Track {len(tracks) + 1}", "fx": []})
+                STATE.record({"kind": "tracks", "track_count": len(tracks)})
+                index = len(tracks) - 1
+            return self._send(200, {"success": True, "track": index})
+
+        if path == "/track/send":
+            track = int(body.get("track", 0))
+            dest = int(body.get("dest", -1))
+            with STATE.lock:
+                tracks = STATE.data["tracks"]
+                if not (0 <= track < len(tracks) and 0 <= dest < len(tracks)) or track == dest:
+                    return self._send(404, {"error": "Track not found"})
+                sends = tracks[track].setdefault("sends", [])
+                sends.append(
+                    {
+                        "dest": dest,
+                        "volume_db": float(body.get("volume_db", 0.0)),
+                        "pan": max(-1.0, min(1.0, float(body.get("pan", 0.0)))),
+                    }
+                )
+            return self._send(200, {"success": True, "track": track, "dest": dest, "send": len(sends) - 1})
+
+        if path == "/track/mix":
+            track = int(body.get("track", 0))
+            with STATE.lock:
+                try:
+                    t = STATE.data["tracks"][track]
+                except Exception:
+                    return self._send(404, {"error": "Track not found"})
+                if body.get("volume_db") is not None:
+                    t["volume_db"] = float(body["volume_db"])
+                if body.get("pan") is not None:
+                    t["pan"] = max(-1.0, min(1.0, float(body["pan"])))
+                volume_db, pan = t.get("volume_db", 0.0), t.get("pan", 0.0)
+            return self._send(200, {"success": True, "track": track, "volume_db": volume_db, "pan": pan})
+
         if path == "/fx/add":
             track = int(body.get("track", 0))
             plugin_name = body.get("plugin", "")
@@ -480,6 +687,7 @@ class Handler(BaseHTTPRequestHandler):
                     return self._send(404, {"error": "Track not found"})
                 fx_list.append(plugin_template(plugin_name))
                 fx_index = len(fx_list) - 1
+                STATE.record({"kind": "chain", "track": track, "fx_count": len(fx_list)})
             return self._send(
                 200,
                 {
@@ -490,6 +698,22 @@ class Handler(BaseHTTPRequestHandler):
                 },
             )
 
+        if path == "/fx/move":
+            track = int(body.get("track", 0))
+            from_fx = int(body.get("from_fx", 0))
+            to_fx = int(body.get("to_fx", 0))
+            with STATE.lock:
+                try:
+                    fx_list = STATE.data["tracks"][track]["fx"]
+                except Exception:
+                    return self._send(404, {"error": "Track not found"})
+                if not (0 <= from_fx < len(fx_list) and 0 <= to_fx < len(fx_list)):
+                    return self._send(200, {"success": False})
+                fx_list.insert(to_fx, fx_list.pop(from_fx))
+                if from_fx != to_fx:
+                    STATE.record({"kind": "chain", "track": track, "fx_count": len(fx_list)})
+            return self._send(200, {"success": True, "track": track, "from_fx": from_fx, "to_fx": to_fx})
+
         if path == "/fx/param_index":
             track = int(body.get("track", 0))
             fx = int(body.get("fx", 0))
@@ -508,6 +732,10 @@ class Handler(BaseHTTPRequestHandler):
                         value = 0.9
 
                 value = max(0.0, min(1.0, value))
+                if abs(float(p["value"]) - value) > 1e-6:
+                    STATE.record(
+                        {"kind": "param", "track": track, "fx": fx, "param": param_index, "value": value}
+                    )
                 p["value"] = value
 
             return self._send(
@@ -524,6 +752,25 @@ class Handler(BaseHTTPRequestHandler):
 
         return self._send(404, {"error": "Unknown endpoint", "path": path})
 
+    def do_DELETE(self):
+        parsed = urlparse(self.path)
+        path = parsed.path
+        qs = parse_qs(parsed.query)
+
+        if path == "/fx/remove":
+            track = int(qs.get("track", ["0"])[0])
+            fx = int(qs.get("fx", ["0"])[0])
+            with STATE.lock:
+                try:
+                    fx_list = STATE.data["tracks"][track]["fx"]
+                    fx_list.pop(fx)
+                except Exception:
+                    return self._send(404, {"error": "Not found"})
+                STATE.record({"kind": "chain", "track": track, "fx_count": len(fx_list)})
+            return self._send(200, {"success": True, "track": track, "fx": fx})
+
+        return self._send(404, {"error": "Unknown endpoint", "path": path})
+
     def log_message(self, *_args):
         # Silence logs for cleaner test output.
         return
@@ -532,7 +779,8 @@ class Handler(BaseHTTPRequestHandler):
 def main():
     host = os.environ.get("MOCK_HOST", "127.0.0.1")
     port = int(os.environ.get("MOCK_PORT", "8888"))
-    server = HTTPServer((host, port), Handler)
+    # Threaded so a pending /changes long-poll does not block other requests.
+    server = ThreadingHTTPServer((host, port), Handler)
     server.serve_forever()
 
 
@@ -1208,6 +1208,9 @@ fn offline_engineer(name: &str, _prompt: &str) -> EngineerOut {
         parameters: ToneParameters {
             amp,
             eq,
+            eq_bands: Vec::new(),
+            cab: Default::default(),
+            routing: Default::default(),
             effects,
             reverb,
             delay,
@@ -1439,6 +1442,107 @@ async fn apply_actions(client: &Client, actions: &[ParameterAction]) -> Result<A
                 }
                 logs.push(format!("set fx {} param {} -> {:.3}", plugin_index, param_name, applied));
             }
+            ParameterAction::MovePlugin {
+                track,
+                from_plugin_index,
+                to_plugin_index,
+                ..
+            } => {
+                let resp: Value = client
+                    .post(format!("{}/fx/move", BASE_URL))
+                    .json(&json!({"track": track, "from_fx": from_plugin_index, "to_fx": to_plugin_index}))
+                    .send()
+                    .await?
+                    .json()
+                    .await?;
+                if resp["success"].as_bool() != Some(true) {
+                    warnings.push(format!("move failed for fx {} -> {}", from_plugin_index, to_plugin_index));
+                }
+                logs.push(format!("moved fx {} -> {}", from_plugin_index, to_plugin_index));
+            }
+            ParameterAction::SetNamedConfig {
+                track,
+                plugin_index,
+                key,
+                value,
+                ..
+            } => {
+                let resp: Value = client
+                    .post(format!("{}/fx/named_config", BASE_URL))
+                    .json(&json!({"track": track, "fx": plugin_index, "key": key, "value": value}))
+                    .send()
+                    .await?
+                    .json()
+                    .await?;
+                if resp["success"].as_bool() != Some(true) {
+                    warnings.push(format!("named config {} failed for fx {}", key, plugin_index));
+                }
+                logs.push(format!("set fx {} config {} -> {}", plugin_index, key, value));
+            }
+            ParameterAction::CreateContainer {
+                track,
+                plugin_indices,
+                parallel,
+                ..
+            } => {
+                let resp: Value = client
+                    .post(format!("{}/fx/container", BASE_URL))
+                    .json(&json!({"track": track, "fx": plugin_indices, "parallel": parallel}))
+                    .send()
+                    .await?
+                    .json()
+                    .await?;
+                if resp["success"].as_bool() != Some(true) {
+                    warnings.push(format!("container failed for fx {:?}", plugin_indices));
+                }
+                logs.push(format!("container fx {:?} -> slot {}", plugin_indices, resp["container_fx"]));
+            }
+            ParameterAction::CreateSendTrack {
+                track,
+                name,
+                plugins,
+                volume_db,
+                pan,
+                ..
+            } => {
+                let created: Value = client
+                    .post(format!("{}/track/add", BASE_URL))
+                    .json(&json!({"name": name}))
+                    .send()
+                    .await?
+                    .json()
+                    .await?;
+                let Some(dest) = created["track"].as_i64() else {
+                    warnings.push(format!("track '{}' was not created", name));
+                    continue;
+                };
+                client
+                    .post(format!("{}/track/send", BASE_URL))
+                    .json(&json!({"track": track, "dest": dest, "volume_db": volume_db, "pan": pan}))
+                    .send()
+                    .await?;
+                for plugin in plugins {
+                    client
+                        .post(format!("{}/fx/add", BASE_URL))
+                        .json(&json!({"track": dest, "plugin": plugin}))
+                        .send()
+                        .await?;
+                }
+                logs.push(format!("send track {} '{}' with {} plugin(s)", dest, name, plugins.len()));
+            }
+            ParameterAction::SetTrackMix {
+                track,
+                volume_db,
+                pan,
+                ..
+            } => {
+                client
+                    .post(format!("{}/track/mix", BASE_URL))
+                    .json(&json!({"track": track, "volume_db": volume_db, "pan": pan}))
+                    .send()
+                    .await?;
+                logs.push(format!("track {} mix {:?} dB pan {:?}", track, volume_db, pan));
+            }
         }
     }
 
@@ -1834,6 +1938,9 @@ fn parse_tone_parameters(v: &Value) -> Result<ToneParameters> {
     Ok(ToneParameters {
         amp: parse_map_f64(&v["amp"])?,
         eq: parse_map_f64(&v["eq"])?,
+        eq_bands: serde_json::from_value(v["eq_bands"].clone()).unwrap_or_default(),
+        cab: serde_json::from_value(v["cab"].clone()).unwrap_or_default(),
+        routing: serde_json::from_value(v["routing"].clone()).unwrap_or_default(),
         effects: parse_effects(&v["effects"])?,
         reverb: parse_map_f64(&v["reverb"])?,
         delay: parse_map_f64(&v["delay"])?,
@@ -1,12 +1,12 @@
 pub mod parameter_ai {
-    #[derive(Debug, Clone, PartialEq)]
+    #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
     pub struct ReaperSnapshot {
         pub track_index: i32,
         pub track_name: String,
         pub plugins: Vec<ReaperPlugin>,
     }
 
-    #[derive(Debug, Clone, PartialEq)]
+    #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
     pub struct ReaperPlugin {
         pub index: i32,
         pub name: String,
@@ -14,7 +14,7 @@ pub mod parameter_ai {
         pub parameters: Vec<ReaperParameter>,
     }
 
-    #[derive(Debug, Clone, PartialEq)]
+    #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
     pub struct ReaperParameter {
         pub index: i32,
         pub name: String,
@@ -46,6 +46,39 @@ pub mod parameter_ai {
             position: Option<i32>,
             reason: String,
         },
+        MovePlugin {
+            track: i32,
+            from_plugin_index: i32,
+            to_plugin_index: i32,
+            reason: String,
+        },
+        SetNamedConfig {
+            track: i32,
+            plugin_index: i32,
+            key: String,
+            value: String,
+            reason: String,
+        },
+        CreateContainer {
+            track: i32,
+            plugin_indices: Vec<i32>,
+            parallel: bool,
+            reason: String,
+        },
+        CreateSendTrack {
+            track: i32,
+            name: String,
+            plugins: Vec<String>,
+            volume_db: f64,
+            pan: f64,
+            reason: String,
+        },
+        SetTrackMix {
+            track: i32,
+            volume_db: Option<f64>,
+            pan: Option<f64>,
+            reason: String,
+        },
     }
 }
 
@@ -56,6 +89,9 @@ pub mod tone_encyclopedia {
     pub struct ToneParameters {
         pub amp: HashMap<String, f64>,
         pub eq: HashMap<String, f64>,
+        pub eq_bands: Vec<crate::eq_model::EqBand>,
+        pub cab: crate::cab_model::CabParameters,
+        pub routing: crate::routing::Routing,
         pub effects: Vec<EffectParameters>,
         pub reverb: HashMap<String, f64>,
         pub delay: HashMap<String, f64>,
@@ -68,13 +104,114 @@ pub mod tone_encyclopedia {
     }
 }
 
+#[cfg(test)]
+#[path = "../../tauri-app/src-tauri/src/test_fixtures.rs"]
+mod test_fixtures;
+
 // Compile the exact mapper code under test.
 #[path = "../../tauri-app/src-tauri/src/chain_mapper.rs"]
 mod chain_mapper;
 
 pub use chain_mapper::{ChainMapper, ChainMapperConfig, ChainMappingResult};
 
+#[path = "../../tauri-app/src-tauri/src/chain_order.rs"]
+mod chain_order;
+pub use chain_order::{
+    plan_chain_order, ChainMove, ChainOrder, ChainOrderPlan, ChainSlot, CANONICAL_CHAIN_ORDER,
+};
+
 #[path = "../../tauri-app/src-tauri/src/tone_sanitizer.rs"]
 mod tone_sanitizer;
 
 pub use tone_sanitizer::{sanitize as sanitize_tone, SanitizedTone};
+
+#[path = "../../tauri-app/src-tauri/src/snapshot_diff.rs"]
+mod snapshot_diff;
+
+pub use snapshot_diff::{snapshot_diff, SnapshotDiff};
+
+#[path = "../../tauri-app/src-tauri/src/tone_presets.rs"]
+mod tone_presets;
+
+pub use tone_presets::{
+    missing_plugins, plan_reorder, plan_settings, PresetLibrary, RecallSettings, TonePreset,
+};
+
+#[path = "../../tauri-app/src-tauri/src/rpp_parser.rs"]
+mod rpp_parser;
+pub use rpp_parser::{load_reaper_file, parse_rfxchain, parse_rpp, RppProject};
+
+#[path = "../../tauri-app/src-tauri/src/rfxchain_export.rs"]
+mod rfxchain_export;
+pub use rfxchain_export::{
+    extract_fx_chain, plan_export, ExportPlan, RfxChainExport, EXPORT_TRACK_NAME, STOCK_CHAIN,
+};
+
+#[path = "../../tauri-app/src-tauri/src/reaper_changes.rs"]
+mod reaper_changes;
+pub use reaper_changes::{ChangeBatch, ReaperChange, ReaperChangedEvent, SnapshotRevisions};
+
+#[path = "../../tauri-app/src-tauri/src/snapshot_cache.rs"]
+mod snapshot_cache;
+pub use snapshot_cache::{CacheStats, PluginKey, SnapshotCache};
+
+#[path = "../../tauri-app/src-tauri/src/param_curve.rs"]
+mod param_curve;
+pub use param_curve::{
+    parse_display, probe_points, DisplayUnit, DisplayValue, ParamCurve, ParamCurves,
+    CURVE_PROBE_STEPS,
+};
+
+#[path = "../../tauri-app/src-tauri/src/tempo_sync.rs"]
+mod tempo_sync;
+pub use tempo_sync::{deserialize_delay_map, NoteDivision, NoteModifier, DELAY_NOTE_KEY};
+
+#[path = "../../tauri-app/src-tauri/src/plugin_profile.rs"]
+mod plugin_profile;
+pub use plugin_profile::{
+    CurveScale, CurveSpec, EqBandLayout, EqBandSlot, EqWidthMode, GateKind, PluginProfile,
+    PluginProfileRegistry, RoleSpec, SectionGate,
+};
+
+#[path = "../../tauri-app/src-tauri/src/cab_model.rs"]
+mod cab_model;
+pub use cab_model::{match_option, CabParameters};
+
+#[path = "../../tauri-app/src-tauri/src/routing.rs"]
+mod routing;
+pub use routing::{pan_label, ParallelPath, Routing, RoutingKind, MAX_PARALLEL_PATHS};
+
+#[path = "../../tauri-app/src-tauri/src/eq_model.rs"]
+mod eq_model;
+pub use eq_model::{
+    parse_frequency_hz, q_to_octaves, strongest_bands, tone_eq_bands, EqBand, EqFilterType,
+};
+
+#[path = "../../tauri-app/src-tauri/src/gain_staging.rs"]
+mod gain_staging;
+pub use gain_staging::{
+    find_level_control, next_level_value, GainStagingConfig, MeterReading, StageReport,
+    SILENCE_DB,
+};
+
+#[path = "../../tauri-app/src-tauri/src/ab_compare.rs"]
+mod ab_compare;
+pub use ab_compare::{AbSession, AbSide, MAX_COMPENSATION_DB};
+
+#[path = "../../tauri-app/src-tauri/src/tone_intent.rs"]
+mod tone_intent;
+pub use tone_intent::{words, Adjustment, Amount, Direction, ToneIntent, ToneSection};
+
+#[path = "../../tauri-app/src-tauri/src/relative_edit.rs"]
+mod relative_edit;
+pub use relative_edit::{
+    is_relative_request, plan_relative_edit, RelativeEditPlan, EQ_STEP_DB, KNOB_STEP,
+};
+
+#[path = "../../tauri-app/src-tauri/src/tone_vocabulary.rs"]
+mod tone_vocabulary;
+pub use tone_vocabulary::{EqMove, KnobMove, ToneVocabulary, VocabularyTerm, SUPPORTED_VERSION};
+
+#[path = "../../tauri-app/src-tauri/src/encyclopedia_search.rs"]
+mod encyclopedia_search;
+pub use encyclopedia_search::{SearchField, SearchHit, SearchIndex};
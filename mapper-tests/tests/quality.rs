@@ -34,6 +34,9 @@ fn orders_actions_load_then_enable_then_set() {
     let mut tone = ToneParameters {
         amp: HashMap::new(),
         eq: HashMap::new(),
+        eq_bands: Vec::new(),
+        cab: Default::default(),
+        routing: Default::default(),
         effects: vec![EffectParameters {
             effect_type: "noise_gate".to_string(),
             parameters: HashMap::from([("threshold".to_string(), 0.3)]),
@@ -52,7 +55,12 @@ fn orders_actions_load_then_enable_then_set() {
         let k = match a {
             ParameterAction::LoadPlugin { .. } => 0,
             ParameterAction::EnablePlugin { .. } => 1,
-            ParameterAction::SetParameter { .. } => 2,
+            ParameterAction::SetParameter { .. }
+            | ParameterAction::SetNamedConfig { .. }
+            | ParameterAction::SetTrackMix { .. } => 2,
+            ParameterAction::MovePlugin { .. } => 3,
+            ParameterAction::CreateSendTrack { .. } => 4,
+            ParameterAction::CreateContainer { .. } => 5,
         };
         assert!(k >= phase, "actions not ordered: saw {:?} after phase {}", a, phase);
         phase = k;
@@ -71,6 +79,9 @@ fn clamps_out_of_range_values() {
     let mut tone = ToneParameters {
         amp: HashMap::new(),
         eq: HashMap::new(),
+        eq_bands: Vec::new(),
+        cab: Default::default(),
+        routing: Default::default(),
         effects: vec![],
         reverb: HashMap::new(),
         delay: HashMap::new(),
@@ -111,6 +122,9 @@ fn picks_reaeq_for_eq_role_when_present() {
     let tone = ToneParameters {
         amp: HashMap::new(),
         eq: HashMap::from([("800Hz".to_string(), -4.0)]),
+        eq_bands: Vec::new(),
+        cab: Default::default(),
+        routing: Default::default(),
         effects: vec![],
         reverb: HashMap::new(),
         delay: HashMap::new(),
@@ -145,6 +159,9 @@ fn deterministic_for_same_input() {
     let mut tone = ToneParameters {
         amp: HashMap::new(),
         eq: HashMap::new(),
+        eq_bands: Vec::new(),
+        cab: Default::default(),
+        routing: Default::default(),
         effects: vec![],
         reverb: HashMap::new(),
         delay: HashMap::new(),
@@ -177,6 +194,9 @@ fn warns_on_unmapped_param() {
     let mut tone = ToneParameters {
         amp: HashMap::new(),
         eq: HashMap::new(),
+        eq_bands: Vec::new(),
+        cab: Default::default(),
+        routing: Default::default(),
         effects: vec![],
         reverb: HashMap::new(),
         delay: HashMap::new(),
@@ -222,6 +242,9 @@ fn inserts_section_gate_toggle_before_setting_section_param() {
     let mut tone = ToneParameters {
         amp: HashMap::new(),
         eq: HashMap::new(),
+        eq_bands: Vec::new(),
+        cab: Default::default(),
+        routing: Default::default(),
         effects: vec![],
         reverb: HashMap::new(),
         delay: HashMap::new(),
@@ -265,6 +288,9 @@ fn inserts_enable_plugin_if_plugin_disabled_but_params_set() {
     let mut tone = ToneParameters {
         amp: HashMap::new(),
         eq: HashMap::new(),
+        eq_bands: Vec::new(),
+        cab: Default::default(),
+        routing: Default::default(),
         effects: vec![],
         reverb: HashMap::new(),
         delay: HashMap::new(),
@@ -311,6 +337,9 @@ fn sanitizer_clamps_and_canonicalizes_engineer_output() {
             ("800Hz".to_string(), -99.0),
             ("2kHz".to_string(), 99.0),
         ]),
+        eq_bands: Vec::new(),
+        cab: Default::default(),
+        routing: Default::default(),
         effects: vec![EffectParameters {
             effect_type: "Gate".to_string(),
             parameters: HashMap::from([("Thresh".to_string(), 5.0)]),
@@ -380,6 +409,9 @@ fn invariants_hold_across_varied_inputs() {
         let mut tone = ToneParameters {
             amp: HashMap::new(),
             eq: HashMap::new(),
+            eq_bands: Vec::new(),
+            cab: Default::default(),
+            routing: Default::default(),
             effects: vec![EffectParameters {
                 effect_type: "Gate".to_string(),
                 parameters: HashMap::from([("Thresh".to_string(), 2.5)]),
@@ -411,7 +443,12 @@ fn invariants_hold_across_varied_inputs() {
             let k = match a {
                 ParameterAction::LoadPlugin { .. } => 0,
                 ParameterAction::EnablePlugin { .. } => 1,
-                ParameterAction::SetParameter { .. } => 2,
+                ParameterAction::SetParameter { .. }
+                | ParameterAction::SetNamedConfig { .. }
+                | ParameterAction::SetTrackMix { .. } => 2,
+                ParameterAction::MovePlugin { .. } => 3,
+                ParameterAction::CreateSendTrack { .. } => 4,
+                ParameterAction::CreateContainer { .. } => 5,
             };
             assert!(k >= phase);
             phase = k;
@@ -29,19 +29,32 @@
 #define REAPERAPI_WANT_TrackFX_SetParamNormalized
 #define REAPERAPI_WANT_TrackFX_GetParamNormalized
 #define REAPERAPI_WANT_TrackFX_GetFormattedParamValue
+#define REAPERAPI_WANT_TrackFX_FormatParamValueNormalized
 #define REAPERAPI_WANT_TrackFX_AddByName
 #define REAPERAPI_WANT_TrackFX_Delete
 #define REAPERAPI_WANT_TrackFX_CopyToTrack
 #define REAPERAPI_WANT_EnumInstalledFX
 #define REAPERAPI_WANT_InsertTrackAtIndex
 #define REAPERAPI_WANT_DeleteTrack
+#define REAPERAPI_WANT_GetTrackStateChunk
 #define REAPERAPI_WANT_TrackFX_GetEnabled
 #define REAPERAPI_WANT_TrackFX_SetEnabled
+#define REAPERAPI_WANT_TrackFX_SetNamedConfigParm
+#define REAPERAPI_WANT_GetSetMediaTrackInfo_String
+#define REAPERAPI_WANT_SetMediaTrackInfo_Value
+#define REAPERAPI_WANT_GetMediaTrackInfo_Value
+#define REAPERAPI_WANT_CreateTrackSend
+#define REAPERAPI_WANT_SetTrackSendInfo_Value
+#define REAPERAPI_WANT_Track_GetPeakInfo
+#define REAPERAPI_WANT_GetPlayState
+#define REAPERAPI_WANT_OnPlayButton
+#define REAPERAPI_WANT_OnStopButton
 #define REAPERAPI_WANT_SetCurrentBPM
 #define REAPERAPI_WANT_GetProjectTimeSignature2
 #define REAPERAPI_WANT_Main_SaveProject
 #define REAPERAPI_WANT_Main_openProject
 #define REAPERAPI_WANT_GetProjectPath
+#define REAPERAPI_WANT_EnumProjects
 #define REAPERAPI_WANT_CSurf_OnPlayRateChange
 
 #include "reaper_plugin_functions.h"  // Sonra functions
@@ -55,6 +68,10 @@ typedef ReaProject Reaproject;
 #include <algorithm>
 #include <sstream>
 #include <mutex>
+#include <condition_variable>
+#include <deque>
+#include <chrono>
+#include <cmath>
 #include <cstdio>
 #include <cctype>
 
@@ -73,6 +90,24 @@ static std::thread g_server_thread;
 static std::mutex g_api_mutex;
 static std::map<std::string, json> g_plugin_cache;
 
+// Değişiklik akışı (GET /changes): timer ana thread'de FX zincirlerini tarar,
+// farkları sıra numaralı olaylar olarak tamponlar; long-poll istekleri bekler.
+struct FxFingerprint {
+    std::string name;
+    bool enabled = true;
+    std::vector<double> params;
+};
+
+static std::mutex g_change_mutex;
+static std::condition_variable g_change_cv;
+static std::deque<json> g_change_events;
+static long long g_change_seq = 0;
+static const size_t kMaxChangeEvents = 512;
+static const int kChangeScanEveryTicks = 8;  // ~30Hz timer -> ~4 tarama/sn
+static std::vector<std::vector<FxFingerprint>> g_fx_state;
+static bool g_fx_state_ready = false;
+static int (*g_plugin_register)(const char*, void*) = nullptr;
+
 struct ParamMetadata {
     std::string display;
     std::string unit;
@@ -282,6 +317,107 @@ int FindParamIndex(const std::map<std::string, int>& params, const std::string&
     return -1; // Not found
 }
 
+// Değişiklik olayı ekle (g_change_mutex tutulurken çağrılır)
+static void PushChangeEvent(json event) {
+    event["seq"] = ++g_change_seq;
+    g_change_events.push_back(std::move(event));
+    while (g_change_events.size() > kMaxChangeEvents) {
+        g_change_events.pop_front();
+    }
+}
+
+static std::vector<std::vector<FxFingerprint>> CaptureFxState() {
+    std::vector<std::vector<FxFingerprint>> state;
+    int track_count = p_CountTracks(nullptr);
+    state.resize(track_count);
+
+    for (int t = 0; t < track_count; ++t) {
+        MediaTrack* track = p_GetTrack(nullptr, t);
+        if (!track) continue;
+
+        int fx_count = p_TrackFX_GetCount(track);
+        for (int f = 0; f < fx_count; ++f) {
+            FxFingerprint fx;
+            char name[256] = {0};
+            p_TrackFX_GetFXName(track, f, name, sizeof(name));
+            fx.name = name;
+            fx.enabled = p_TrackFX_GetEnabled ? p_TrackFX_GetEnabled(track, f) : true;
+
+            int param_count = p_TrackFX_GetNumParams(track, f);
+            fx.params.reserve(param_count);
+            for (int p = 0; p < param_count; ++p) {
+                fx.params.push_back(p_TrackFX_GetParamNormalized(track, f, p));
+            }
+            state[t].push_back(std::move(fx));
+        }
+    }
+    return state;
+}
+
+// Önceki tarama ile karşılaştır: zincir değiştiyse tek "chain" olayı,
+// aksi halde FX başına "enabled" ve parametre başına "param" olayları.
+static void DiffFxState(const std::vector<std::vector<FxFingerprint>>& next) {
+    if (next.size() != g_fx_state.size()) {
+        PushChangeEvent({{"kind", "tracks"}, {"track_count", (int)next.size()}});
+    }
+
+    size_t shared_tracks = std::min(next.size(), g_fx_state.size());
+    for (size_t t = 0; t < shared_tracks; ++t) {
+        const auto& before = g_fx_state[t];
+        const auto& after = next[t];
+
+        bool chain_changed = before.size() != after.size();
+        for (size_t f = 0; !chain_changed && f < after.size(); ++f) {
+            chain_changed = before[f].name != after[f].name ||
+                            before[f].params.size() != after[f].params.size();
+        }
+        if (chain_changed) {
+            PushChangeEvent({{"kind", "chain"}, {"track", (int)t}, {"fx_count", (int)after.size()}});
+            continue;
+        }
+
+        for (size_t f = 0; f < after.size(); ++f) {
+            if (before[f].enabled != after[f].enabled) {
+                PushChangeEvent({
+                    {"kind", "enabled"}, {"track", (int)t}, {"fx", (int)f},
+                    {"enabled", after[f].enabled}
+                });
+            }
+            for (size_t p = 0; p < after[f].params.size(); ++p) {
+                if (std::abs(before[f].params[p] - after[f].params[p]) > 1e-6) {
+                    PushChangeEvent({
+                        {"kind", "param"}, {"track", (int)t}, {"fx", (int)f},
+                        {"param", (int)p}, {"value", after[f].params[p]}
+                    });
+                }
+            }
+        }
+    }
+}
+
+// REAPER timer callback (ana thread)
+static void ChangeScanTimer() {
+    static int tick = 0;
+    if (++tick % kChangeScanEveryTicks != 0) return;
+
+    std::vector<std::vector<FxFingerprint>> next;
+    {
+        std::lock_guard<std::mutex> api_lock(g_api_mutex);
+        next = CaptureFxState();
+    }
+
+    std::lock_guard<std::mutex> lock(g_change_mutex);
+    long long before = g_change_seq;
+    if (g_fx_state_ready) {
+        DiffFxState(next);
+    }
+    g_fx_state = std::move(next);
+    g_fx_state_ready = true;
+    if (g_change_seq != before) {
+        g_change_cv.notify_all();
+    }
+}
+
 // HTTP Endpoint Handlers
 void SetupHTTPEndpoints() {
     
@@ -772,6 +908,72 @@ void SetupHTTPEndpoints() {
         }
     });
 
+    // Parametreyi değiştirmeden verilen normalize değerlerdeki görüntü metnini döndür
+    // (istemci bununla değer->görüntü eğrisini öğrenir: dB/Hz/ms hedeflerini çevirmek için)
+    // GET /fx/param_format?track=0&fx=1&params=2,3&values=0,0.5,1
+    g_server.Get("/fx/param_format", [](const httplib::Request& req, httplib::Response& res) {
+        std::lock_guard<std::mutex> lock(g_api_mutex);
+
+        try {
+            if (!p_TrackFX_FormatParamValueNormalized) {
+                res.status = 501;
+                res.set_content(R"({"error":"TrackFX_FormatParamValueNormalized not available"})", "application/json");
+                return;
+            }
+
+            int track_idx = req.has_param("track") ? std::stoi(req.get_param_value("track")) : 0;
+            int fx_idx = req.has_param("fx") ? std::stoi(req.get_param_value("fx")) : 0;
+
+            MediaTrack* track = p_GetTrack(nullptr, track_idx);
+            if (!track || fx_idx < 0 || fx_idx >= p_TrackFX_GetCount(track)) {
+                res.status = 404;
+                res.set_content(R"({"error":"Track or FX not found"})", "application/json");
+                return;
+            }
+
+            auto split = [](const std::string& text) {
+                std::vector<std::string> items;
+                std::stringstream ss(text);
+                std::string item;
+                while (std::getline(ss, item, ',')) {
+                    if (!item.empty()) items.push_back(item);
+                }
+                return items;
+            };
+
+            std::vector<double> values;
+            for (const auto& v : split(req.get_param_value("values"))) {
+                values.push_back(std::min(1.0, std::max(0.0, std::stod(v))));
+            }
+
+            int param_count = p_TrackFX_GetNumParams(track, fx_idx);
+            json params = json::array();
+            for (const auto& p : split(req.get_param_value("params"))) {
+                int param_idx = std::stoi(p);
+                if (param_idx < 0 || param_idx >= param_count) continue;
+
+                json samples = json::array();
+                for (double value : values) {
+                    char formatted[256] = {0};
+                    p_TrackFX_FormatParamValueNormalized(track, fx_idx, param_idx, value, formatted, sizeof(formatted));
+                    samples.push_back({{"value", value}, {"display", std::string(formatted)}});
+                }
+                params.push_back({{"param_index", param_idx}, {"samples", samples}});
+            }
+
+            json response = {
+                {"track", track_idx},
+                {"fx", fx_idx},
+                {"params", params}
+            };
+            res.set_content(response.dump(), "application/json");
+        } catch (const std::exception& e) {
+            res.status = 400;
+            json error = {{"error", e.what()}};
+            res.set_content(error.dump(), "application/json");
+        }
+    });
+
     // Toggle FX enable/bypass state
     g_server.Post("/fx/toggle", [](const httplib::Request& req, httplib::Response& res) {
         std::lock_guard<std::mutex> lock(g_api_mutex);
@@ -809,6 +1011,393 @@ void SetupHTTPEndpoints() {
         }
     });
     
+    // Plugin'e özel isimli ayar (ör. IR loader'ın dosya yolu)
+    g_server.Post("/fx/named_config", [](const httplib::Request& req, httplib::Response& res) {
+        std::lock_guard<std::mutex> lock(g_api_mutex);
+
+        try {
+            json body = json::parse(req.body);
+
+            int track_idx = body.value("track", 0);
+            int fx_idx = body.value("fx", 0);
+            std::string key = body.value("key", "");
+            std::string value = body.value("value", "");
+
+            if (key.empty()) {
+                res.status = 400;
+                res.set_content(R"({"error":"key is required"})", "application/json");
+                return;
+            }
+
+            if (!p_TrackFX_SetNamedConfigParm) {
+                res.status = 501;
+                res.set_content(R"({"error":"TrackFX_SetNamedConfigParm not available"})", "application/json");
+                return;
+            }
+
+            MediaTrack* track = p_GetTrack(nullptr, track_idx);
+            if (!track) {
+                res.status = 404;
+                res.set_content(R"({"error":"Track not found"})", "application/json");
+                return;
+            }
+
+            if (fx_idx < 0 || fx_idx >= p_TrackFX_GetCount(track)) {
+                res.status = 400;
+                res.set_content(R"({"error":"FX index out of range"})", "application/json");
+                return;
+            }
+
+            // Plugin anahtarı tanımıyorsa false döner
+            bool ok = p_TrackFX_SetNamedConfigParm(track, fx_idx, key.c_str(), value.c_str());
+
+            json response = {
+                {"success", ok},
+                {"track", track_idx},
+                {"fx", fx_idx},
+                {"key", key}
+            };
+            res.set_content(response.dump(), "application/json");
+        } catch (const std::exception& e) {
+            res.status = 400;
+            json error = {{"error", e.what()}};
+            res.set_content(error.dump(), "application/json");
+        }
+    });
+
+    // FX'leri tek bir container'a taşı (REAPER 7+). parallel=true ise her çocuk
+    // bir öncekiyle paralel çalışır, çıkışlar toplanır (dual amp vb.)
+    // Body: {"track":0,"fx":[1,2],"parallel":true}
+    g_server.Post("/fx/container", [](const httplib::Request& req, httplib::Response& res) {
+        std::lock_guard<std::mutex> lock(g_api_mutex);
+
+        try {
+            json body = json::parse(req.body);
+
+            int track_idx = body.value("track", 0);
+            std::vector<int> fx = body.value("fx", std::vector<int>{});
+            bool parallel = body.value("parallel", false);
+
+            MediaTrack* track = p_GetTrack(nullptr, track_idx);
+            if (!track) {
+                res.status = 404;
+                res.set_content(R"({"error":"Track not found"})", "application/json");
+                return;
+            }
+
+            int fx_count = p_TrackFX_GetCount(track);
+            std::vector<int> sorted = fx;
+            std::sort(sorted.begin(), sorted.end());
+            bool valid = !fx.empty() &&
+                         std::adjacent_find(sorted.begin(), sorted.end()) == sorted.end() &&
+                         sorted.front() >= 0 && sorted.back() < fx_count;
+            if (!valid) {
+                res.status = 400;
+                res.set_content(R"({"error":"FX index out of range or repeated"})", "application/json");
+                return;
+            }
+
+            // Container ilk FX'in yerine eklenir (-1000 - pos: pos'a ekle)
+            int container_idx = sorted.front();
+            if (p_TrackFX_AddByName(track, "Container", false, -1000 - container_idx) != container_idx) {
+                res.status = 500;
+                res.set_content(R"({"error":"Failed to create container (REAPER 7+ required)"})", "application/json");
+                return;
+            }
+
+            // Container adresleme: 0x2000000 + (alt_index + 1) * (üst seviye FX sayısı + 1) + (container + 1)
+            auto child_address = [&](int child) {
+                return 0x2000000 + (child + 1) * (p_TrackFX_GetCount(track) + 1) + (container_idx + 1);
+            };
+
+            std::vector<int> moved;
+            for (size_t child = 0; child < fx.size(); ++child) {
+                // Container eklendiği için +1, daha önce taşınan alttaki FX'ler kadar -1
+                int current = fx[child] + 1 -
+                    (int)std::count_if(moved.begin(), moved.end(), [&](int m) { return m < fx[child]; });
+                p_TrackFX_CopyToTrack(track, current, track, child_address((int)child), true);
+                moved.push_back(fx[child]);
+
+                if (parallel && child > 0 && p_TrackFX_SetNamedConfigParm) {
+                    p_TrackFX_SetNamedConfigParm(track, child_address((int)child), "parallel", "1");
+                }
+            }
+
+            json response = {
+                {"success", true},
+                {"track", track_idx},
+                {"container_fx", container_idx},
+                {"count", (int)fx.size()}
+            };
+            res.set_content(response.dump(), "application/json");
+        } catch (const std::exception& e) {
+            res.status = 400;
+            json error = {{"error", e.what()}};
+            res.set_content(error.dump(), "application/json");
+        }
+    });
+
+    // Projenin sonuna track ekle
+    // Body: {"name":"Guitar - Wet L"}
+    g_server.Post("/track/add", [](const httplib::Request& req, httplib::Response& res) {
+        std::lock_guard<std::mutex> lock(g_api_mutex);
+
+        try {
+            json body = json::parse(req.body);
+            std::string name = body.value("name", "");
+
+            int track_idx = p_CountTracks(nullptr);
+            p_InsertTrackAtIndex(track_idx, true);
+            MediaTrack* track = p_GetTrack(nullptr, track_idx);
+            if (!track) {
+                res.status = 500;
+                res.set_content(R"({"error":"Failed to create track"})", "application/json");
+                return;
+            }
+            if (!name.empty()) {
+                p_GetSetMediaTrackInfo_String(track, "P_NAME", const_cast<char*>(name.c_str()), true);
+            }
+
+            json response = {
+                {"success", true},
+                {"track", track_idx}
+            };
+            res.set_content(response.dump(), "application/json");
+        } catch (const std::exception& e) {
+            res.status = 400;
+            json error = {{"error", e.what()}};
+            res.set_content(error.dump(), "application/json");
+        }
+    });
+
+    // Track'i sil (export için açılan geçici track'ler)
+    // Body: {"track":5}
+    g_server.Post("/track/remove", [](const httplib::Request& req, httplib::Response& res) {
+        std::lock_guard<std::mutex> lock(g_api_mutex);
+
+        try {
+            json body = json::parse(req.body);
+            int track_idx = body.value("track", -1);
+            MediaTrack* track = p_GetTrack(nullptr, track_idx);
+            if (!track) {
+                res.status = 404;
+                res.set_content(R"({"error":"Track not found"})", "application/json");
+                return;
+            }
+
+            p_DeleteTrack(track);
+            res.set_content(R"({"success":true})", "application/json");
+        } catch (const std::exception& e) {
+            res.status = 400;
+            json error = {{"error", e.what()}};
+            res.set_content(error.dump(), "application/json");
+        }
+    });
+
+    // Track state chunk'ı (REAPER'ın RPP'ye yazdığı <TRACK ...> bloğu, plugin state'leri dahil).
+    // Chunk boyutu önceden bilinmediği için buffer, sonuç sığana kadar büyütülür.
+    g_server.Get("/track/chunk", [](const httplib::Request& req, httplib::Response& res) {
+        std::lock_guard<std::mutex> lock(g_api_mutex);
+
+        int track_idx = req.has_param("track") ? std::stoi(req.get_param_value("track")) : 0;
+        MediaTrack* track = p_GetTrack(nullptr, track_idx);
+        if (!track) {
+            res.status = 404;
+            res.set_content(R"({"error":"Track not found"})", "application/json");
+            return;
+        }
+
+        const size_t max_size = 64 * 1024 * 1024;
+        std::vector<char> buffer(1024 * 1024);
+        while (true) {
+            buffer[0] = '\0';
+            if (!p_GetTrackStateChunk(track, buffer.data(), (int)buffer.size(), false)) {
+                res.status = 500;
+                res.set_content(R"({"error":"Failed to read track state chunk"})", "application/json");
+                return;
+            }
+            size_t length = std::find(buffer.begin(), buffer.end(), '\0') - buffer.begin();
+            if (length + 1 < buffer.size()) {
+                break;
+            }
+            if (buffer.size() >= max_size) {
+                res.status = 500;
+                res.set_content(R"({"error":"Track state chunk too large"})", "application/json");
+                return;
+            }
+            buffer.resize(buffer.size() * 2);
+        }
+
+        json response = {
+            {"track", track_idx},
+            {"chunk", std::string(buffer.data())}
+        };
+        res.set_content(response.dump(), "application/json");
+    });
+
+    // Track'ten track'e send (post-fader)
+    // Body: {"track":0,"dest":3,"volume_db":-6.0,"pan":-1.0}
+    g_server.Post("/track/send", [](const httplib::Request& req, httplib::Response& res) {
+        std::lock_guard<std::mutex> lock(g_api_mutex);
+
+        try {
+            json body = json::parse(req.body);
+
+            int track_idx = body.value("track", 0);
+            int dest_idx = body.value("dest", -1);
+            double volume_db = body.value("volume_db", 0.0);
+            double pan = std::clamp(body.value("pan", 0.0), -1.0, 1.0);
+
+            MediaTrack* track = p_GetTrack(nullptr, track_idx);
+            MediaTrack* dest = dest_idx >= 0 ? p_GetTrack(nullptr, dest_idx) : nullptr;
+            if (!track || !dest || track == dest) {
+                res.status = 404;
+                res.set_content(R"({"error":"Track not found"})", "application/json");
+                return;
+            }
+
+            int send_idx = p_CreateTrackSend(track, dest);
+            if (send_idx < 0) {
+                res.status = 500;
+                res.set_content(R"({"error":"Failed to create send"})", "application/json");
+                return;
+            }
+            // category 0 = send; D_VOL lineer kazanç
+            p_SetTrackSendInfo_Value(track, 0, send_idx, "D_VOL", std::pow(10.0, volume_db / 20.0));
+            p_SetTrackSendInfo_Value(track, 0, send_idx, "D_PAN", pan);
+
+            json response = {
+                {"success", true},
+                {"track", track_idx},
+                {"dest", dest_idx},
+                {"send", send_idx}
+            };
+            res.set_content(response.dump(), "application/json");
+        } catch (const std::exception& e) {
+            res.status = 400;
+            json error = {{"error", e.what()}};
+            res.set_content(error.dump(), "application/json");
+        }
+    });
+
+    // Track volume / pan (null olan alan değişmez)
+    // Body: {"track":0,"volume_db":-3.0,"pan":0.0}
+    g_server.Post("/track/mix", [](const httplib::Request& req, httplib::Response& res) {
+        std::lock_guard<std::mutex> lock(g_api_mutex);
+
+        try {
+            json body = json::parse(req.body);
+
+            int track_idx = body.value("track", 0);
+            MediaTrack* track = p_GetTrack(nullptr, track_idx);
+            if (!track) {
+                res.status = 404;
+                res.set_content(R"({"error":"Track not found"})", "application/json");
+                return;
+            }
+
+            if (body.contains("volume_db") && body["volume_db"].is_number()) {
+                double volume_db = body["volume_db"].get<double>();
+                p_SetMediaTrackInfo_Value(track, "D_VOL", std::pow(10.0, volume_db / 20.0));
+            }
+            if (body.contains("pan") && body["pan"].is_number()) {
+                p_SetMediaTrackInfo_Value(track, "D_PAN", std::clamp(body["pan"].get<double>(), -1.0, 1.0));
+            }
+
+            double gain = p_GetMediaTrackInfo_Value(track, "D_VOL");
+            json response = {
+                {"success", true},
+                {"track", track_idx},
+                {"volume_db", gain > 0.0 ? 20.0 * std::log10(gain) : -150.0},
+                {"pan", p_GetMediaTrackInfo_Value(track, "D_PAN")}
+            };
+            res.set_content(response.dump(), "application/json");
+        } catch (const std::exception& e) {
+            res.status = 400;
+            json error = {{"error", e.what()}};
+            res.set_content(error.dump(), "application/json");
+        }
+    });
+
+    g_server.Get("/track/mix", [](const httplib::Request& req, httplib::Response& res) {
+        std::lock_guard<std::mutex> lock(g_api_mutex);
+
+        int track_idx = req.has_param("track") ? std::stoi(req.get_param_value("track")) : 0;
+        MediaTrack* track = p_GetTrack(nullptr, track_idx);
+        if (!track) {
+            res.status = 404;
+            res.set_content(R"({"error":"Track not found"})", "application/json");
+            return;
+        }
+
+        double gain = p_GetMediaTrackInfo_Value(track, "D_VOL");
+        json response = {
+            {"track", track_idx},
+            {"volume_db", gain > 0.0 ? 20.0 * std::log10(gain) : -150.0},
+            {"pan", p_GetMediaTrackInfo_Value(track, "D_PAN")}
+        };
+        res.set_content(response.dump(), "application/json");
+    });
+
+    // Track meter: window_ms boyunca peak/RMS örnekle (dBFS, fader etkisi çıkarılır).
+    // Transport duruyorsa edit cursor'dan çalınır ve sonunda durdurulur; RMS, meter
+    // örneklerinin karesel ortalamasıdır (meter peak'leri üzerinden yaklaşık değer).
+    g_server.Get("/track/meter", [](const httplib::Request& req, httplib::Response& res) {
+        int track_idx = req.has_param("track") ? std::stoi(req.get_param_value("track")) : 0;
+        int window_ms = req.has_param("window_ms") ? std::stoi(req.get_param_value("window_ms")) : 600;
+        window_ms = std::clamp(window_ms, 50, 10000);
+
+        bool started = false;
+        {
+            std::lock_guard<std::mutex> lock(g_api_mutex);
+            if (!p_GetTrack(nullptr, track_idx)) {
+                res.status = 404;
+                res.set_content(R"({"error":"Track not found"})", "application/json");
+                return;
+            }
+            if ((p_GetPlayState() & 1) == 0) {
+                p_OnPlayButton();
+                started = true;
+            }
+        }
+
+        // Meter'ın oturması için kısa bekleme, sonra ~20ms aralıklarla örnekle
+        std::this_thread::sleep_for(std::chrono::milliseconds(100));
+        double peak = 0.0;
+        double sum_sq = 0.0;
+        int samples = 0;
+        auto end = std::chrono::steady_clock::now() + std::chrono::milliseconds(window_ms);
+        while (std::chrono::steady_clock::now() < end) {
+            {
+                std::lock_guard<std::mutex> lock(g_api_mutex);
+                MediaTrack* track = p_GetTrack(nullptr, track_idx);
+                if (!track) break;
+                double fader = p_GetMediaTrackInfo_Value(track, "D_VOL");
+                double level = std::max(p_Track_GetPeakInfo(track, 0), p_Track_GetPeakInfo(track, 1));
+                if (fader > 0.0) level /= fader;
+                peak = std::max(peak, level);
+                sum_sq += level * level;
+                ++samples;
+            }
+            std::this_thread::sleep_for(std::chrono::milliseconds(20));
+        }
+
+        if (started) {
+            std::lock_guard<std::mutex> lock(g_api_mutex);
+            p_OnStopButton();
+        }
+
+        auto to_db = [](double amp) { return amp > 1e-8 ? 20.0 * std::log10(amp) : -150.0; };
+        double rms = samples > 0 ? std::sqrt(sum_sq / samples) : 0.0;
+        json response = {
+            {"track", track_idx},
+            {"peak_db", to_db(peak)},
+            {"rms_db", to_db(rms)},
+            {"samples", samples}
+        };
+        res.set_content(response.dump(), "application/json");
+    });
+
     // BPM control
     g_server.Post("/transport/bpm", [](const httplib::Request& req, httplib::Response& res) {
         std::lock_guard<std::mutex> lock(g_api_mutex);
@@ -923,6 +1512,50 @@ void SetupHTTPEndpoints() {
         }
     });
     
+    g_server.Get("/project/info", [](const httplib::Request&, httplib::Response& res) {
+        std::lock_guard<std::mutex> lock(g_api_mutex);
+
+        // Full .RPP path of the active project; empty if it has never been saved.
+        char project_file[1024] = {0};
+        if (p_EnumProjects) {
+            p_EnumProjects(-1, project_file, sizeof(project_file));
+        }
+
+        json response = {
+            {"project_path", std::string(project_file)}
+        };
+
+        res.set_content(response.dump(), "application/json");
+    });
+
+    // Long-poll değişiklik akışı: since'den sonraki olaylar gelene ya da timeout dolana kadar bekler
+    g_server.Get("/changes", [](const httplib::Request& req, httplib::Response& res) {
+        long long since = req.has_param("since") ? std::stoll(req.get_param_value("since")) : 0;
+        int timeout_ms = req.has_param("timeout_ms") ? std::stoi(req.get_param_value("timeout_ms")) : 25000;
+        timeout_ms = std::max(0, std::min(timeout_ms, 60000));
+
+        std::unique_lock<std::mutex> lock(g_change_mutex);
+        g_change_cv.wait_for(lock, std::chrono::milliseconds(timeout_ms), [since]() {
+            return g_change_seq > since;
+        });
+
+        json changes = json::array();
+        long long oldest = g_change_events.empty() ? g_change_seq + 1 : g_change_events.front()["seq"].get<long long>();
+        for (const auto& event : g_change_events) {
+            if (event["seq"].get<long long>() > since) {
+                changes.push_back(event);
+            }
+        }
+
+        json response = {
+            {"seq", g_change_seq},
+            {"changes", changes},
+            // İstemci tamponun dışında kaldıysa tam yenileme yapmalı
+            {"reset", since > 0 && since + 1 < oldest}
+        };
+        res.set_content(response.dump(), "application/json");
+    });
+
     g_server.Post("/project/load", [](const httplib::Request& req, httplib::Response& res) {
         std::lock_guard<std::mutex> lock(g_api_mutex);
         
@@ -976,6 +1609,10 @@ extern "C" REAPER_PLUGIN_DLL_EXPORT int REAPER_PLUGIN_ENTRYPOINT(
 ) {
     if (!rec) {
         // Unloading
+        if (g_plugin_register) {
+            g_plugin_register("-timer", (void*)ChangeScanTimer);
+        }
+        g_change_cv.notify_all();
         StopHTTPServer();
         return 0;
     }
@@ -998,22 +1635,43 @@ extern "C" REAPER_PLUGIN_DLL_EXPORT int REAPER_PLUGIN_ENTRYPOINT(
     IMPAPI(TrackFX_SetParamNormalized);
     IMPAPI(TrackFX_GetParamNormalized);
     IMPAPI(TrackFX_GetFormattedParamValue);
+    // Opsiyonel (REAPER 5.95+): yoksa /fx/param_format 501 döner
+    p_TrackFX_FormatParamValueNormalized =
+        (decltype(p_TrackFX_FormatParamValueNormalized))rec->GetFunc("TrackFX_FormatParamValueNormalized");
     IMPAPI(TrackFX_AddByName);
     IMPAPI(TrackFX_Delete);
     IMPAPI(EnumInstalledFX);
     IMPAPI(InsertTrackAtIndex);
     IMPAPI(DeleteTrack);
+    IMPAPI(GetTrackStateChunk);
     IMPAPI(TrackFX_GetEnabled);
     IMPAPI(TrackFX_SetEnabled);
+    // Opsiyonel: yoksa /fx/named_config 501 döner
+    p_TrackFX_SetNamedConfigParm =
+        (decltype(p_TrackFX_SetNamedConfigParm))rec->GetFunc("TrackFX_SetNamedConfigParm");
+    IMPAPI(GetSetMediaTrackInfo_String);
+    IMPAPI(SetMediaTrackInfo_Value);
+    IMPAPI(GetMediaTrackInfo_Value);
+    IMPAPI(CreateTrackSend);
+    IMPAPI(SetTrackSendInfo_Value);
+    IMPAPI(Track_GetPeakInfo);
+    IMPAPI(GetPlayState);
+    IMPAPI(OnPlayButton);
+    IMPAPI(OnStopButton);
     IMPAPI(SetCurrentBPM);
     IMPAPI(GetProjectTimeSignature2);
     IMPAPI(Main_SaveProject);
     IMPAPI(Main_openProject);
     IMPAPI(GetProjectPath);
+    IMPAPI(EnumProjects);
     #undef IMPAPI
     
     // Start HTTP server
     StartHTTPServer();
+
+    // Değişiklik taraması (ana thread timer)
+    g_plugin_register = rec->Register;
+    g_plugin_register("timer", (void*)ChangeScanTimer);
     
     return 1; // Success
 }
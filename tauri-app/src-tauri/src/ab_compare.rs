@@ -0,0 +1,126 @@
+//! A/B Compare
+//!
+//! Before/after listening for the last Act run. The "before" chain is the undo action's
+//! reverted state and "after" its applied state; switching replays the action one way or
+//! the other without moving the undo cursor. Both sides are metered once, and the louder
+//! side is turned down on the track fader so neither wins by level alone.
+
+use crate::gain_staging::MeterReading;
+use serde::{Deserialize, Serialize};
+
+/// Larger level differences are only partly compensated (the meter probably missed).
+pub const MAX_COMPENSATION_DB: f64 = 24.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbSide {
+    Before,
+    After,
+}
+
+impl AbSide {
+    pub fn other(self) -> Self {
+        match self {
+            AbSide::Before => AbSide::After,
+            AbSide::After => AbSide::Before,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AbSession {
+    /// Undo action being compared; must stay on top of the undo stack.
+    pub action_id: String,
+    pub description: String,
+    pub track: i32,
+    /// Track volume when the session started, restored when it ends.
+    pub base_volume_db: f64,
+    pub active: AbSide,
+    pub before_level: MeterReading,
+    pub after_level: MeterReading,
+}
+
+impl AbSession {
+    /// Fader offset that brings `side` down to the quieter side's loudness.
+    pub fn compensation_db(&self, side: AbSide) -> f64 {
+        let (own, other) = match side {
+            AbSide::Before => (&self.before_level, &self.after_level),
+            AbSide::After => (&self.after_level, &self.before_level),
+        };
+        if own.is_silent() || other.is_silent() {
+            return 0.0;
+        }
+        (other.rms_db - own.rms_db).clamp(-MAX_COMPENSATION_DB, 0.0)
+    }
+
+    pub fn volume_db(&self, side: AbSide) -> f64 {
+        self.base_volume_db + self.compensation_db(side)
+    }
+
+    pub fn describe(&self) -> String {
+        let side = |s: AbSide| match s {
+            AbSide::Before => "before",
+            AbSide::After => "after",
+        };
+        format!(
+            "A/B '{}' on track {}: playing {} (before {:.1} dB RMS, after {:.1} dB RMS; {} turned down {:.1} dB)",
+            self.description,
+            self.track,
+            side(self.active),
+            self.before_level.rms_db,
+            self.after_level.rms_db,
+            if self.compensation_db(AbSide::Before) < 0.0 {
+                side(AbSide::Before)
+            } else {
+                side(AbSide::After)
+            },
+            -self
+                .compensation_db(AbSide::Before)
+                .min(self.compensation_db(AbSide::After))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(before_rms: f64, after_rms: f64) -> AbSession {
+        AbSession {
+            action_id: "a1".to_string(),
+            description: "Tone: plexi".to_string(),
+            track: 0,
+            base_volume_db: -2.0,
+            active: AbSide::After,
+            before_level: MeterReading {
+                peak_db: before_rms + 12.0,
+                rms_db: before_rms,
+            },
+            after_level: MeterReading {
+                peak_db: after_rms + 12.0,
+                rms_db: after_rms,
+            },
+        }
+    }
+
+    #[test]
+    fn turns_the_louder_side_down() {
+        let louder_after = session(-22.0, -17.5);
+        assert_eq!(louder_after.volume_db(AbSide::Before), -2.0);
+        assert_eq!(louder_after.volume_db(AbSide::After), -6.5);
+        assert!(louder_after
+            .describe()
+            .ends_with("after turned down 4.5 dB)"));
+
+        let louder_before = session(-10.0, -40.0);
+        assert_eq!(
+            louder_before.compensation_db(AbSide::Before),
+            -MAX_COMPENSATION_DB
+        );
+        assert_eq!(louder_before.compensation_db(AbSide::After), 0.0);
+
+        let silent = session(-150.0, -18.0);
+        assert_eq!(silent.compensation_db(AbSide::After), 0.0);
+        assert_eq!(AbSide::Before.other(), AbSide::After);
+    }
+}
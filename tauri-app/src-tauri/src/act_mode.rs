@@ -8,16 +8,26 @@
 
 use crate::ai_client::AIProvider;
 use crate::ai_chain_orchestrator::{AIChainOrchestrator, OrchestratorConfig};
+use crate::gain_staging::{
+    find_level_control, next_level_value, GainStagingConfig, MeterReading, StageReport,
+};
 use crate::parameter_ai::{ParameterAction, ReaperParameter, ReaperPlugin, ReaperSnapshot};
+use crate::plugin_profile::PluginProfileRegistry;
 use crate::reaper_client::ReaperClient;
+use crate::relative_edit::{is_relative_request, plan_relative_edit};
+use crate::snapshot_cache::{CacheStats, PluginKey, SnapshotCache};
 use crate::tone_ai::ToneAI;
 use crate::tone_sanitizer;
 use crate::tone_encyclopedia::ToneEncyclopedia;
+use crate::tone_intent::ToneIntent;
+use crate::tone_vocabulary::ToneVocabulary;
 use toneforge_core::UndoManager;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_json::Value;
 use std::error::Error;
+use std::sync::Arc;
+use tokio::task::JoinSet;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ProgressStep {
@@ -45,6 +55,10 @@ pub struct ActMode {
     encyclopedia: ToneEncyclopedia,
     reaper_client: ReaperClient,
     ai_provider: AIProvider,
+    snapshot_cache: Option<Arc<SnapshotCache>>,
+    gain_staging: Option<GainStagingConfig>,
+    profiles: PluginProfileRegistry,
+    vocabulary: ToneVocabulary,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -69,9 +83,38 @@ impl ActMode {
             encyclopedia,
             reaper_client,
             ai_provider,
+            snapshot_cache: None,
+            gain_staging: None,
+            profiles: PluginProfileRegistry::builtin(),
+            vocabulary: ToneVocabulary::builtin(),
         }
     }
 
+    /// Reuse unchanged plugins' parameters across snapshots (and across messages).
+    pub fn with_snapshot_cache(mut self, cache: Arc<SnapshotCache>) -> Self {
+        self.snapshot_cache = Some(cache);
+        self
+    }
+
+    /// Measure levels between plugins after applying and trim output controls toward the
+    /// target. Needs a reference item under the edit cursor.
+    pub fn with_gain_staging(mut self, config: GainStagingConfig) -> Self {
+        self.gain_staging = Some(config);
+        self
+    }
+
+    /// Plugin profiles used to find EQ bands and knobs for relative edits.
+    pub fn with_profiles(mut self, profiles: PluginProfileRegistry) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    /// Descriptive words for Tone AI prompts and relative edits.
+    pub fn with_vocabulary(mut self, vocabulary: ToneVocabulary) -> Self {
+        self.vocabulary = vocabulary;
+        self
+    }
+
     /// Process an action request (apply tone to REAPER)
     pub async fn process_message(
         &self,
@@ -94,6 +137,19 @@ impl ActMode {
         println!("[USER] {}", user_message);
         emit(progress, "start", "info", "Act mode pipeline started", None, None);
 
+        // ========== RELATIVE EDIT ==========
+        // "more mids", "less fizz": adjust the current chain instead of regenerating.
+        let intent =
+            ToneIntent::parse_with_artists(user_message, &self.encyclopedia.get_all_artists());
+        if is_relative_request(&intent, &self.vocabulary) {
+            if let Some(response) = self
+                .process_relative_edit(&intent, user_message, track_index, undo_manager, progress)
+                .await?
+            {
+                return Ok(response);
+            }
+        }
+
         // ========== TIER 1: TONE AI ==========
         println!("\n[TIER 1] Running Tone AI...");
         emit(
@@ -106,7 +162,8 @@ impl ActMode {
         );
 
         let tone_ai = ToneAI::new(self.encyclopedia.clone())
-            .with_ai_provider(self.ai_provider.clone());
+            .with_ai_provider(self.ai_provider.clone())
+            .with_vocabulary(self.vocabulary.clone());
 
         let tone_result = tone_ai
             .process_request(user_message)
@@ -209,12 +266,12 @@ impl ActMode {
 
         // Apply prerequisite actions first if we need to load new plugins.
         if requires_resnapshot {
-            println!("[TIER 2] Applying prerequisites (loads/enables) and refreshing REAPER snapshot...");
+            println!("[TIER 2] Applying prerequisites (routing/loads/enables) and refreshing REAPER snapshot...");
             emit(
                 progress,
                 "apply",
                 "info",
-                "Applying load/enable prerequisites (requires resnapshot)",
+                "Applying routing/load/enable prerequisites (requires resnapshot)",
                 None,
                 None,
             );
@@ -223,7 +280,7 @@ impl ActMode {
                 .actions
                 .iter()
                 .cloned()
-                .filter(|a| matches!(a, ParameterAction::LoadPlugin { .. } | ParameterAction::EnablePlugin { .. }))
+                .filter(|a| is_prerequisite(a))
                 .collect();
 
             let pre_result = self
@@ -239,7 +296,7 @@ impl ActMode {
                 progress,
                 "snapshot",
                 "info",
-                "Snapshot refreshed after loads/enables",
+                "Snapshot refreshed after prerequisites",
                 Some(json!({
                     "track_index": refreshed.track_index,
                     "track_name": refreshed.track_name,
@@ -265,7 +322,7 @@ impl ActMode {
                             .actions
                             .iter()
                             .cloned()
-                            .filter(|a| !matches!(a, ParameterAction::LoadPlugin { .. }))
+                            .filter(|a| !matches!(a, ParameterAction::LoadPlugin { .. }) && !is_routing(a))
                             .collect(),
                         warnings,
                     }
@@ -306,6 +363,9 @@ impl ActMode {
             // Keep a full log for transparency (prereqs first, then sets).
             apply_result.logs.splice(0..0, pre_result.logs);
             apply_result.warnings.splice(0..0, pre_result.warnings);
+            self.stage_gain(track_index, undo_manager, &mut apply_result, progress)
+                .await;
+            commit_undo(undo_manager, &mut apply_result.warnings);
 
             let mut all_warnings = Vec::new();
             all_warnings.extend(phase1.warnings);
@@ -313,10 +373,6 @@ impl ActMode {
             all_warnings.extend(tone_warnings);
             all_warnings.extend(apply_result.warnings.clone());
 
-            if let Some(action_id) = undo_manager.commit_action() {
-                println!("[UNDO] Recorded action: {}", action_id);
-            }
-
             println!("\n========== ACT MODE: PIPELINE COMPLETE ==========\n");
             emit(progress, "done", "info", "Act mode pipeline complete", None, None);
 
@@ -352,19 +408,19 @@ impl ActMode {
             None,
         );
 
-        let apply_result = self
+        let mut apply_result = self
             .apply_parameter_actions(&phase1.actions, &reaper_snapshot, undo_manager, progress)
             .await
             .map_err(|e| format!("Failed to apply actions: {}", e))?;
+        self.stage_gain(track_index, undo_manager, &mut apply_result, progress)
+            .await;
 
         for log in &apply_result.logs {
             println!("[ACTION] {}", log);
         }
 
         // ========== COMMIT UNDO ==========
-        if let Some(action_id) = undo_manager.commit_action() {
-            println!("[UNDO] Recorded action: {}", action_id);
-        }
+        commit_undo(undo_manager, &mut apply_result.warnings);
 
         println!("\n========== ACT MODE: PIPELINE COMPLETE ==========\n");
         emit(progress, "done", "info", "Act mode pipeline complete", None, None);
@@ -385,49 +441,326 @@ impl ActMode {
         })
     }
 
-    async fn collect_reaper_snapshot(
+    /// Apply only the deltas for the request's adjustments. `None` when nothing in the
+    /// chain can realize them, so the caller generates a full tone instead.
+    async fn process_relative_edit(
         &self,
-        track_idx: i32,
-    ) -> Result<ReaperSnapshot, Box<dyn Error>> {
-        let overview = self.reaper_client.get_tracks().await?;
+        intent: &ToneIntent,
+        user_message: &str,
+        track_index: i32,
+        undo_manager: &mut UndoManager,
+        progress: Option<&dyn ActProgressSink>,
+    ) -> Result<Option<ActResponse>, String> {
+        let description = intent.describe();
+        println!("[RELATIVE] {}", description);
+        emit(
+            progress,
+            "relative",
+            "info",
+            "Adjustment request: editing the current chain",
+            Some(json!({ "intent": intent })),
+            None,
+        );
 
-        let track = overview
-            .tracks
-            .iter()
-            .find(|t| t.index == track_idx)
-            .ok_or_else(|| format!("Track {} not found", track_idx))?;
+        let snapshot = self
+            .collect_reaper_snapshot(track_index)
+            .await
+            .map_err(|e| format!("Failed to get REAPER state: {}", e))?;
+        let mut curves = self.profiles.declared_curves(&snapshot);
+        curves.overlay(self.reaper_client.learned_curves());
 
-        let mut plugins = Vec::new();
+        let plan = plan_relative_edit(intent, &snapshot, &self.profiles, &curves, &self.vocabulary);
+        if plan.actions.is_empty() {
+            emit(
+                progress,
+                "relative",
+                "warn",
+                "Nothing in the chain to adjust; generating a full tone instead",
+                Some(json!({ "warnings": plan.warnings })),
+                None,
+            );
+            return Ok(None);
+        }
 
-        for fx in &track.fx_list {
-            let params_snapshot = self.reaper_client.get_fx_params(track_idx, fx.index).await?;
+        emit(
+            progress,
+            "apply",
+            "info",
+            "Applying adjustments to REAPER",
+            Some(json!({ "actions": plan.actions.len() })),
+            None,
+        );
+        undo_manager.begin_action(&format!("Adjust: {}", user_message));
+        let mut apply_result = self
+            .apply_parameter_actions(&plan.actions, &snapshot, undo_manager, progress)
+            .await
+            .map_err(|e| format!("Failed to apply actions: {}", e))?;
+        commit_undo(undo_manager, &mut apply_result.warnings);
+        emit(progress, "done", "info", "Act mode pipeline complete", None, None);
 
-            let parameters: Vec<ReaperParameter> = params_snapshot
-                .params
-                .into_iter()
-                .map(|p| ReaperParameter {
-                    index: p.index,
-                    name: p.name,
-                    current_value: p.value,
-                    display_value: p.display,
-                    unit: p.unit,
-                    format_hint: p.format_hint,
-                })
-                .collect();
+        let adjustments: Vec<String> = intent.adjustments.iter().map(|a| a.describe()).collect();
+        let mut warnings = plan.warnings;
+        warnings.extend(apply_result.warnings);
+        Ok(Some(ActResponse {
+            tone_source: "RelativeEdit".to_string(),
+            tone_description: description,
+            confidence: 1.0,
+            summary: format!(
+                "Adjusted the current chain ({}): {} parameter change(s)",
+                adjustments.join(", "),
+                plan.actions.len()
+            ),
+            actions_count: plan.actions.len(),
+            action_logs: apply_result.logs,
+            warnings,
+        }))
+    }
 
-            plugins.push(ReaperPlugin {
-                index: fx.index,
-                name: fx.name.clone(),
-                enabled: fx.enabled,
-                parameters,
-            });
+    /// Gain staging pass (if configured). Failures only warn: the tone is already applied.
+    async fn stage_gain(
+        &self,
+        track_index: i32,
+        undo_manager: &mut UndoManager,
+        result: &mut ApplyResult,
+        progress: Option<&dyn ActProgressSink>,
+    ) {
+        let Some(config) = &self.gain_staging else {
+            return;
+        };
+        emit(
+            progress,
+            "gain",
+            "info",
+            "Measuring levels between plugins",
+            Some(json!({
+                "target_rms_db": config.target_rms_db,
+                "tolerance_db": config.tolerance_db,
+            })),
+            None,
+        );
+        match self
+            .run_gain_staging(track_index, config, undo_manager, &mut result.warnings, progress)
+            .await
+        {
+            Ok(reports) => {
+                for report in &reports {
+                    result.logs.push(format!("✓ Gain: {}", report.describe(config)));
+                }
+                emit(
+                    progress,
+                    "gain",
+                    "info",
+                    "Gain staging complete",
+                    Some(json!({ "stages": reports })),
+                    None,
+                );
+            }
+            Err(e) => {
+                let w = format!("Gain staging skipped: {}", e);
+                emit(progress, "gain", "warn", &w, None, None);
+                result.warnings.push(w);
+            }
         }
+    }
 
-        Ok(ReaperSnapshot {
-            track_index: track_idx,
-            track_name: track.name.clone(),
-            plugins,
-        })
+    /// Probe each enabled plugin's input/output level by bypassing everything after it,
+    /// adjusting its level control when it lands off target. Enable states are restored
+    /// best-effort; plugins that could not be re-enabled end up in `warnings`.
+    async fn run_gain_staging(
+        &self,
+        track_index: i32,
+        config: &GainStagingConfig,
+        undo_manager: &mut UndoManager,
+        warnings: &mut Vec<String>,
+        progress: Option<&dyn ActProgressSink>,
+    ) -> Result<Vec<StageReport>, Box<dyn Error>> {
+        let snapshot = self.collect_reaper_snapshot(track_index).await?;
+        let mut active: Vec<&ReaperPlugin> = snapshot.plugins.iter().filter(|p| p.enabled).collect();
+        active.sort_by_key(|p| p.index);
+        if active.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let result = self
+            .probe_stages(&snapshot, &active, config, undo_manager, progress)
+            .await;
+
+        // Restore the chain whatever happened while probing.
+        for plugin in &active {
+            if let Err(e) = self
+                .reaper_client
+                .set_fx_enabled(track_index, plugin.index, true)
+                .await
+            {
+                let w = format!("Could not re-enable {} after gain staging: {}", plugin.name, e);
+                emit(progress, "gain", "warn", &w, None, None);
+                warnings.push(w);
+            }
+        }
+        self.invalidate_track(track_index);
+        result
+    }
+
+    async fn probe_stages(
+        &self,
+        snapshot: &ReaperSnapshot,
+        active: &[&ReaperPlugin],
+        config: &GainStagingConfig,
+        undo_manager: &mut UndoManager,
+        progress: Option<&dyn ActProgressSink>,
+    ) -> Result<Vec<StageReport>, Box<dyn Error>> {
+        let track = snapshot.track_index;
+        for plugin in active {
+            self.reaper_client.set_fx_enabled(track, plugin.index, false).await?;
+        }
+        let mut input = self.reaper_client.get_track_meter(track, config.window_ms).await?;
+        if input.is_silent() {
+            return Err("no signal on the track; put the edit cursor on a reference item".into());
+        }
+
+        let mut reports = Vec::new();
+        for (stage, plugin) in active.iter().enumerate() {
+            self.reaper_client.set_fx_enabled(track, plugin.index, true).await?;
+            let output = self.reaper_client.get_track_meter(track, config.window_ms).await?;
+            let control = find_level_control(plugin);
+            let mut report = StageReport {
+                plugin_index: plugin.index,
+                plugin_name: plugin.name.clone(),
+                input,
+                output,
+                control: control.map(|c| c.name.clone()),
+                adjusted: None,
+                adjusted_output: None,
+            };
+
+            if let Some(control) = control.filter(|_| !config.within_tolerance(&output)) {
+                let (value, reading) = self
+                    .trim_level(track, plugin, control, output, config, undo_manager)
+                    .await?;
+                if (value - control.current_value).abs() > 1e-4 {
+                    report.adjusted = Some((control.current_value, value));
+                    report.adjusted_output = Some(reading);
+                }
+            }
+
+            emit(
+                progress,
+                "gain",
+                if config.within_tolerance(&report.final_output()) { "info" } else { "warn" },
+                &report.describe(config),
+                Some(json!(report)),
+                Some(ProgressStep {
+                    current: stage + 1,
+                    total: active.len(),
+                }),
+            );
+            input = report.final_output();
+            reports.push(report);
+        }
+        Ok(reports)
+    }
+
+    /// Set-and-measure until the stage is on target; returns the final value and reading.
+    /// The trim is recorded for undo even when a later measurement fails.
+    async fn trim_level(
+        &self,
+        track: i32,
+        plugin: &ReaperPlugin,
+        control: &ReaperParameter,
+        output: MeterReading,
+        config: &GainStagingConfig,
+        undo_manager: &mut UndoManager,
+    ) -> Result<(f64, MeterReading), Box<dyn Error>> {
+        // Best-effort: controls without a numeric display fall back to measured slopes.
+        let _ = self
+            .reaper_client
+            .learn_param_curves(track, plugin.index, &plugin.name, &[control.index])
+            .await;
+        let curves = self.reaper_client.learned_curves();
+        let curve = curves.get(&plugin.name, control.index);
+
+        let mut value = control.current_value;
+        let mut reading = output;
+        let mut previous = None;
+        let mut failure: Option<String> = None;
+        for _ in 0..config.max_iterations {
+            if config.within_tolerance(&reading) {
+                break;
+            }
+            let next = next_level_value(
+                value,
+                config.correction_db(&reading),
+                curve,
+                previous,
+                reading.rms_db,
+            );
+            if (next - value).abs() < 1e-4 {
+                break;
+            }
+            if let Err(e) = self
+                .reaper_client
+                .set_param_by_index(track, plugin.index, control.index, next)
+                .await
+            {
+                failure = Some(e.to_string());
+                break;
+            }
+            previous = Some((value, reading.rms_db));
+            value = next;
+            match self.reaper_client.get_track_meter(track, config.window_ms).await {
+                Ok(next_reading) => reading = next_reading,
+                Err(e) => {
+                    failure = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+
+        // The control already moved; keep it undoable before reporting a failure.
+        if (value - control.current_value).abs() > 1e-4 {
+            undo_manager.record_param_change(
+                track,
+                plugin.index,
+                &plugin.name,
+                control.index,
+                &control.name,
+                control.current_value,
+                value,
+            );
+        }
+        match failure {
+            Some(e) => Err(e.into()),
+            None => Ok((value, reading)),
+        }
+    }
+
+    async fn collect_reaper_snapshot(
+        &self,
+        track_idx: i32,
+    ) -> Result<ReaperSnapshot, Box<dyn Error>> {
+        let (snapshot, stats) = collect_cached_snapshot(
+            &self.reaper_client,
+            self.snapshot_cache.as_deref(),
+            track_idx,
+        )
+        .await?;
+        println!(
+            "[REAPER] Snapshot cache: {} reused, {} fetched",
+            stats.hits, stats.misses
+        );
+        Ok(snapshot)
+    }
+
+    fn invalidate_fx(&self, track: i32, fx: i32) {
+        if let Some(cache) = &self.snapshot_cache {
+            cache.invalidate_fx(track, fx);
+        }
+    }
+
+    fn invalidate_track(&self, track: i32) {
+        if let Some(cache) = &self.snapshot_cache {
+            cache.invalidate_track(track);
+        }
     }
 
     async fn apply_parameter_actions(
@@ -464,6 +797,7 @@ impl ActMode {
                             );
 
                             // Apply change
+                            self.invalidate_fx(*track, *plugin_index);
                             self.reaper_client
                                 .set_param_by_index(*track, *plugin_index, *param_index, *value)
                                 .await?;
@@ -538,6 +872,7 @@ impl ActMode {
                         undo_manager.record_fx_toggle(*track, *plugin_index, plugin_name, plugin.enabled);
                     }
 
+                    self.invalidate_fx(*track, *plugin_index);
                     self.reaper_client.set_fx_enabled(*track, *plugin_index, true).await?;
                     emit(
                         progress,
@@ -563,11 +898,39 @@ impl ActMode {
                     position,
                     ..
                 } => {
-                    let slot = self.reaper_client.add_plugin(*track, plugin_name).await?;
+                    self.invalidate_track(*track);
+                    let mut slot = self.reaper_client.add_plugin(*track, plugin_name).await?;
                     undo_manager.record_plugin_change(*track, slot, plugin_name, true);
-                    if let Some(target) = position {
-                        if *target >= 0 && *target != slot {
-                            let _ = self.reaper_client.move_fx(*track, slot, *target).await;
+                    if let Some(target) = position.filter(|&p| p >= 0 && p < slot) {
+                        // Record the move only once REAPER made it, so undo/redo replay the
+                        // load at the slot the plugin actually ended up in.
+                        match self.reaper_client.move_fx(*track, slot, target).await {
+                            Ok(true) => {
+                                undo_manager.record_fx_move(*track, slot, target);
+                                slot = target;
+                            }
+                            result => {
+                                let error = match result {
+                                    Err(e) => e.to_string(),
+                                    _ => "REAPER rejected the move".to_string(),
+                                };
+                                let w = format!(
+                                    "Loaded '{}' at slot {} but could not move it to {}: {}",
+                                    plugin_name, slot, target, error
+                                );
+                                warnings.push(w.clone());
+                                emit(
+                                    progress,
+                                    "verify",
+                                    "warn",
+                                    &w,
+                                    Some(json!({ "plugin": plugin_name, "slot": slot })),
+                                    Some(ProgressStep {
+                                        current: idx + 1,
+                                        total: actions.len(),
+                                    }),
+                                );
+                            }
                         }
                     }
                     emit(
@@ -597,11 +960,31 @@ impl ActMode {
                     to_plugin_index,
                     reason,
                 } => {
-                    undo_manager.record_fx_move(*track, *from_plugin_index, *to_plugin_index);
-                    let _ = self
+                    self.invalidate_track(*track);
+                    if !self
                         .reaper_client
                         .move_fx(*track, *from_plugin_index, *to_plugin_index)
-                        .await?;
+                        .await?
+                    {
+                        let w = format!(
+                            "REAPER rejected moving FX {} -> {}",
+                            from_plugin_index, to_plugin_index
+                        );
+                        warnings.push(w.clone());
+                        emit(
+                            progress,
+                            "verify",
+                            "warn",
+                            &w,
+                            Some(json!({ "from": from_plugin_index, "to": to_plugin_index })),
+                            Some(ProgressStep {
+                                current: idx + 1,
+                                total: actions.len(),
+                            }),
+                        );
+                        continue;
+                    }
+                    undo_manager.record_fx_move(*track, *from_plugin_index, *to_plugin_index);
                     emit(
                         progress,
                         "apply",
@@ -622,6 +1005,173 @@ impl ActMode {
                         from_plugin_index, to_plugin_index, reason
                     ));
                 }
+                ParameterAction::SetNamedConfig {
+                    track,
+                    plugin_index,
+                    key,
+                    value,
+                    reason,
+                } => {
+                    let plugin_name = snapshot
+                        .plugins
+                        .iter()
+                        .find(|p| p.index == *plugin_index)
+                        .map(|p| p.name.clone())
+                        .unwrap_or_else(|| format!("FX {}", plugin_index));
+
+                    self.invalidate_fx(*track, *plugin_index);
+                    if !self
+                        .reaper_client
+                        .set_fx_named_config(*track, *plugin_index, key, value)
+                        .await?
+                    {
+                        let w = format!("{} rejected named config '{}' = '{}'", plugin_name, key, value);
+                        warnings.push(w.clone());
+                        emit(
+                            progress,
+                            "verify",
+                            "warn",
+                            &w,
+                            Some(json!({ "plugin": plugin_name, "key": key })),
+                            Some(ProgressStep {
+                                current: idx + 1,
+                                total: actions.len(),
+                            }),
+                        );
+                        continue;
+                    }
+                    undo_manager.record_untracked(&format!("{} named config '{}'", plugin_name, key));
+                    emit(
+                        progress,
+                        "apply",
+                        "info",
+                        "Set named config",
+                        Some(json!({
+                            "plugin": plugin_name,
+                            "key": key,
+                            "value": value,
+                            "reason": reason,
+                        })),
+                        Some(ProgressStep {
+                            current: idx + 1,
+                            total: actions.len(),
+                        }),
+                    );
+                    logs.push(format!("✓ {} :: {} = '{}' - {}", plugin_name, key, value, reason));
+                }
+                ParameterAction::CreateContainer {
+                    track,
+                    plugin_indices,
+                    parallel,
+                    reason,
+                } => {
+                    self.invalidate_track(*track);
+                    let container = self
+                        .reaper_client
+                        .create_fx_container(*track, plugin_indices, *parallel)
+                        .await?;
+                    undo_manager.record_untracked(&format!("FX container with FX {:?}", plugin_indices));
+                    emit(
+                        progress,
+                        "apply",
+                        "info",
+                        "Created FX container",
+                        Some(json!({
+                            "plugins": plugin_indices,
+                            "container": container,
+                            "parallel": parallel,
+                            "reason": reason,
+                        })),
+                        Some(ProgressStep {
+                            current: idx + 1,
+                            total: actions.len(),
+                        }),
+                    );
+                    logs.push(format!(
+                        "✓ Container at slot {} with FX {:?} - {}",
+                        container, plugin_indices, reason
+                    ));
+                }
+                ParameterAction::CreateSendTrack {
+                    track,
+                    name,
+                    plugins,
+                    volume_db,
+                    pan,
+                    reason,
+                } => {
+                    // A track with this name is from an earlier apply; leave it as it is.
+                    let existing = self
+                        .reaper_client
+                        .get_tracks()
+                        .await?
+                        .tracks
+                        .into_iter()
+                        .find(|t| t.name == *name);
+                    if let Some(existing) = existing {
+                        warnings.push(format!(
+                            "Send track '{}' already exists (track {}); left as is",
+                            name, existing.index
+                        ));
+                        continue;
+                    }
+
+                    let dest = self.reaper_client.add_track(name).await?;
+                    undo_manager.record_untracked(&format!("send track '{}'", name));
+                    self.reaper_client
+                        .add_track_send(*track, dest, *volume_db, *pan)
+                        .await?;
+                    for plugin in plugins {
+                        if let Err(e) = self.reaper_client.add_plugin(dest, plugin).await {
+                            warnings.push(format!("Send track '{}': failed to load '{}': {}", name, plugin, e));
+                        }
+                    }
+                    emit(
+                        progress,
+                        "apply",
+                        "info",
+                        "Created send track",
+                        Some(json!({
+                            "track": dest,
+                            "name": name,
+                            "plugins": plugins,
+                            "volume_db": volume_db,
+                            "pan": pan,
+                            "reason": reason,
+                        })),
+                        Some(ProgressStep {
+                            current: idx + 1,
+                            total: actions.len(),
+                        }),
+                    );
+                    logs.push(format!("✓ Send track {} '{}' - {}", dest, name, reason));
+                }
+                ParameterAction::SetTrackMix {
+                    track,
+                    volume_db,
+                    pan,
+                    reason,
+                } => {
+                    self.reaper_client.set_track_mix(*track, *volume_db, *pan).await?;
+                    undo_manager.record_untracked(&format!("track {} volume/pan", track));
+                    emit(
+                        progress,
+                        "apply",
+                        "info",
+                        "Set track mix",
+                        Some(json!({
+                            "track": track,
+                            "volume_db": volume_db,
+                            "pan": pan,
+                            "reason": reason,
+                        })),
+                        Some(ProgressStep {
+                            current: idx + 1,
+                            total: actions.len(),
+                        }),
+                    );
+                    logs.push(format!("✓ Track {} mix - {}", track, reason));
+                }
             }
         }
 
@@ -629,11 +1179,139 @@ impl ActMode {
     }
 }
 
+/// Fetch a full track snapshot (FX list + every plugin's parameters) from REAPER.
+pub async fn collect_reaper_snapshot(
+    reaper_client: &ReaperClient,
+    track_idx: i32,
+) -> Result<ReaperSnapshot, Box<dyn Error>> {
+    collect_cached_snapshot(reaper_client, None, track_idx)
+        .await
+        .map(|(snapshot, _)| snapshot)
+}
+
+/// Fetch a track snapshot, reusing cached parameter lists for plugins that have not
+/// changed and fetching the rest concurrently. The FX list (names, bypass) is always live.
+pub async fn collect_cached_snapshot(
+    reaper_client: &ReaperClient,
+    cache: Option<&SnapshotCache>,
+    track_idx: i32,
+) -> Result<(ReaperSnapshot, CacheStats), Box<dyn Error>> {
+    let overview = reaper_client.get_tracks().await?;
+
+    let track = overview
+        .tracks
+        .iter()
+        .find(|t| t.index == track_idx)
+        .ok_or_else(|| format!("Track {} not found", track_idx))?;
+
+    let revision = cache.map(|c| c.current_revision());
+    let mut stats = CacheStats::default();
+    let mut plugins: Vec<Option<ReaperPlugin>> = vec![None; track.fx_list.len()];
+    let mut fetches = JoinSet::new();
+
+    for (slot, fx) in track.fx_list.iter().enumerate() {
+        let key = PluginKey::new(&fx.name, fx.index);
+        if let Some(mut cached) = cache.and_then(|c| c.get(track_idx, &key)) {
+            cached.enabled = fx.enabled;
+            plugins[slot] = Some(cached);
+            stats.hits += 1;
+            continue;
+        }
+
+        stats.misses += 1;
+        let client = reaper_client.clone();
+        let (index, name, enabled) = (fx.index, fx.name.clone(), fx.enabled);
+        fetches.spawn(async move {
+            let params_snapshot = client
+                .get_fx_params(track_idx, index)
+                .await
+                .map_err(|e| format!("Failed to read '{}' parameters: {}", name, e))?;
+
+            let parameters: Vec<ReaperParameter> = params_snapshot
+                .params
+                .into_iter()
+                .map(|p| ReaperParameter {
+                    index: p.index,
+                    name: p.name,
+                    current_value: p.value,
+                    display_value: p.display,
+                    unit: p.unit,
+                    format_hint: p.format_hint,
+                })
+                .collect();
+
+            Ok::<_, String>((
+                slot,
+                ReaperPlugin {
+                    index,
+                    name,
+                    enabled,
+                    parameters,
+                },
+            ))
+        });
+    }
+
+    while let Some(joined) = fetches.join_next().await {
+        let (slot, plugin) = joined.map_err(|e| e.to_string())??;
+        if let (Some(cache), Some(revision)) = (cache, revision) {
+            cache.insert(track_idx, plugin.clone(), revision);
+        }
+        plugins[slot] = Some(plugin);
+    }
+
+    if let Some(cache) = cache {
+        let live: Vec<PluginKey> = track
+            .fx_list
+            .iter()
+            .map(|fx| PluginKey::new(&fx.name, fx.index))
+            .collect();
+        cache.retain_track(track_idx, &live);
+    }
+
+    let snapshot = ReaperSnapshot {
+        track_index: track_idx,
+        track_name: track.name.clone(),
+        plugins: plugins.into_iter().flatten().collect(),
+    };
+    Ok((snapshot, stats))
+}
+
 struct ApplyResult {
     logs: Vec<String>,
     warnings: Vec<String>,
 }
 
+/// Phase1 actions applied before the resnapshot: they change which FX (and tracks) exist,
+/// so phase2 has to plan against their result.
+fn is_prerequisite(action: &ParameterAction) -> bool {
+    matches!(
+        action,
+        ParameterAction::LoadPlugin { .. } | ParameterAction::EnablePlugin { .. }
+    ) || is_routing(action)
+}
+
+fn is_routing(action: &ParameterAction) -> bool {
+    matches!(
+        action,
+        ParameterAction::CreateContainer { .. } | ParameterAction::CreateSendTrack { .. }
+    )
+}
+
+/// Commit the open undo action. Routing and named-config writes cannot be replayed, so
+/// the core drops an action containing them; tell the user instead of failing silently.
+fn commit_undo(undo_manager: &mut UndoManager, warnings: &mut Vec<String>) {
+    let untracked = undo_manager.untracked_changes().to_vec();
+    match undo_manager.commit_action() {
+        Some(action_id) => println!("[UNDO] Recorded action: {}", action_id),
+        None if !untracked.is_empty() => warnings.push(format!(
+            "Not added to undo history: undo cannot revert the {}",
+            untracked.join(", ")
+        )),
+        None => {}
+    }
+}
+
 fn emit(
     sink: Option<&dyn ActProgressSink>,
     stage: &str,
@@ -95,7 +95,12 @@ impl AIChainOrchestrator {
             .map_err(|e| format!("Parameter AI phase1 error: {}", e))?;
 
         let requires_resnapshot = phase1.actions.iter().any(|a| {
-            matches!(a, ParameterAction::LoadPlugin { .. } | ParameterAction::MovePlugin { .. })
+            matches!(
+                a,
+                ParameterAction::LoadPlugin { .. }
+                    | ParameterAction::MovePlugin { .. }
+                    | ParameterAction::CreateContainer { .. }
+            )
         });
 
         if requires_resnapshot {
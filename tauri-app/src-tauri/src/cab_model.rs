@@ -0,0 +1,235 @@
+//! Cab Model
+//!
+//! The cabinet half of a tone: speaker, microphone and mic placement, an optional impulse
+//! response file and the cab's low/high cut. Encyclopedia entries often only name the
+//! cabinet ("Marshall 4x12 with SM57"); `CabParameters::from_description` turns such a
+//! line into the same fields.
+
+use crate::plugin_profile::normalize_token;
+use serde::{Deserialize, Serialize};
+
+/// Common guitar cab microphones: display name, then name spellings (normalized).
+const KNOWN_MICS: &[(&str, &[&str])] = &[
+    ("SM57", &["sm57", "57"]),
+    ("SM7B", &["sm7b", "sm7"]),
+    ("MD421", &["md421", "421"]),
+    ("R-121", &["r121", "121"]),
+    ("U87", &["u87", "87"]),
+    ("e609", &["e609", "609"]),
+    ("e906", &["e906", "906"]),
+    ("M160", &["m160", "160"]),
+    ("C414", &["c414", "414"]),
+    ("RE20", &["re20"]),
+];
+
+/// Words that make a description segment a speaker/cabinet rather than something else.
+const SPEAKER_WORDS: &[&str] = &[
+    "cab",
+    "cabinet",
+    "speaker",
+    "celestion",
+    "greenback",
+    "v30",
+    "vintage",
+    "creamback",
+    "jensen",
+    "eminence",
+    "openback",
+    "closedback",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CabParameters {
+    /// Cabinet / speaker, e.g. "4x12 Greenback".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mic: Option<String>,
+    /// 0.0 = center of the cone (bright) .. 1.0 = edge / off axis (dark).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mic_position: Option<f64>,
+    /// Impulse response file for IR loaders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ir_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub low_cut_hz: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub high_cut_hz: Option<f64>,
+}
+
+impl CabParameters {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// One line for prompts and logs ("speaker 4x12 Greenback, mic SM57, ...").
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(speaker) = &self.speaker {
+            parts.push(format!("speaker {}", speaker));
+        }
+        if let Some(mic) = &self.mic {
+            parts.push(format!("mic {}", mic));
+        }
+        if let Some(position) = self.mic_position {
+            parts.push(format!("mic position {:.2} (0 center, 1 edge)", position));
+        }
+        if let Some(hz) = self.low_cut_hz {
+            parts.push(format!("low cut {:.0} Hz", hz));
+        }
+        if let Some(hz) = self.high_cut_hz {
+            parts.push(format!("high cut {:.0} Hz", hz));
+        }
+        if let Some(path) = &self.ir_path {
+            parts.push(format!("IR file {}", path));
+        }
+        parts.join(", ")
+    }
+
+    /// Parse a free-form cabinet line ("Marshall 1960A 4x12, SM57 off axis").
+    pub fn from_description(text: &str) -> Self {
+        let mut cab = Self::default();
+        let lower = text.to_lowercase();
+        let words = tokens(&lower);
+        let joined = normalize_token(&lower);
+
+        // Bare numbers ("57") only count as whole words; full names also when spelled
+        // with a dash ("SM-57").
+        cab.mic = KNOWN_MICS
+            .iter()
+            .find(|(_, spellings)| {
+                spellings.iter().any(|s| {
+                    words.iter().any(|w| w == s)
+                        || (s.chars().any(|c| c.is_alphabetic()) && joined.contains(s))
+                })
+            })
+            .map(|(name, _)| name.to_string());
+
+        cab.mic_position = if lower.contains("off axis") || lower.contains("off-axis") {
+            Some(1.0)
+        } else if lower.contains("cap edge") {
+            Some(0.5)
+        } else if lower.contains("edge") {
+            Some(1.0)
+        } else if ["on axis", "on-axis", "center", "centre"]
+            .iter()
+            .any(|w| lower.contains(w))
+        {
+            Some(0.0)
+        } else {
+            None
+        };
+
+        // The speaker is the first segment that looks like one ("4x12", "Greenback").
+        cab.speaker = text
+            .split([',', ';', '+', '/'])
+            .flat_map(|s| s.split(" with "))
+            .map(str::trim)
+            .find(|segment| {
+                let norm = normalize_token(segment);
+                tokens(&segment.to_lowercase())
+                    .iter()
+                    .any(|t| is_speaker_config(t))
+                    || SPEAKER_WORDS.iter().any(|w| norm.contains(w))
+            })
+            .map(str::to_string);
+
+        cab
+    }
+}
+
+/// Index of the option label that best fits `wanted`: an exact (normalized) match, else the
+/// label sharing the most name characters ("4x12 Greenback" -> "4x12 GB Greenback").
+pub fn match_option(labels: &[String], wanted: &str) -> Option<usize> {
+    let wanted_norm = normalize_token(wanted);
+    if wanted_norm.is_empty() {
+        return None;
+    }
+    if let Some(i) = labels
+        .iter()
+        .position(|l| normalize_token(l) == wanted_norm)
+    {
+        return Some(i);
+    }
+
+    let mut words = tokens(&wanted.to_lowercase());
+    if let Some((_, spellings)) = KNOWN_MICS.iter().find(|(name, spellings)| {
+        normalize_token(name) == wanted_norm || spellings.contains(&wanted_norm.as_str())
+    }) {
+        words.extend(spellings.iter().map(|s| s.to_string()));
+    }
+
+    let mut best: Option<(usize, usize)> = None;
+    for (i, label) in labels.iter().enumerate() {
+        let label_norm = normalize_token(label);
+        let score: usize = words
+            .iter()
+            .filter(|w| label_norm.contains(w.as_str()))
+            .map(|w| w.len())
+            .sum();
+        if score > 0 && best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((i, score));
+        }
+    }
+    best.map(|(i, _)| i)
+}
+
+fn tokens(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.len() >= 2)
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+/// "4x12", "1x10", "2x12".
+fn is_speaker_config(token: &str) -> bool {
+    let Some((count, size)) = token.split_once('x') else {
+        return false;
+    };
+    !count.is_empty()
+        && !size.is_empty()
+        && count.chars().all(|c| c.is_ascii_digit())
+        && size.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cabinet_descriptions() {
+        let cab = CabParameters::from_description("Marshall 1960A 4x12 with SM57 off axis");
+        assert_eq!(cab.speaker.as_deref(), Some("Marshall 1960A 4x12"));
+        assert_eq!(cab.mic.as_deref(), Some("SM57"));
+        assert_eq!(cab.mic_position, Some(1.0));
+
+        let cab = CabParameters::from_description("Mesa 2x12, Celestion V30 + MD421 cap edge");
+        assert_eq!(cab.speaker.as_deref(), Some("Mesa 2x12"));
+        assert_eq!(cab.mic.as_deref(), Some("MD421"));
+        assert_eq!(cab.mic_position, Some(0.5));
+
+        let cab = CabParameters::from_description("WEM 4x12");
+        assert_eq!(cab.mic, None);
+        assert!(!cab.is_empty());
+        assert_eq!(cab.describe(), "speaker WEM 4x12");
+        assert!(CabParameters::from_description("unknown").is_empty());
+    }
+
+    #[test]
+    fn matches_option_labels() {
+        let speakers: Vec<String> = ["1x12 Open", "2x12 V30", "4x12 V30", "4x12 Greenback"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(match_option(&speakers, "Marshall 4x12 Greenback"), Some(3));
+        assert_eq!(match_option(&speakers, "2x12 v30"), Some(1));
+        assert_eq!(match_option(&speakers, "Fender"), None);
+
+        let mics: Vec<String> = ["Dynamic 57", "Ribbon 121", "Condenser 87"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(match_option(&mics, "SM57"), Some(0));
+        assert_eq!(match_option(&mics, "R-121"), Some(1));
+    }
+}
@@ -0,0 +1,279 @@
+//! Chain Editing
+//!
+//! Direct FX chain edits made from the UI (outside of a tone application). Every edit is
+//! written to REAPER first and then recorded as its own undo action, with enough state to be
+//! reverted faithfully. Repeated parameter nudges are merged by the undo manager.
+
+use crate::reaper_client::{FXParamEntry, ReaperClient};
+use serde::Serialize;
+use tokio::sync::Mutex as AsyncMutex;
+use toneforge_core::{PluginParamState, PluginState, UndoManager};
+
+/// Which parameter to edit: an exact index or a fuzzy name ("gain", "Band 2 Freq").
+#[derive(Debug, Clone)]
+pub enum ParamTarget {
+    Index(i32),
+    Name(String),
+}
+
+impl ParamTarget {
+    pub fn from_args(param_index: Option<i32>, param_name: Option<String>) -> Result<Self, String> {
+        match (param_index, param_name) {
+            (Some(index), _) => Ok(Self::Index(index)),
+            (None, Some(name)) if !name.trim().is_empty() => Ok(Self::Name(name)),
+            _ => Err("Either param_index or param_name is required".to_string()),
+        }
+    }
+}
+
+/// The value to set: normalized 0..1, or a display target such as "-42 dB" / "375 ms"
+/// that is inverted through the parameter's learned curve.
+#[derive(Debug, Clone)]
+pub enum ParamValue {
+    Normalized(f64),
+    Display(String),
+}
+
+impl ParamValue {
+    pub fn from_args(value: Option<f64>, display: Option<String>) -> Result<Self, String> {
+        match (display, value) {
+            (Some(display), _) if !display.trim().is_empty() => Ok(Self::Display(display)),
+            (_, Some(value)) => Ok(Self::Normalized(value)),
+            _ => Err("Either value or display is required".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParamEdit {
+    pub track: i32,
+    pub fx: i32,
+    pub fx_name: String,
+    pub param_index: i32,
+    pub param_name: String,
+    pub old_value: f64,
+    pub new_value: f64,
+}
+
+/// Name and enabled state of an FX slot.
+async fn fx_info(reaper: &ReaperClient, track: i32, fx: i32) -> Result<(String, bool), String> {
+    let tracks = reaper.get_tracks().await.map_err(|e| e.to_string())?;
+    tracks
+        .tracks
+        .iter()
+        .find(|t| t.index == track)
+        .and_then(|t| t.fx_list.iter().find(|f| f.index == fx))
+        .map(|f| (f.name.clone(), f.enabled))
+        .ok_or_else(|| format!("Track {} has no FX {}", track, fx))
+}
+
+fn resolve_param<'a>(
+    reaper: &ReaperClient,
+    params: &'a [FXParamEntry],
+    target: &ParamTarget,
+) -> Result<&'a FXParamEntry, String> {
+    match target {
+        ParamTarget::Index(index) => params
+            .iter()
+            .find(|p| p.index == *index)
+            .ok_or_else(|| format!("No parameter with index {}", index)),
+        ParamTarget::Name(name) => reaper
+            .find_param_entry(params, name)
+            .ok_or_else(|| format!("No parameter matching '{}'", name)),
+    }
+}
+
+/// Set one parameter and record it for undo.
+pub async fn set_param_with_undo(
+    reaper: &ReaperClient,
+    undo_manager: &AsyncMutex<UndoManager>,
+    track: i32,
+    fx: i32,
+    target: ParamTarget,
+    value: ParamValue,
+) -> Result<ParamEdit, String> {
+    let (fx_name, _) = fx_info(reaper, track, fx).await?;
+    let snapshot = reaper
+        .get_fx_params(track, fx)
+        .await
+        .map_err(|e| format!("Failed to read parameters of '{}': {}", fx_name, e))?;
+    let entry = resolve_param(reaper, &snapshot.params, &target)?;
+
+    let new_value = match value {
+        ParamValue::Normalized(value) => value.clamp(0.0, 1.0),
+        ParamValue::Display(display) => reaper
+            .normalized_for_display(track, fx, &fx_name, entry.index, &display)
+            .await
+            .map_err(|e| format!("Cannot set '{}' to {}: {}", entry.name, display, e))?,
+    };
+    reaper
+        .set_param_by_index(track, fx, entry.index, new_value)
+        .await
+        .map_err(|e| format!("Failed to set '{}': {}", entry.name, e))?;
+
+    let mut manager = undo_manager.lock().await;
+    manager.begin_action(&format!("Set {} on {}", entry.name, fx_name));
+    manager.record_param_change(
+        track,
+        fx,
+        &fx_name,
+        entry.index,
+        &entry.name,
+        entry.value,
+        new_value,
+    );
+    manager.commit_action();
+
+    Ok(ParamEdit {
+        track,
+        fx,
+        fx_name,
+        param_index: entry.index,
+        param_name: entry.name.clone(),
+        old_value: entry.value,
+        new_value,
+    })
+}
+
+/// Enable/bypass a plugin and record it for undo.
+pub async fn set_enabled_with_undo(
+    reaper: &ReaperClient,
+    undo_manager: &AsyncMutex<UndoManager>,
+    track: i32,
+    fx: i32,
+    enabled: bool,
+) -> Result<bool, String> {
+    let (fx_name, was_enabled) = fx_info(reaper, track, fx).await?;
+    let current = reaper
+        .set_fx_enabled(track, fx, enabled)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if current != was_enabled {
+        let mut manager = undo_manager.lock().await;
+        let verb = if current { "Enable" } else { "Bypass" };
+        manager.begin_action(&format!("{} {}", verb, fx_name));
+        manager.record_fx_toggle(track, fx, &fx_name, was_enabled);
+        manager.commit_action();
+    }
+    Ok(current)
+}
+
+/// Load a plugin (optionally moving it to `position`) and record it for undo.
+/// Returns the final slot.
+pub async fn add_plugin_with_undo(
+    reaper: &ReaperClient,
+    undo_manager: &AsyncMutex<UndoManager>,
+    track: i32,
+    plugin_name: &str,
+    position: Option<i32>,
+) -> Result<i32, String> {
+    let slot = reaper
+        .add_plugin(track, plugin_name)
+        .await
+        .map_err(|e| format!("Failed to load '{}': {}", plugin_name, e))?;
+
+    let target = position.filter(|&p| p >= 0 && p < slot);
+    let moved = match target {
+        Some(target) => reaper.move_fx(track, slot, target).await.map_err(|e| e.to_string()),
+        None => Ok(false),
+    };
+
+    let mut manager = undo_manager.lock().await;
+    manager.begin_action(&format!("Add {}", plugin_name));
+    manager.record_plugin_change(track, slot, plugin_name, true);
+    let final_slot = match (target, &moved) {
+        (Some(target), Ok(true)) => {
+            manager.record_fx_move(track, slot, target);
+            target
+        }
+        _ => slot,
+    };
+    manager.commit_action();
+
+    match (target, moved) {
+        (Some(target), Ok(false)) => Err(format!(
+            "Loaded '{}' at slot {} but REAPER refused to move it to {}",
+            plugin_name, slot, target
+        )),
+        (_, Err(e)) => Err(format!(
+            "Loaded '{}' at slot {} but could not move it: {}",
+            plugin_name, slot, e
+        )),
+        _ => Ok(final_slot),
+    }
+}
+
+/// Move a plugin within its chain and record it for undo.
+pub async fn move_plugin_with_undo(
+    reaper: &ReaperClient,
+    undo_manager: &AsyncMutex<UndoManager>,
+    track: i32,
+    from: i32,
+    to: i32,
+) -> Result<String, String> {
+    let (fx_name, _) = fx_info(reaper, track, from).await?;
+    if from == to {
+        return Ok(fx_name);
+    }
+    let moved = reaper
+        .move_fx(track, from, to)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !moved {
+        return Err(format!("REAPER refused to move '{}' to slot {}", fx_name, to));
+    }
+
+    let mut manager = undo_manager.lock().await;
+    manager.begin_action(&format!("Move {}", fx_name));
+    manager.record_fx_move(track, from, to);
+    manager.commit_action();
+    Ok(fx_name)
+}
+
+/// Capture a plugin's bypass state and parameter values before it is removed.
+async fn capture_plugin_state(
+    reaper: &ReaperClient,
+    track: i32,
+    fx: i32,
+) -> Result<(String, PluginState), String> {
+    let (name, enabled) = fx_info(reaper, track, fx).await?;
+    let snapshot = reaper
+        .get_fx_params(track, fx)
+        .await
+        .map_err(|e| format!("Failed to read parameters of '{}': {}", name, e))?;
+
+    let state = PluginState {
+        enabled,
+        params: snapshot
+            .params
+            .into_iter()
+            .map(|p| PluginParamState {
+                param_index: p.index,
+                param_name: p.name,
+                value: p.value,
+            })
+            .collect(),
+    };
+    Ok((name, state))
+}
+
+/// Remove a plugin and record it (with its full state) for undo.
+pub async fn remove_plugin_with_undo(
+    reaper: &ReaperClient,
+    undo_manager: &AsyncMutex<UndoManager>,
+    track: i32,
+    fx: i32,
+) -> Result<String, String> {
+    let (name, state) = capture_plugin_state(reaper, track, fx).await?;
+    reaper
+        .remove_plugin(track, fx)
+        .await
+        .map_err(|e| format!("Failed to remove '{}': {}", name, e))?;
+
+    let mut manager = undo_manager.lock().await;
+    manager.begin_action(&format!("Remove {}", name));
+    manager.record_plugin_removal(track, fx, &name, state);
+    manager.commit_action();
+    Ok(name)
+}
@@ -3,9 +3,18 @@
 //! Goal: deterministically map ToneParameters -> REAPER ParameterAction list,
 //! keeping AI away from large parameter spaces and unit conversions.
 
-use crate::parameter_ai::{ParameterAction, ReaperPlugin, ReaperSnapshot};
+use crate::cab_model::{match_option, CabParameters};
+use crate::chain_order::{plan_chain_order, ChainOrder};
+use crate::eq_model::{q_to_octaves, strongest_bands, tone_eq_bands, EqBand, EqFilterType};
+use crate::param_curve::{split_unit_key, DisplayUnit, DisplayValue, ParamCurves};
+use crate::parameter_ai::{ParameterAction, ReaperParameter, ReaperPlugin, ReaperSnapshot};
+use crate::plugin_profile::{
+    find_param, normalize_token, EqBandLayout, EqBandSlot, EqWidthMode, GateKind, PluginProfileRegistry,
+};
+use crate::routing::{pan_label, Routing, RoutingKind};
+use crate::tempo_sync::{NoteDivision, DELAY_NOTE_KEY};
 use crate::tone_encyclopedia::{EffectParameters, ToneParameters};
-use std::borrow::Cow;
+use crate::tone_vocabulary::ToneVocabulary;
 use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone)]
@@ -33,11 +42,74 @@ pub struct ChainMappingResult {
 
 pub struct ChainMapper {
     config: ChainMapperConfig,
+    curves: ParamCurves,
+    tempo_bpm: Option<f64>,
+    profiles: PluginProfileRegistry,
+    chain_order: Option<ChainOrder>,
+    vocabulary: ToneVocabulary,
+}
+
+/// What the group mappers need besides the plugin and the tone values.
+struct MapContext<'a> {
+    profiles: &'a PluginProfileRegistry,
+    /// Declared profile curves overlaid with learned ones.
+    curves: ParamCurves,
+    tempo_bpm: Option<f64>,
+    max_eq_points: usize,
 }
 
 impl ChainMapper {
     pub fn new(config: ChainMapperConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            curves: ParamCurves::new(),
+            tempo_bpm: None,
+            profiles: PluginProfileRegistry::builtin(),
+            chain_order: None,
+            vocabulary: ToneVocabulary::builtin(),
+        }
+    }
+
+    /// Plugin profiles (roles, parameter names, curves, gates); defaults to the built-ins.
+    pub fn with_profiles(mut self, profiles: PluginProfileRegistry) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    /// Use learned value-to-display curves for unit targets ("threshold_db", EQ bands)
+    /// instead of guessing the plugin's scaling.
+    pub fn with_curves(mut self, curves: ParamCurves) -> Self {
+        self.curves = curves;
+        self
+    }
+
+    /// Project tempo, used to turn note-division delay times into milliseconds.
+    pub fn with_tempo(mut self, bpm: f64) -> Self {
+        self.tempo_bpm = Some(bpm).filter(|bpm| bpm.is_finite() && *bpm > 0.0);
+        self
+    }
+
+    /// Also reorder the chain into `order` (see `chain_order`). Moves come after every
+    /// other action, so plugin indices in enable/set actions refer to the snapshot.
+    pub fn with_chain_order(mut self, order: ChainOrder) -> Self {
+        self.chain_order = Some(order);
+        self
+    }
+
+    /// Descriptive words used as `eq` keys ("mud": -3.0) are placed in their vocabulary
+    /// region; defaults to the built-in vocabulary.
+    pub fn with_vocabulary(mut self, vocabulary: ToneVocabulary) -> Self {
+        self.vocabulary = vocabulary;
+        self
+    }
+
+    /// Plugin to load for a role, if loading is allowed and the role names one.
+    fn load_for(&self, role: &str) -> Option<&str> {
+        if self.config.allow_load_plugins {
+            self.profiles.load_name(role)
+        } else {
+            None
+        }
     }
 
     pub fn map(&self, tone_params: &ToneParameters, snapshot: &ReaperSnapshot) -> ChainMappingResult {
@@ -46,8 +118,17 @@ impl ChainMapper {
         let mut warnings: Vec<String> = Vec::new();
         let mut requires_resnapshot = false;
 
+        let mut curves = self.profiles.declared_curves(snapshot);
+        curves.overlay(self.curves.clone());
+        let ctx = MapContext {
+            profiles: &self.profiles,
+            curves,
+            tempo_bpm: self.tempo_bpm,
+            max_eq_points: self.config.max_eq_points,
+        };
+
         // Amp
-        let amp_plugin = pick_best_plugin(snapshot, &role_keywords_amp());
+        let amp_plugin = pick_best_plugin(snapshot, &self.profiles, "amp");
         if let Some(plugin) = amp_plugin {
             if !plugin.enabled && !tone_params.amp.is_empty() {
                 actions.push(ParameterAction::EnablePlugin {
@@ -62,6 +143,7 @@ impl ChainMapper {
                 plugin,
                 &tone_params.amp,
                 "amp",
+                &ctx,
                 &mut actions,
                 &mut warnings,
             );
@@ -69,11 +151,29 @@ impl ChainMapper {
             warnings.push("No suitable amp plugin found; amp parameters were not applied".to_string());
         }
 
+        // Cab (a cab/IR plugin, else the amp sim's own cab section)
+        if !tone_params.cab.is_empty() {
+            let plugin = pick_best_plugin(snapshot, &self.profiles, "cab")
+                .or_else(|| amp_plugin.filter(|p| has_cab_section(p)));
+            if let Some(plugin) = plugin {
+                if !plugin.enabled {
+                    actions.push(ParameterAction::EnablePlugin {
+                        track,
+                        plugin_index: plugin.index,
+                        plugin_name: plugin.name.clone(),
+                        reason: "Enable cab plugin for tone mapping".to_string(),
+                    });
+                }
+                map_cab(track, plugin, &tone_params.cab, &ctx, &mut actions, &mut warnings);
+            } else {
+                warnings.push("No cab or IR loader plugin found; cab settings were not applied".to_string());
+            }
+        }
+
         // Effects (gate/overdrive/etc.)
         for effect in &tone_params.effects {
-            let role = normalize_token(&effect.effect_type);
-            let keywords = role_keywords_for_effect(&role);
-            let plugin = pick_best_plugin(snapshot, &keywords);
+            let role = self.profiles.canonical_role(&effect.effect_type);
+            let plugin = pick_best_plugin(snapshot, &self.profiles, &role);
             if let Some(plugin) = plugin {
                 if !plugin.enabled && !effect.parameters.is_empty() {
                     actions.push(ParameterAction::EnablePlugin {
@@ -83,22 +183,15 @@ impl ChainMapper {
                         reason: format!("Enable '{}' plugin for tone mapping", effect.effect_type),
                     });
                 }
-                map_effect_group(track, plugin, effect, &mut actions, &mut warnings);
-            } else if self.config.allow_load_plugins {
-                if let Some(default_fx) = default_plugin_for_effect(&role) {
-                    actions.push(ParameterAction::LoadPlugin {
-                        track,
-                        plugin_name: default_fx.to_string(),
-                        position: None,
-                        reason: format!("Load missing effect plugin for '{}'", effect.effect_type),
-                    });
-                    requires_resnapshot = true;
-                } else {
-                    warnings.push(format!(
-                        "No suitable plugin found for effect '{}'; skipped",
-                        effect.effect_type
-                    ));
-                }
+                map_effect_group(track, plugin, effect, &ctx, &mut actions, &mut warnings);
+            } else if let Some(default_fx) = self.load_for(&role) {
+                actions.push(ParameterAction::LoadPlugin {
+                    track,
+                    plugin_name: default_fx.to_string(),
+                    position: None,
+                    reason: format!("Load missing effect plugin for '{}'", effect.effect_type),
+                });
+                requires_resnapshot = true;
             } else {
                 warnings.push(format!(
                     "No suitable plugin found for effect '{}'; skipped",
@@ -109,7 +202,7 @@ impl ChainMapper {
 
         // Reverb
         if !tone_params.reverb.is_empty() {
-            let plugin = pick_best_plugin(snapshot, &role_keywords_reverb());
+            let plugin = pick_best_plugin(snapshot, &self.profiles, "reverb");
             if let Some(plugin) = plugin {
                 if !plugin.enabled {
                     actions.push(ParameterAction::EnablePlugin {
@@ -124,13 +217,14 @@ impl ChainMapper {
                     plugin,
                     &tone_params.reverb,
                     "reverb",
+                    &ctx,
                     &mut actions,
                     &mut warnings,
                 );
-            } else if self.config.allow_load_plugins {
+            } else if let Some(load) = self.load_for("reverb") {
                 actions.push(ParameterAction::LoadPlugin {
                     track,
-                    plugin_name: "ReaVerbate (Cockos)".to_string(),
+                    plugin_name: load.to_string(),
                     position: None,
                     reason: "Load missing reverb plugin".to_string(),
                 });
@@ -142,7 +236,7 @@ impl ChainMapper {
 
         // Delay
         if !tone_params.delay.is_empty() {
-            let plugin = pick_best_plugin(snapshot, &role_keywords_delay());
+            let plugin = pick_best_plugin(snapshot, &self.profiles, "delay");
             if let Some(plugin) = plugin {
                 if !plugin.enabled {
                     actions.push(ParameterAction::EnablePlugin {
@@ -152,18 +246,20 @@ impl ChainMapper {
                         reason: "Enable delay plugin for tone mapping".to_string(),
                     });
                 }
+                let delay = resolve_delay_note(track, plugin, &tone_params.delay, &ctx, &mut actions, &mut warnings);
                 map_param_group(
                     track,
                     plugin,
-                    &tone_params.delay,
+                    &delay,
                     "delay",
+                    &ctx,
                     &mut actions,
                     &mut warnings,
                 );
-            } else if self.config.allow_load_plugins {
+            } else if let Some(load) = self.load_for("delay") {
                 actions.push(ParameterAction::LoadPlugin {
                     track,
-                    plugin_name: "ReaDelay (Cockos)".to_string(),
+                    plugin_name: load.to_string(),
                     position: None,
                     reason: "Load missing delay plugin".to_string(),
                 });
@@ -173,9 +269,9 @@ impl ChainMapper {
             }
         }
 
-        // EQ (initial: ReaEQ-focused)
-        if !tone_params.eq.is_empty() {
-            let plugin = pick_best_plugin(snapshot, &role_keywords_eq());
+        // EQ (band-based; the profile's band layout, else generic "Band N Freq/Gain/Q" naming)
+        if !tone_params.eq.is_empty() || !tone_params.eq_bands.is_empty() {
+            let plugin = pick_best_plugin(snapshot, &self.profiles, "eq");
             if let Some(plugin) = plugin {
                 if !plugin.enabled {
                     actions.push(ParameterAction::EnablePlugin {
@@ -185,18 +281,19 @@ impl ChainMapper {
                         reason: "Enable EQ plugin for tone mapping".to_string(),
                     });
                 }
-                if contains_token(&plugin.name, "reaeq") {
-                    map_eq_reaeq(track, plugin, &tone_params.eq, self.config.max_eq_points, &mut actions, &mut warnings);
-                } else {
-                    warnings.push(format!(
-                        "EQ plugin '{}' is not supported by deterministic mapper yet; EQ skipped",
-                        plugin.name
-                    ));
-                }
-            } else if self.config.allow_load_plugins {
+                let generic = EqBandLayout::default();
+                let layout = self
+                    .profiles
+                    .profile_for(&plugin.name)
+                    .and_then(|profile| profile.eq_bands.as_ref())
+                    .unwrap_or(&generic);
+                let eq = self.vocabulary.resolve_eq_keys(&tone_params.eq);
+                let bands = tone_eq_bands(&eq, &tone_params.eq_bands);
+                map_eq_bands(track, plugin, layout, bands, &ctx, &mut actions, &mut warnings);
+            } else if let Some(load) = self.load_for("eq") {
                 actions.push(ParameterAction::LoadPlugin {
                     track,
-                    plugin_name: "ReaEQ (Cockos)".to_string(),
+                    plugin_name: load.to_string(),
                     position: None,
                     reason: "Load missing EQ plugin".to_string(),
                 });
@@ -206,7 +303,30 @@ impl ChainMapper {
             }
         }
 
-        let actions = ensure_prerequisites(actions, snapshot, &mut warnings);
+        // Chain order (loaded plugins are appended, so moves within the snapshot stay valid;
+        // they get their place on the next pass)
+        if let Some(order) = &self.chain_order {
+            let plan = plan_chain_order(snapshot, &self.profiles, order);
+            for m in plan.moves {
+                actions.push(ParameterAction::MovePlugin {
+                    track,
+                    from_plugin_index: m.from,
+                    to_plugin_index: m.to,
+                    reason: format!(
+                        "order :: move '{}' ({}) from slot {} to {}",
+                        m.plugin_name, m.role, m.from, m.to
+                    ),
+                });
+                requires_resnapshot = true;
+            }
+        }
+
+        // Parallel paths (after the moves: a container renumbers the chain)
+        if !tone_params.routing.is_empty() {
+            requires_resnapshot |= self.map_routing(&tone_params.routing, snapshot, &mut actions, &mut warnings);
+        }
+
+        let actions = ensure_prerequisites(actions, snapshot, &self.profiles, &mut warnings);
         let actions = plan_actions(actions, &mut warnings);
         let summary = build_summary(&actions, requires_resnapshot);
 
@@ -217,11 +337,179 @@ impl ChainMapper {
             requires_resnapshot,
         }
     }
+
+    /// Send tracks or a parallel container for the tone's paths. Returns whether the chain
+    /// has to be snapshotted again first (plugins loaded for a path, pending moves).
+    fn map_routing(
+        &self,
+        routing: &Routing,
+        snapshot: &ReaperSnapshot,
+        actions: &mut Vec<ParameterAction>,
+        warnings: &mut Vec<String>,
+    ) -> bool {
+        let track = snapshot.track_index;
+        if routing.dry_db.is_some() || routing.dry_pan.is_some() {
+            actions.push(ParameterAction::SetTrackMix {
+                track,
+                volume_db: routing.dry_db,
+                pan: routing.dry_pan,
+                reason: format!(
+                    "routing :: dry path {:+.1} dB {}",
+                    routing.dry_db.unwrap_or(0.0),
+                    pan_label(routing.dry_pan.unwrap_or(0.0))
+                ),
+            });
+        }
+
+        match routing.kind {
+            RoutingKind::Sends => {
+                for path in &routing.paths {
+                    let plugins: Vec<String> = path
+                        .chain
+                        .iter()
+                        .filter_map(|entry| match self.path_plugin_name(entry, snapshot) {
+                            Some(name) => Some(name),
+                            None => {
+                                warnings.push(format!(
+                                    "routing :: nothing to load for '{}' on path '{}'; skipped",
+                                    entry, path.name
+                                ));
+                                None
+                            }
+                        })
+                        .collect();
+                    actions.push(ParameterAction::CreateSendTrack {
+                        track,
+                        name: format!("{} - {}", snapshot.track_name, path.name),
+                        plugins,
+                        volume_db: path.volume_db,
+                        pan: path.pan,
+                        reason: format!(
+                            "routing :: send path '{}' {:+.1} dB {}",
+                            path.name,
+                            path.volume_db,
+                            pan_label(path.pan)
+                        ),
+                    });
+                }
+                false
+            }
+            RoutingKind::Container => {
+                if snapshot
+                    .plugins
+                    .iter()
+                    .any(|p| normalize_token(&p.name).contains("container"))
+                {
+                    warnings.push("Track already has an FX container; parallel routing left as is".to_string());
+                    return false;
+                }
+                if actions.iter().any(|a| matches!(a, ParameterAction::MovePlugin { .. })) {
+                    warnings.push("Parallel container waits for the chain order moves; run again".to_string());
+                    return true;
+                }
+
+                let mut used: Vec<&ReaperPlugin> = Vec::new();
+                let mut pending_load = false;
+                for path in &routing.paths {
+                    let Some(entry) = path.chain.first() else {
+                        warnings.push(format!("routing :: path '{}' has no plugin; skipped", path.name));
+                        continue;
+                    };
+                    if path.chain.len() > 1 {
+                        warnings.push(format!(
+                            "routing :: container path '{}' runs one plugin; use \"sends\" for a chain",
+                            path.name
+                        ));
+                    }
+                    if path.volume_db != 0.0 || path.pan != 0.0 {
+                        warnings.push(format!(
+                            "routing :: container paths are summed at unity; level/pan of '{}' ignored",
+                            path.name
+                        ));
+                    }
+
+                    let found = snapshot
+                        .plugins
+                        .iter()
+                        .filter(|p| !used.iter().any(|u| u.index == p.index))
+                        .map(|p| (p, self.path_plugin_score(entry, p)))
+                        .filter(|(_, score)| *score > 0)
+                        .max_by_key(|(p, score)| (*score, -p.index))
+                        .map(|(p, _)| p);
+                    if let Some(plugin) = found {
+                        used.push(plugin);
+                        continue;
+                    }
+                    // A second instance of a plugin already on a path (dual amp), else the
+                    // role's stock plugin.
+                    let load = used
+                        .iter()
+                        .find(|p| self.path_plugin_score(entry, p) > 0)
+                        .map(|p| p.name.clone())
+                        .or_else(|| self.load_for(entry).map(str::to_string));
+                    match load {
+                        Some(plugin_name) if self.config.allow_load_plugins => {
+                            actions.push(ParameterAction::LoadPlugin {
+                                track,
+                                plugin_name,
+                                position: None,
+                                reason: format!("Load plugin for parallel path '{}'", path.name),
+                            });
+                            pending_load = true;
+                        }
+                        _ => warnings.push(format!(
+                            "routing :: no plugin for '{}' on path '{}'; skipped",
+                            entry, path.name
+                        )),
+                    }
+                }
+
+                if pending_load {
+                    return true;
+                }
+                if used.len() < 2 {
+                    warnings.push("Parallel container needs at least two paths with plugins; skipped".to_string());
+                    return false;
+                }
+                actions.push(ParameterAction::CreateContainer {
+                    track,
+                    plugin_indices: used.iter().map(|p| p.index).collect(),
+                    parallel: true,
+                    reason: format!(
+                        "routing :: parallel container [{}]",
+                        used.iter().map(|p| p.name.as_str()).collect::<Vec<_>>().join(" | ")
+                    ),
+                });
+                false
+            }
+        }
+    }
+
+    /// How well a snapshot plugin fits a path entry: by role, else by name.
+    fn path_plugin_score(&self, entry: &str, plugin: &ReaperPlugin) -> i32 {
+        if self.profiles.has_role(entry) {
+            return self.profiles.role_score(&plugin.name, entry);
+        }
+        let entry = normalize_token(entry);
+        i32::from(!entry.is_empty() && normalize_token(&plugin.name).contains(&entry))
+    }
+
+    /// Plugin to load on a send track for a path entry: the plugin playing that role on the
+    /// track, the role's stock plugin, or the entry itself as a plugin name.
+    fn path_plugin_name(&self, entry: &str, snapshot: &ReaperSnapshot) -> Option<String> {
+        if !self.profiles.has_role(entry) {
+            return Some(entry.to_string()).filter(|e| !e.trim().is_empty());
+        }
+        pick_best_plugin(snapshot, &self.profiles, entry)
+            .map(|p| p.name.clone())
+            .or_else(|| self.profiles.load_name(entry).map(str::to_string))
+    }
 }
 
 fn ensure_prerequisites(
     mut actions: Vec<ParameterAction>,
     snapshot: &ReaperSnapshot,
+    profiles: &PluginProfileRegistry,
     warnings: &mut Vec<String>,
 ) -> Vec<ParameterAction> {
     // Ensure plugin is enabled before any SetParameter (guard against bugs/missed enables).
@@ -262,11 +550,26 @@ fn ensure_prerequisites(
     // This helps avoid “changing params in an inactive section”.
     let mut inserted_section_toggles: HashSet<(i32, i32)> = HashSet::new(); // (plugin_index, gate_param_index)
 
-    // Build per-plugin gate parameter candidates
+    // Build per-plugin gate parameter candidates: declared by the plugin's profile, or
+    // guessed from parameter names.
     let mut plugin_gates: HashMap<i32, Vec<GateParam>> = HashMap::new();
     for plugin in &snapshot.plugins {
         let mut gates = Vec::new();
-        for p in &plugin.parameters {
+        let declared = profiles
+            .profile_for(&plugin.name)
+            .map(|profile| profile.gates.as_slice())
+            .unwrap_or_default();
+        for gate in declared {
+            let Some(p) = find_param(plugin, &gate.param) else { continue };
+            gates.push(GateParam {
+                param_index: p.index,
+                param_name: p.name.clone(),
+                current_value: p.current_value,
+                kind: gate.kind,
+                module_tokens: gate.section.iter().map(|t| t.to_lowercase()).collect(),
+            });
+        }
+        for p in plugin.parameters.iter().filter(|_| declared.is_empty()) {
             if let Some(kind) = gate_kind(&p.name) {
                 gates.push(GateParam {
                     param_index: p.index,
@@ -362,12 +665,6 @@ fn ensure_prerequisites(
     actions
 }
 
-#[derive(Debug, Clone, Copy)]
-enum GateKind {
-    Bypass,
-    Enable,
-}
-
 #[derive(Debug, Clone)]
 struct GateParam {
     param_index: i32,
@@ -382,7 +679,12 @@ fn gate_kind(name: &str) -> Option<GateKind> {
     if n.contains("bypass") {
         return Some(GateKind::Bypass);
     }
-    if n.contains("enable") || n.contains("enabled") || n.ends_with("on") || n.contains("active") {
+    // "Chorus On", but not "Note Division" / "Compression".
+    let on_word = name
+        .split(|c: char| !c.is_alphanumeric())
+        .rfind(|w| !w.is_empty())
+        .is_some_and(|w| w.eq_ignore_ascii_case("on"));
+    if n.contains("enable") || n.contains("enabled") || on_word || n.contains("active") {
         return Some(GateKind::Enable);
     }
     None
@@ -441,12 +743,19 @@ fn build_summary(actions: &[ParameterAction], requires_resnapshot: bool) -> Stri
     let mut set_count = 0usize;
     let mut enable_count = 0usize;
     let mut load_count = 0usize;
+    let mut move_count = 0usize;
+    let mut path_count = 0usize;
 
     for a in actions {
         match a {
-            ParameterAction::SetParameter { .. } => set_count += 1,
+            ParameterAction::SetParameter { .. }
+            | ParameterAction::SetNamedConfig { .. }
+            | ParameterAction::SetTrackMix { .. } => set_count += 1,
             ParameterAction::EnablePlugin { .. } => enable_count += 1,
             ParameterAction::LoadPlugin { .. } => load_count += 1,
+            ParameterAction::MovePlugin { .. } => move_count += 1,
+            ParameterAction::CreateContainer { plugin_indices, .. } => path_count += plugin_indices.len(),
+            ParameterAction::CreateSendTrack { .. } => path_count += 1,
         }
     }
 
@@ -460,6 +769,12 @@ fn build_summary(actions: &[ParameterAction], requires_resnapshot: bool) -> Stri
     if set_count > 0 {
         parts.push(format!("set {} parameter(s)", set_count));
     }
+    if move_count > 0 {
+        parts.push(format!("move {} plugin(s)", move_count));
+    }
+    if path_count > 0 {
+        parts.push(format!("route {} parallel path(s)", path_count));
+    }
     if parts.is_empty() {
         parts.push("no actions".to_string());
     }
@@ -520,7 +835,8 @@ fn plan_actions(mut actions: Vec<ParameterAction>, warnings: &mut Vec<String>) -
     }
 
     // Deterministic ordering:
-    // - Load -> Enable -> Set
+    // - Load -> Enable -> Set -> Move (moves keep their sequence; each is relative to the last)
+    //   -> Routing (send tracks, then the container, which renumbers the chain)
     // - Within Set: "gate" params (enable/bypass) first
     let mut indexed: Vec<( (i32, i32, i32, usize), ParameterAction)> = filtered
         .into_iter()
@@ -529,13 +845,23 @@ fn plan_actions(mut actions: Vec<ParameterAction>, warnings: &mut Vec<String>) -
             let type_rank = match &a {
                 ParameterAction::LoadPlugin { .. } => 0,
                 ParameterAction::EnablePlugin { .. } => 1,
-                ParameterAction::SetParameter { .. } => 2,
+                ParameterAction::SetParameter { .. }
+                | ParameterAction::SetNamedConfig { .. }
+                | ParameterAction::SetTrackMix { .. } => 2,
+                ParameterAction::MovePlugin { .. } => 3,
+                ParameterAction::CreateSendTrack { .. } => 4,
+                ParameterAction::CreateContainer { .. } => 5,
             };
 
             let plugin_rank: i32 = match &a {
-                ParameterAction::LoadPlugin { .. } => -1,
+                ParameterAction::LoadPlugin { .. }
+                | ParameterAction::MovePlugin { .. }
+                | ParameterAction::CreateContainer { .. }
+                | ParameterAction::CreateSendTrack { .. }
+                | ParameterAction::SetTrackMix { .. } => -1,
                 ParameterAction::EnablePlugin { plugin_index, .. } => *plugin_index,
                 ParameterAction::SetParameter { plugin_index, .. } => *plugin_index,
+                ParameterAction::SetNamedConfig { plugin_index, .. } => *plugin_index,
             };
 
             let set_rank: i32 = match &a {
@@ -564,6 +890,7 @@ fn map_effect_group(
     track: i32,
     plugin: &ReaperPlugin,
     effect: &EffectParameters,
+    ctx: &MapContext,
     actions: &mut Vec<ParameterAction>,
     warnings: &mut Vec<String>,
 ) {
@@ -572,6 +899,7 @@ fn map_effect_group(
         plugin,
         &effect.parameters,
         &format!("effect:{}", effect.effect_type),
+        ctx,
         actions,
         warnings,
     );
@@ -582,11 +910,15 @@ fn map_param_group(
     plugin: &ReaperPlugin,
     params: &HashMap<String, f64>,
     group: &str,
+    ctx: &MapContext,
     actions: &mut Vec<ParameterAction>,
     warnings: &mut Vec<String>,
 ) {
     for (key, value) in params {
-        let maybe_param = pick_best_param(plugin, key);
+        // "threshold_db": -42 is a physical target, not a normalized value.
+        let unit_target = split_unit_key(key);
+        let lookup_key = unit_target.map(|(base, _, _)| base).unwrap_or(key);
+        let maybe_param = pick_best_param(ctx.profiles, plugin, lookup_key);
         let Some(param) = maybe_param else {
             warnings.push(format!(
                 "Unmapped {} param '{}' for plugin '{}'",
@@ -595,100 +927,402 @@ fn map_param_group(
             continue;
         };
 
+        let (value, reason) = match unit_target {
+            None => (*value, format!("{} :: {} -> {}", group, key, param.name)),
+            Some((_, unit, scale)) => {
+                let target = DisplayValue::new(value * scale, unit);
+                match ctx.curves.normalized(&plugin.name, param.index, &target) {
+                    Some(Ok(normalized)) => (
+                        normalized,
+                        format!("{} :: {} -> {} = {}", group, key, param.name, target),
+                    ),
+                    Some(Err(e)) => {
+                        warnings.push(format!("{} param '{}' on '{}': {}", group, key, plugin.name, e));
+                        continue;
+                    }
+                    None => {
+                        warnings.push(format!(
+                            "{} param '{}' needs a learned curve for '{}' on '{}'; skipped",
+                            group, key, param.name, plugin.name
+                        ));
+                        continue;
+                    }
+                }
+            }
+        };
+
         actions.push(ParameterAction::SetParameter {
             track,
             plugin_index: plugin.index,
             param_index: param.index,
             param_name: param.name.clone(),
-            value: *value,
-            reason: format!("{} :: {} -> {}", group, key, param.name),
+            value,
+            reason,
         });
     }
 }
 
-fn map_eq_reaeq(
+/// Turn `delay.time_note` into something the plugin understands: its own sync mode when it
+/// has one (sync toggle + a division parameter with a numeric beats curve), otherwise a
+/// `time_ms` target computed from the project tempo. Returns the remaining delay params.
+fn resolve_delay_note(
     track: i32,
     plugin: &ReaperPlugin,
-    eq: &HashMap<String, f64>,
-    max_points: usize,
+    delay: &HashMap<String, f64>,
+    ctx: &MapContext,
     actions: &mut Vec<ParameterAction>,
     warnings: &mut Vec<String>,
-) {
-    // Pick strongest EQ points by |dB|
-    let mut points: Vec<(f64, f64)> = eq
+) -> HashMap<String, f64> {
+    let mut rest = delay.clone();
+    let Some(fraction) = rest.remove(DELAY_NOTE_KEY) else {
+        return rest;
+    };
+    let Some(note) = NoteDivision::from_whole_fraction(fraction) else {
+        warnings.push(format!(
+            "delay :: {} {} is not a note division; skipped",
+            DELAY_NOTE_KEY, fraction
+        ));
+        return rest;
+    };
+
+    let sync_param = plugin.parameters.iter().find(|p| {
+        let n = normalize_token(&p.name);
+        n.contains("sync") || n.contains("tempo")
+    });
+    let division = plugin
+        .parameters
         .iter()
-        .filter_map(|(k, db)| parse_frequency_hz(k).map(|hz| (hz, *db)))
-        .collect();
+        .filter(|p| {
+            let n = normalize_token(&p.name);
+            ["division", "note", "musical", "beat"].iter().any(|k| n.contains(k))
+        })
+        .find_map(|p| {
+            let target = DisplayValue::new(note.beats(), DisplayUnit::Unitless);
+            curve_or(&ctx.curves, plugin, p.index, target).map(|value| (p, value))
+        });
+
+    if let (Some(sync), Some((division, value))) = (sync_param, division) {
+        actions.push(ParameterAction::SetParameter {
+            track,
+            plugin_index: plugin.index,
+            param_index: sync.index,
+            param_name: sync.name.clone(),
+            value: 1.0,
+            reason: format!("delay :: enable tempo sync for {}", note.label()),
+        });
+        actions.push(ParameterAction::SetParameter {
+            track,
+            plugin_index: plugin.index,
+            param_index: division.index,
+            param_name: division.name.clone(),
+            value,
+            reason: format!("delay :: {} = {} beats", note.label(), note.beats()),
+        });
+        return rest;
+    }
 
-    points.sort_by(|a, b| b.1.abs().partial_cmp(&a.1.abs()).unwrap_or(std::cmp::Ordering::Equal));
-    points.truncate(max_points);
+    match ctx.tempo_bpm {
+        Some(bpm) => {
+            rest.entry("time_ms".to_string())
+                .or_insert_with(|| note.to_ms(bpm));
+        }
+        None => warnings.push(format!(
+            "delay :: {} needs the project tempo or a sync mode on '{}'; skipped",
+            note.label(),
+            plugin.name
+        )),
+    }
+    rest
+}
+
+/// Parameters of one EQ band, grouped by the layout's slot words.
+#[derive(Default)]
+struct EqBandParams<'a> {
+    freq: Option<&'a ReaperParameter>,
+    gain: Option<&'a ReaperParameter>,
+    width: Option<&'a ReaperParameter>,
+    filter_type: Option<&'a ReaperParameter>,
+    slope: Option<&'a ReaperParameter>,
+    enable: Option<&'a ReaperParameter>,
+}
+
+/// Whether a band can realize the requested filter. `None` requests (plain `eq` points)
+/// fit any boost/cut band.
+fn eq_band_fits(layout: &EqBandLayout, number: i32, params: &EqBandParams, request: &EqBand) -> bool {
+    if params.freq.is_none() || (request.filter.is_none_or(|f| f.has_gain()) && params.gain.is_none()) {
+        return false;
+    }
+    if let Some(fixed) = layout.fixed_type(number) {
+        return request.filter.map_or(fixed.has_gain(), |f| f == fixed);
+    }
+    if params.filter_type.is_some() && !layout.types.is_empty() {
+        return request.filter.is_none_or(|f| layout.types.contains_key(&f));
+    }
+    request.filter.is_none_or(|f| f == EqFilterType::Peak)
+}
 
-    if points.is_empty() {
+fn map_eq_bands(
+    track: i32,
+    plugin: &ReaperPlugin,
+    layout: &EqBandLayout,
+    bands: Vec<EqBand>,
+    ctx: &MapContext,
+    actions: &mut Vec<ParameterAction>,
+    warnings: &mut Vec<String>,
+) {
+    if bands.is_empty() {
         warnings.push("EQ map: no parsable frequency keys found; skipped".to_string());
         return;
     }
+    let requests = strongest_bands(bands, ctx.max_eq_points);
 
-    // Gather bands from param names: "Band N Freq" and "Band N Gain"
-    let mut band_freq_param: HashMap<i32, &crate::parameter_ai::ReaperParameter> = HashMap::new();
-    let mut band_gain_param: HashMap<i32, &crate::parameter_ai::ReaperParameter> = HashMap::new();
-
+    // Group the plugin's band params per the layout: "Band N Freq", "Band N Q", ...
+    let mut band_params: HashMap<i32, EqBandParams> = HashMap::new();
     for p in &plugin.parameters {
-        if let Some(band) = parse_reaeq_band_number(&p.name) {
-            let name_norm = normalize_token(&p.name);
-            if name_norm.contains("freq") {
-                band_freq_param.insert(band, p);
-            } else if name_norm.contains("gain") {
-                band_gain_param.insert(band, p);
-            }
-        }
+        let Some((number, slot)) = layout.band_slot(&p.name) else { continue };
+        let entry = band_params.entry(number).or_default();
+        let field = match slot {
+            EqBandSlot::Freq => &mut entry.freq,
+            EqBandSlot::Gain => &mut entry.gain,
+            EqBandSlot::Width => &mut entry.width,
+            EqBandSlot::Type => &mut entry.filter_type,
+            EqBandSlot::Slope => &mut entry.slope,
+            EqBandSlot::Enable => &mut entry.enable,
+        };
+        field.get_or_insert(p);
     }
+    let mut numbers: Vec<i32> = band_params
+        .iter()
+        .filter(|(_, params)| params.freq.is_some())
+        .map(|(number, _)| *number)
+        .collect();
+    numbers.sort();
 
-    if band_freq_param.is_empty() || band_gain_param.is_empty() {
+    if numbers.is_empty() {
         warnings.push(format!(
-            "EQ map: '{}' does not look like ReaEQ band params; skipped",
+            "EQ map: '{}' has no band params matching its band layout; skipped",
             plugin.name
         ));
         return;
     }
 
-    // Assign requested points to increasing band numbers (simple deterministic)
-    let mut bands: Vec<i32> = band_freq_param.keys().copied().collect();
-    bands.sort();
+    // Requests arrive sorted by frequency. Each takes the first free band that can realize it,
+    // preferring bands above the last one used so band order follows frequency order.
+    let mut used: HashSet<i32> = HashSet::new();
+    let mut last_band = i32::MIN;
+    for request in requests {
+        let fits = |n: &i32| !used.contains(n) && eq_band_fits(layout, *n, &band_params[n], &request);
+        let band = numbers
+            .iter()
+            .copied()
+            .find(|n| *n > last_band && fits(n))
+            .or_else(|| numbers.iter().copied().find(|n| fits(n)));
+        let Some(band) = band else {
+            warnings.push(format!(
+                "EQ map: no free band on '{}' can do {}; skipped",
+                plugin.name,
+                request.describe()
+            ));
+            continue;
+        };
+        used.insert(band);
+        last_band = band;
+        let params = &band_params[&band];
+        let mut set = |param: &ReaperParameter, value: f64, reason: String| {
+            actions.push(ParameterAction::SetParameter {
+                track,
+                plugin_index: plugin.index,
+                param_index: param.index,
+                param_name: param.name.clone(),
+                value,
+                reason,
+            });
+        };
 
-    for ((hz, db), band) in points.into_iter().zip(bands.into_iter()) {
-        let Some(freq_param) = band_freq_param.get(&band) else { continue };
-        let Some(gain_param) = band_gain_param.get(&band) else { continue };
+        if let Some(enable) = params.enable {
+            set(enable, 1.0, format!("eq :: enable band {}", band));
+        }
 
-        let freq_norm = hz_to_normalized_log(hz);
-        let gain_norm = db_to_normalized(db, 24.0);
+        // Settable types: plain points become peaks; fixed-type bands keep their shape.
+        let filter = match (params.filter_type, layout.fixed_type(band)) {
+            (_, Some(fixed)) => fixed,
+            (Some(type_param), None) if !layout.types.is_empty() => {
+                let filter = request.filter.unwrap_or(EqFilterType::Peak);
+                match layout.types.get(&filter) {
+                    Some(value) => {
+                        set(type_param, *value, format!("eq :: set band {} type to {}", band, filter.label()))
+                    }
+                    None => warnings.push(format!(
+                        "EQ map: '{}' band {} cannot be set to {}",
+                        plugin.name,
+                        band,
+                        filter.label()
+                    )),
+                }
+                filter
+            }
+            _ => request.filter.unwrap_or(EqFilterType::Peak),
+        };
 
-        actions.push(ParameterAction::SetParameter {
-            track,
-            plugin_index: plugin.index,
-            param_index: freq_param.index,
-            param_name: freq_param.name.clone(),
-            value: freq_norm,
-            reason: format!("eq :: set band {} freq to {:.0} Hz", band, hz),
-        });
+        // Learned or declared curves when available; otherwise assume a 20 Hz..20 kHz log
+        // frequency knob, the layout's symmetric gain range and its log width range.
+        let freq_param = params.freq.expect("bands without a freq param are filtered out");
+        let hz = request.freq_hz;
+        let freq_norm = curve_or(&ctx.curves, plugin, freq_param.index, DisplayValue::new(hz, DisplayUnit::Hertz))
+            .unwrap_or_else(|| hz_to_normalized_log(hz));
+        set(freq_param, freq_norm, format!("eq :: set band {} freq to {:.0} Hz", band, hz));
+
+        if let (true, Some(gain_param)) = (filter.has_gain(), params.gain) {
+            let db = request.gain_db;
+            let gain_norm = curve_or(&ctx.curves, plugin, gain_param.index, DisplayValue::new(db, DisplayUnit::Decibel))
+                .unwrap_or_else(|| db_to_normalized(db, layout.gain_range_db));
+            set(gain_param, gain_norm, format!("eq :: set band {} gain to {:+.1} dB", band, db));
+        }
 
+        if let Some(q) = request.q {
+            match params.width {
+                Some(width_param) => {
+                    let (width, label) = match layout.q_mode {
+                        EqWidthMode::Q => (q, format!("Q {:.2}", q)),
+                        EqWidthMode::Octaves => (q_to_octaves(q), format!("{:.2} oct", q_to_octaves(q))),
+                    };
+                    let target = DisplayValue::new(width, DisplayUnit::Unitless);
+                    let width_norm = curve_or(&ctx.curves, plugin, width_param.index, target)
+                        .unwrap_or_else(|| log_normalized(width, layout.q_range));
+                    set(width_param, width_norm, format!("eq :: set band {} width to {}", band, label));
+                }
+                None => warnings.push(format!(
+                    "EQ map: '{}' band {} has no width param; Q {:.2} skipped",
+                    plugin.name, band, q
+                )),
+            }
+        }
+
+        if let Some(slope) = request.slope_db_oct.filter(|_| filter.has_slope()) {
+            match (params.slope, layout.slope_value(slope)) {
+                (Some(slope_param), Some(value)) => {
+                    set(slope_param, value, format!("eq :: set band {} slope to {:.0} dB/oct", band, slope))
+                }
+                _ => warnings.push(format!(
+                    "EQ map: '{}' band {} cannot do {:.0} dB/oct; slope skipped",
+                    plugin.name, band, slope
+                )),
+            }
+        }
+    }
+}
+
+fn has_cab_section(plugin: &ReaperPlugin) -> bool {
+    plugin.parameters.iter().any(|p| {
+        let n = normalize_token(&p.name);
+        ["cab", "speaker", "mic", "impulse"].iter().any(|k| n.contains(k))
+    })
+}
+
+fn map_cab(
+    track: i32,
+    plugin: &ReaperPlugin,
+    cab: &CabParameters,
+    ctx: &MapContext,
+    actions: &mut Vec<ParameterAction>,
+    warnings: &mut Vec<String>,
+) {
+    let profile = ctx.profiles.profile_for(&plugin.name);
+    let mut set = |param: &ReaperParameter, value: f64, reason: String| {
         actions.push(ParameterAction::SetParameter {
             track,
             plugin_index: plugin.index,
-            param_index: gain_param.index,
-            param_name: gain_param.name.clone(),
-            value: gain_norm,
-            reason: format!("eq :: set band {} gain to {:+.1} dB", band, db),
+            param_index: param.index,
+            param_name: param.name.clone(),
+            value,
+            reason,
         });
+    };
+
+    // Speaker and mic are stepped parameters: pick the declared label that fits best.
+    for (key, wanted) in [("speaker", &cab.speaker), ("mic", &cab.mic)] {
+        let Some(wanted) = wanted else { continue };
+        let Some(param) = pick_best_param(ctx.profiles, plugin, key) else {
+            warnings.push(format!("Unmapped cab param '{}' for plugin '{}'", key, plugin.name));
+            continue;
+        };
+        let Some(labels) = profile.and_then(|p| p.options_for(&param.name)) else {
+            warnings.push(format!(
+                "cab :: {} '{}' needs declared options for '{}' on '{}'; skipped",
+                key, wanted, param.name, plugin.name
+            ));
+            continue;
+        };
+        let Some(choice) = match_option(labels, wanted) else {
+            warnings.push(format!(
+                "cab :: no '{}' option on '{}' fits {} '{}'",
+                param.name, plugin.name, key, wanted
+            ));
+            continue;
+        };
+        let value = if labels.len() > 1 {
+            choice as f64 / (labels.len() - 1) as f64
+        } else {
+            0.0
+        };
+        set(
+            param,
+            value,
+            format!("cab :: {} '{}' -> {} = {}", key, wanted, param.name, labels[choice]),
+        );
+    }
+
+    if let Some(position) = cab.mic_position {
+        match pick_best_param(ctx.profiles, plugin, "micposition") {
+            Some(param) => set(
+                param,
+                position.clamp(0.0, 1.0),
+                format!("cab :: mic position {:.2} -> {}", position, param.name),
+            ),
+            None => warnings.push(format!(
+                "Unmapped cab param 'mic_position' for plugin '{}'",
+                plugin.name
+            )),
+        }
+    }
+
+    for (key, hz) in [("lowcut", cab.low_cut_hz), ("highcut", cab.high_cut_hz)] {
+        let Some(hz) = hz else { continue };
+        let Some(param) = pick_best_param(ctx.profiles, plugin, key) else {
+            warnings.push(format!("Unmapped cab param '{}' for plugin '{}'", key, plugin.name));
+            continue;
+        };
+        let target = DisplayValue::new(hz, DisplayUnit::Hertz);
+        let value = curve_or(&ctx.curves, plugin, param.index, target)
+            .unwrap_or_else(|| hz_to_normalized_log(hz));
+        set(param, value, format!("cab :: {} {:.0} Hz -> {}", key, hz, param.name));
+    }
+
+    if let Some(path) = &cab.ir_path {
+        match profile.and_then(|p| p.ir_file.as_ref()) {
+            Some(key) => actions.push(ParameterAction::SetNamedConfig {
+                track,
+                plugin_index: plugin.index,
+                key: key.clone(),
+                value: path.clone(),
+                reason: format!("cab :: load IR '{}'", path),
+            }),
+            None => warnings.push(format!(
+                "'{}' has no IR file setting in its profile; load '{}' manually",
+                plugin.name, path
+            )),
+        }
     }
 }
 
 fn pick_best_plugin<'a>(
     snapshot: &'a ReaperSnapshot,
-    keywords: &[Cow<'static, str>],
+    profiles: &PluginProfileRegistry,
+    role: &str,
 ) -> Option<&'a ReaperPlugin> {
     let mut best: Option<(&ReaperPlugin, i32)> = None;
     for p in &snapshot.plugins {
-        let score = score_text_against_keywords(&p.name, keywords);
+        let score = profiles.role_score(&p.name, role);
         if score <= 0 {
             continue;
         }
@@ -701,9 +1335,20 @@ fn pick_best_plugin<'a>(
     best.map(|(p, _)| p)
 }
 
-fn pick_best_param<'a>(plugin: &'a ReaperPlugin, key: &str) -> Option<&'a crate::parameter_ai::ReaperParameter> {
+fn pick_best_param<'a>(
+    profiles: &PluginProfileRegistry,
+    plugin: &'a ReaperPlugin,
+    key: &str,
+) -> Option<&'a crate::parameter_ai::ReaperParameter> {
+    // Names declared by the plugin's profile win over fuzzy matching.
+    if let Some(param) = profiles
+        .profile_for(&plugin.name)
+        .and_then(|profile| profile.param_for(plugin, key))
+    {
+        return Some(param);
+    }
     let key_norm = normalize_token(key);
-    let synonyms = synonyms_for_key(&key_norm);
+    let synonyms = profiles.synonyms(&key_norm);
     let mut best: Option<(&crate::parameter_ai::ReaperParameter, i32)> = None;
     for p in &plugin.parameters {
         let score = score_param_name(&p.name, &key_norm, &synonyms);
@@ -719,7 +1364,7 @@ fn pick_best_param<'a>(plugin: &'a ReaperPlugin, key: &str) -> Option<&'a crate:
     best.map(|(p, _)| p)
 }
 
-fn score_param_name(param_name: &str, key_norm: &str, synonyms: &[Cow<'static, str>]) -> i32 {
+fn score_param_name(param_name: &str, key_norm: &str, synonyms: &[String]) -> i32 {
     let p = normalize_token(param_name);
     if p == key_norm {
         return 100;
@@ -728,7 +1373,7 @@ fn score_param_name(param_name: &str, key_norm: &str, synonyms: &[Cow<'static, s
         return 60;
     }
     for (i, s) in synonyms.iter().enumerate() {
-        let s = s.as_ref();
+        let s = s.as_str();
         if p == s {
             return 90 - i as i32;
         }
@@ -739,120 +1384,8 @@ fn score_param_name(param_name: &str, key_norm: &str, synonyms: &[Cow<'static, s
     0
 }
 
-fn score_text_against_keywords(text: &str, keywords: &[Cow<'static, str>]) -> i32 {
-    let t = normalize_token(text);
-    let mut score = 0;
-    for (i, k) in keywords.iter().enumerate() {
-        if t.contains(k.as_ref()) {
-            score += 10 - i as i32;
-        }
-    }
-    score
-}
-
-fn kws(list: &[&'static str]) -> Vec<Cow<'static, str>> {
-    list.iter().map(|s| Cow::Borrowed(*s)).collect()
-}
-
-fn role_keywords_amp() -> Vec<Cow<'static, str>> {
-    kws(&["neuraldsp", "archetype", "amp", "sim", "amplifier", "dist", "gain"])
-}
-
-fn role_keywords_eq() -> Vec<Cow<'static, str>> {
-    kws(&["reaeq", "proq", "eq", "equalizer"])
-}
-
-fn role_keywords_gate() -> Vec<Cow<'static, str>> {
-    kws(&["reagate", "gate", "noisegate", "noise"])
-}
-
-fn role_keywords_reverb() -> Vec<Cow<'static, str>> {
-    kws(&["reaverbate", "reaverb", "reverb", "room", "hall"])
-}
-
-fn role_keywords_delay() -> Vec<Cow<'static, str>> {
-    kws(&["readelay", "delay", "echo"])
-}
-
-fn role_keywords_for_effect(effect_type_norm: &str) -> Vec<Cow<'static, str>> {
-    match effect_type_norm {
-        "noisegate" | "gate" => role_keywords_gate(),
-        "overdrive" | "od" => kws(&["overdrive", "od", "screamer", "drive"]),
-        "distortion" | "dist" => kws(&["distortion", "dist", "fuzz"]),
-        "compressor" | "comp" => kws(&["compressor", "comp"]),
-        _ => vec![Cow::Owned(effect_type_norm.to_string())],
-    }
-}
-
-fn default_plugin_for_effect(effect_type_norm: &str) -> Option<&'static str> {
-    match effect_type_norm {
-        "noisegate" | "gate" => Some("ReaGate (Cockos)"),
-        _ => None,
-    }
-}
-
-fn synonyms_for_key(key_norm: &str) -> Vec<Cow<'static, str>> {
-    match key_norm {
-        "gain" => kws(&["gain", "drive", "input", "pregain", "preamp"]),
-        "drive" => kws(&["drive", "gain", "input"]),
-        "bass" | "low" => kws(&["bass", "low", "lf", "lows"]),
-        "mid" | "middle" => kws(&["mid", "middle", "mids", "mf"]),
-        "treble" | "high" => kws(&["treble", "high", "hf", "highs", "presence"]),
-        "presence" => kws(&["presence", "pres", "bright"]),
-        "master" | "output" | "level" | "volume" => kws(&["master", "output", "level", "volume"]),
-        "threshold" => kws(&["threshold", "thresh"]),
-        "attack" => kws(&["attack", "att"]),
-        "release" => kws(&["release", "rel"]),
-        "mix" => kws(&["mix", "wet", "drywet", "blend"]),
-        "time" => kws(&["time", "ms", "sec", "seconds"]),
-        "feedback" => kws(&["feedback", "fb"]),
-        _ => vec![Cow::Owned(key_norm.to_string())],
-    }
-}
-
-fn normalize_token(text: &str) -> String {
-    text.to_lowercase()
-        .chars()
-        .filter(|c| c.is_alphanumeric())
-        .collect()
-}
-
-fn contains_token(text: &str, token: &str) -> bool {
-    normalize_token(text).contains(&normalize_token(token))
-}
-
-fn parse_frequency_hz(text: &str) -> Option<f64> {
-    let s = text.trim().to_lowercase().replace(' ', "");
-    if let Some(khz_pos) = s.find("khz") {
-        let num = &s[..khz_pos];
-        let v: f64 = num.parse().ok()?;
-        return Some(v * 1000.0);
-    }
-    if let Some(hz_pos) = s.find("hz") {
-        let num = &s[..hz_pos];
-        let v: f64 = num.parse().ok()?;
-        return Some(v);
-    }
-    None
-}
-
-fn parse_reaeq_band_number(param_name: &str) -> Option<i32> {
-    let lower = param_name.to_lowercase();
-    let band_pos = lower.find("band")?;
-    let after = &lower[band_pos + 4..];
-    let after = after.trim_start();
-    let mut digits = String::new();
-    for c in after.chars() {
-        if c.is_ascii_digit() {
-            digits.push(c);
-        } else {
-            break;
-        }
-    }
-    if digits.is_empty() {
-        return None;
-    }
-    digits.parse().ok()
+fn curve_or(curves: &ParamCurves, plugin: &ReaperPlugin, param_index: i32, target: DisplayValue) -> Option<f64> {
+    curves.normalized(&plugin.name, param_index, &target)?.ok()
 }
 
 fn db_to_normalized(db: f64, max_abs_db: f64) -> f64 {
@@ -860,6 +1393,12 @@ fn db_to_normalized(db: f64, max_abs_db: f64) -> f64 {
     (clamped + max_abs_db) / (2.0 * max_abs_db)
 }
 
+/// Position of `value` on a log-scaled knob spanning `range`.
+fn log_normalized(value: f64, range: [f64; 2]) -> f64 {
+    let [min, max] = range;
+    ((value.clamp(min, max).ln() - min.ln()) / (max.ln() - min.ln())).clamp(0.0, 1.0)
+}
+
 fn hz_to_normalized_log(hz: f64) -> f64 {
     let hz = hz.clamp(20.0, 20_000.0);
     let min = 20.0_f64.ln();
@@ -908,6 +1447,9 @@ mod tests {
         let mut params = ToneParameters {
             amp: HashMap::new(),
             eq: HashMap::new(),
+            eq_bands: Vec::new(),
+            cab: Default::default(),
+            routing: Default::default(),
             effects: vec![],
             reverb: HashMap::new(),
             delay: HashMap::new(),
@@ -932,9 +1474,518 @@ mod tests {
     }
 
     #[test]
-    fn parses_frequency_strings() {
-        assert_eq!(parse_frequency_hz("800Hz").unwrap() as i32, 800);
-        assert_eq!(parse_frequency_hz("2kHz").unwrap() as i32, 2000);
-        assert!(parse_frequency_hz("abc").is_none());
+    fn chain_order_moves_follow_settings() {
+        let mut snapshot = fake_snapshot();
+        snapshot.plugins.insert(
+            0,
+            ReaperPlugin {
+                index: 0,
+                name: "VST: ReaDelay (Cockos)".to_string(),
+                enabled: true,
+                parameters: vec![],
+            },
+        );
+        snapshot.plugins[1].index = 1;
+        let mut params = ToneParameters {
+            amp: HashMap::from([("gain".to_string(), 0.8)]),
+            eq: HashMap::new(),
+            eq_bands: Vec::new(),
+            cab: Default::default(),
+            routing: Default::default(),
+            effects: vec![],
+            reverb: HashMap::new(),
+            delay: HashMap::new(),
+        };
+
+        let config = ChainMapperConfig {
+            allow_load_plugins: false,
+            ..Default::default()
+        };
+        let result = ChainMapper::new(config.clone())
+            .with_chain_order(ChainOrder::canonical())
+            .map(&params, &snapshot);
+        assert!(matches!(
+            result.actions.first(),
+            Some(ParameterAction::SetParameter { plugin_index: 1, param_index: 0, .. })
+        ));
+        assert!(matches!(
+            result.actions.last(),
+            Some(ParameterAction::MovePlugin { from_plugin_index: 1, to_plugin_index: 0, .. })
+        ));
+        assert!(result.requires_resnapshot);
+        assert!(result.summary.contains("move 1 plugin(s)"));
+
+        // Without an order the mapper never reorders.
+        params.amp.clear();
+        let result = ChainMapper::new(config).map(&params, &snapshot);
+        assert!(result.actions.is_empty());
+    }
+
+    #[test]
+    fn unit_targets_use_learned_curves() {
+        use crate::param_curve::{probe_points, ParamCurve};
+
+        let mut snapshot = fake_snapshot();
+        snapshot.plugins[0].parameters.push(ReaperParameter {
+            index: 2,
+            name: "Gate Threshold".to_string(),
+            current_value: 0.5,
+            display_value: "-30.0 dB".to_string(),
+            unit: "dB".to_string(),
+            format_hint: "decibel".to_string(),
+        });
+        let mut params = ToneParameters {
+            amp: HashMap::from([("threshold_db".to_string(), -42.0)]),
+            eq: HashMap::new(),
+            eq_bands: Vec::new(),
+            cab: Default::default(),
+            routing: Default::default(),
+            effects: vec![],
+            reverb: HashMap::new(),
+            delay: HashMap::new(),
+        };
+        let config = ChainMapperConfig {
+            allow_load_plugins: false,
+            ..Default::default()
+        };
+
+        // Without a curve the target is skipped rather than guessed.
+        let result = ChainMapper::new(config.clone()).map(&params, &snapshot);
+        assert!(result.actions.is_empty());
+        assert!(result.warnings.iter().any(|w| w.contains("learned curve")));
+
+        let samples: Vec<(f64, String)> = probe_points(9)
+            .into_iter()
+            .map(|n| (n, format!("{:.1} dB", -60.0 + 60.0 * n)))
+            .collect();
+        let mut curves = ParamCurves::new();
+        curves.insert(&snapshot.plugins[0].name, 2, ParamCurve::from_samples(&samples).unwrap());
+        params.amp.insert("gain".to_string(), 0.7);
+
+        let result = ChainMapper::new(config).with_curves(curves).map(&params, &snapshot);
+        let threshold = result.actions.iter().find_map(|a| match a {
+            ParameterAction::SetParameter { param_index: 2, value, .. } => Some(*value),
+            _ => None,
+        });
+        assert!((threshold.unwrap() - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn delay_note_uses_sync_mode_or_tempo() {
+        use crate::param_curve::{probe_points, ParamCurve};
+
+        let param = |index: i32, name: &str| ReaperParameter {
+            index,
+            name: name.to_string(),
+            current_value: 0.0,
+            display_value: String::new(),
+            unit: String::new(),
+            format_hint: "raw".to_string(),
+        };
+        let curve = |f: &dyn Fn(f64) -> String| {
+            let samples: Vec<(f64, String)> = probe_points(33).into_iter().map(|n| (n, f(n))).collect();
+            ParamCurve::from_samples(&samples).unwrap()
+        };
+        let mut snapshot = fake_snapshot();
+        snapshot.plugins = vec![ReaperPlugin {
+            index: 0,
+            name: "Delay".to_string(),
+            enabled: true,
+            parameters: vec![param(0, "Time"), param(1, "Tempo Sync"), param(2, "Note Division")],
+        }];
+        let params = ToneParameters {
+            amp: HashMap::new(),
+            eq: HashMap::new(),
+            eq_bands: Vec::new(),
+            cab: Default::default(),
+            routing: Default::default(),
+            effects: vec![],
+            reverb: HashMap::new(),
+            delay: HashMap::from([(DELAY_NOTE_KEY.to_string(), 0.1875)]),
+        };
+        let config = ChainMapperConfig {
+            allow_load_plugins: false,
+            ..Default::default()
+        };
+        let set_value = |result: &ChainMappingResult, index: i32| {
+            result.actions.iter().find_map(|a| match a {
+                ParameterAction::SetParameter { param_index, value, .. } if *param_index == index => Some(*value),
+                _ => None,
+            })
+        };
+
+        // No tempo, no sync curve: nothing to do.
+        let result = ChainMapper::new(config.clone()).map(&params, &snapshot);
+        assert!(result.actions.is_empty());
+
+        // Tempo known: 1/8. at 120 BPM = 375 ms on a linear 0..2000 ms knob.
+        let mut curves = ParamCurves::new();
+        curves.insert("Delay", 0, curve(&|n| format!("{:.1} ms", 2000.0 * n)));
+        let result = ChainMapper::new(config.clone())
+            .with_curves(curves.clone())
+            .with_tempo(120.0)
+            .map(&params, &snapshot);
+        assert!((set_value(&result, 0).unwrap() - 0.1875).abs() < 1e-3);
+
+        // Sync mode with a 0..4 beats division knob wins over milliseconds.
+        curves.insert("Delay", 2, curve(&|n| format!("{:.3}", 4.0 * n)));
+        let result = ChainMapper::new(config)
+            .with_curves(curves)
+            .with_tempo(120.0)
+            .map(&params, &snapshot);
+        assert_eq!(set_value(&result, 1), Some(1.0));
+        assert!((set_value(&result, 2).unwrap() - 0.1875).abs() < 1e-3);
+        assert!(set_value(&result, 0).is_none());
+    }
+
+    #[test]
+    fn profiles_drive_roles_params_gates_and_eq() {
+        let param = |index: i32, name: &str, current_value: f64| ReaperParameter {
+            index,
+            name: name.to_string(),
+            current_value,
+            display_value: String::new(),
+            unit: String::new(),
+            format_hint: "raw".to_string(),
+        };
+        let mut snapshot = fake_snapshot();
+        snapshot.plugins.push(ReaperPlugin {
+            index: 1,
+            name: "VST3: Helix Native (Line 6)".to_string(),
+            enabled: true,
+            parameters: vec![param(0, "Amp Power", 0.0), param(1, "Amp Drv", 0.5), param(2, "Amp Out", 0.5)],
+        });
+        snapshot.plugins.push(ReaperPlugin {
+            index: 2,
+            name: "VST3: Pro-Q 3 (FabFilter)".to_string(),
+            enabled: true,
+            parameters: vec![param(0, "Band 1 Frequency", 0.5), param(1, "Band 1 Gain", 0.5)],
+        });
+        let params = ToneParameters {
+            amp: HashMap::from([("gain".to_string(), 0.8), ("master_db".to_string(), -6.0)]),
+            eq: HashMap::from([("800Hz".to_string(), -4.0)]),
+            eq_bands: Vec::new(),
+            cab: Default::default(),
+            routing: Default::default(),
+            effects: vec![],
+            reverb: HashMap::new(),
+            delay: HashMap::new(),
+        };
+
+        let json = r#"{"profiles": [
+            {"name": "Helix Native", "matches": ["helixnative"], "roles": ["amp"],
+             "params": {"gain": ["Amp Drv"], "master": ["Amp Out"]},
+             "curves": {"Amp Out": {"min": "-60 dB", "max": "0 dB"}},
+             "gates": [{"param": "Amp Power", "kind": "enable", "section": ["amp"]}]},
+            {"name": "Pro-Q 3", "matches": ["proq3"], "roles": ["eq"],
+             "eq_bands": {"freq": "frequency", "gain_range_db": 30.0}}
+        ]}"#;
+        let dir = std::env::temp_dir().join(format!("toneforge-mapper-profiles-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("team.json"), json).unwrap();
+        let mut profiles = PluginProfileRegistry::builtin();
+        let load_warnings = profiles.load_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(load_warnings.is_empty(), "{:?}", load_warnings);
+
+        let result = ChainMapper::new(ChainMapperConfig {
+            allow_load_plugins: false,
+            ..Default::default()
+        })
+        .with_profiles(profiles)
+        .map(&params, &snapshot);
+        let set = |plugin: i32, index: i32| {
+            result.actions.iter().find_map(|a| match a {
+                ParameterAction::SetParameter { plugin_index, param_index, value, .. }
+                    if *plugin_index == plugin && *param_index == index =>
+                {
+                    Some(*value)
+                }
+                _ => None,
+            })
+        };
+
+        // Helix beats the keyword-matched Archetype for the amp role.
+        assert_eq!(set(1, 1), Some(0.8));
+        assert!((set(1, 2).unwrap() - 0.9).abs() < 1e-6);
+        assert_eq!(set(1, 0), Some(1.0), "declared gate is switched on");
+        assert!(set(0, 0).is_none());
+        // Pro-Q bands via the declared layout and its 30 dB gain range.
+        assert!((set(2, 1).unwrap() - (26.0 / 60.0)).abs() < 1e-6);
+        assert!(set(2, 0).is_some());
+    }
+
+    #[test]
+    fn eq_bands_follow_frequency_order_types_and_slopes() {
+        let param = |index: i32, name: &str| ReaperParameter {
+            index,
+            name: name.to_string(),
+            current_value: 0.5,
+            display_value: String::new(),
+            unit: String::new(),
+            format_hint: "raw".to_string(),
+        };
+        let reaeq = ReaperPlugin {
+            index: 0,
+            name: "VST: ReaEQ (Cockos)".to_string(),
+            enabled: true,
+            parameters: (1..=4)
+                .flat_map(|band| {
+                    let base = (band - 1) * 3;
+                    [
+                        param(base, &format!("Band {} Freq", band)),
+                        param(base + 1, &format!("Band {} Gain", band)),
+                        param(base + 2, &format!("Band {} Q", band)),
+                    ]
+                })
+                .collect(),
+        };
+        let pro_q = ReaperPlugin {
+            index: 0,
+            name: "VST3: Pro-Q 3 (FabFilter)".to_string(),
+            enabled: true,
+            parameters: (1..=3)
+                .flat_map(|band| {
+                    let base = (band - 1) * 6;
+                    ["Used", "Frequency", "Gain", "Q", "Shape", "Slope"]
+                        .into_iter()
+                        .enumerate()
+                        .map(move |(i, slot)| param(base + i as i32, &format!("Band {} {}", band, slot)))
+                })
+                .collect(),
+        };
+        let params = ToneParameters {
+            amp: HashMap::new(),
+            eq: HashMap::from([("2kHz".to_string(), 1.5)]),
+            eq_bands: serde_json::from_str(
+                r#"[{"freq_hz": 8000, "gain_db": 2, "type": "high_shelf"},
+                    {"freq_hz": 80, "type": "hpf", "slope_db_oct": 24},
+                    {"freq_hz": 400, "gain_db": -3, "q": 2.0, "type": "peak"}]"#,
+            )
+            .unwrap(),
+            cab: Default::default(),
+            routing: Default::default(),
+            effects: vec![],
+            reverb: HashMap::new(),
+            delay: HashMap::new(),
+        };
+        let map = |plugin: &ReaperPlugin| {
+            let snapshot = ReaperSnapshot {
+                track_index: 0,
+                track_name: "Guitar".to_string(),
+                plugins: vec![plugin.clone()],
+            };
+            ChainMapper::new(ChainMapperConfig::default()).map(&params, &snapshot)
+        };
+        let reasons = |result: &ChainMappingResult| -> Vec<String> {
+            result
+                .actions
+                .iter()
+                .filter_map(|a| match a {
+                    ParameterAction::SetParameter { reason, .. } => Some(reason.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        // ReaEQ's fixed band types: the HPF has nowhere to go, the rest land by frequency.
+        let result = map(&reaeq);
+        let reasons_reaeq = reasons(&result);
+        assert!(reasons_reaeq.contains(&"eq :: set band 2 freq to 400 Hz".to_string()));
+        assert!(reasons_reaeq.contains(&"eq :: set band 2 width to Q 2.00".to_string()));
+        assert!(reasons_reaeq.contains(&"eq :: set band 3 freq to 2000 Hz".to_string()));
+        assert!(reasons_reaeq.contains(&"eq :: set band 4 freq to 8000 Hz".to_string()));
+        assert!(result.warnings.iter().any(|w| w.contains("80 Hz high-pass")), "{:?}", result.warnings);
+
+        // Pro-Q sets shape and slope per band; with three bands the 8 kHz shelf is left over.
+        let result = map(&pro_q);
+        let reasons_pro_q = reasons(&result);
+        assert!(reasons_pro_q.contains(&"eq :: enable band 1".to_string()));
+        assert!(reasons_pro_q.contains(&"eq :: set band 1 type to high-pass".to_string()));
+        assert!(reasons_pro_q.contains(&"eq :: set band 1 slope to 24 dB/oct".to_string()));
+        assert!(!reasons_pro_q.iter().any(|r| r.starts_with("eq :: set band 1 gain")));
+        assert!(reasons_pro_q.contains(&"eq :: set band 2 freq to 400 Hz".to_string()));
+        assert!(reasons_pro_q.contains(&"eq :: set band 3 type to peak".to_string()));
+        assert!(reasons_pro_q.contains(&"eq :: set band 3 freq to 2000 Hz".to_string()));
+        assert!(result.warnings.iter().any(|w| w.contains("no free band")), "{:?}", result.warnings);
+        let slope = result.actions.iter().find_map(|a| match a {
+            ParameterAction::SetParameter { param_index: 5, value, .. } => Some(*value),
+            _ => None,
+        });
+        assert_eq!(slope, Some(0.375));
+    }
+
+    #[test]
+    fn cab_maps_to_amp_cab_section_or_ir_loader() {
+        let param = |index: i32, name: &str| ReaperParameter {
+            index,
+            name: name.to_string(),
+            current_value: 0.0,
+            display_value: String::new(),
+            unit: String::new(),
+            format_hint: "raw".to_string(),
+        };
+        let amp = ReaperPlugin {
+            index: 0,
+            name: "VST3: Archetype Plini (Neural DSP)".to_string(),
+            enabled: true,
+            parameters: vec![
+                param(0, "Gain"),
+                param(1, "Cab Type"),
+                param(2, "Cab Mic"),
+                param(3, "Mic Position"),
+                param(4, "Cab Low Cut"),
+            ],
+        };
+        let ir_loader = ReaperPlugin {
+            index: 1,
+            name: "JS: IR Loader".to_string(),
+            enabled: true,
+            parameters: vec![param(0, "Low Cut"), param(1, "High Cut")],
+        };
+        let mut cab = CabParameters::from_description("Marshall 4x12 Greenback with SM57 off axis");
+        cab.low_cut_hz = Some(80.0);
+        cab.ir_path = Some("/irs/greenback_57.wav".to_string());
+        let params = ToneParameters {
+            amp: HashMap::new(),
+            eq: HashMap::new(),
+            eq_bands: Vec::new(),
+            cab,
+            routing: Default::default(),
+            effects: vec![],
+            reverb: HashMap::new(),
+            delay: HashMap::new(),
+        };
+
+        let json = r#"{"profiles": [
+            {"name": "Archetype Plini", "matches": ["archetypeplini"], "roles": ["amp"],
+             "params": {"speaker": ["Cab Type"], "mic": ["Cab Mic"]},
+             "options": {"Cab Type": ["1x12 Open", "4x12 V30", "4x12 Greenback"],
+                         "Cab Mic": ["Dynamic 57", "Ribbon 121", "Condenser 87"]}},
+            {"name": "IR Loader", "matches": ["irloader"], "roles": ["cab"], "ir_file": "FILE0"}
+        ]}"#;
+        let dir = std::env::temp_dir().join(format!("toneforge-cab-profiles-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("cab.json"), json).unwrap();
+        let mut profiles = PluginProfileRegistry::builtin();
+        let load_warnings = profiles.load_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(load_warnings.is_empty(), "{:?}", load_warnings);
+
+        let map = |plugins: Vec<ReaperPlugin>| {
+            let snapshot = ReaperSnapshot {
+                track_index: 0,
+                track_name: "Guitar".to_string(),
+                plugins,
+            };
+            ChainMapper::new(ChainMapperConfig::default())
+                .with_profiles(profiles.clone())
+                .map(&params, &snapshot)
+        };
+        let set = |result: &ChainMappingResult, plugin: i32, index: i32| {
+            result.actions.iter().find_map(|a| match a {
+                ParameterAction::SetParameter { plugin_index, param_index, value, .. }
+                    if *plugin_index == plugin && *param_index == index =>
+                {
+                    Some(*value)
+                }
+                _ => None,
+            })
+        };
+
+        // Amp sim only: its cab section gets speaker, mic, position and low cut.
+        let result = map(vec![amp.clone()]);
+        assert_eq!(set(&result, 0, 1), Some(1.0));
+        assert_eq!(set(&result, 0, 2), Some(0.0));
+        assert_eq!(set(&result, 0, 3), Some(1.0));
+        assert!((set(&result, 0, 4).unwrap() - hz_to_normalized_log(80.0)).abs() < 1e-9);
+        assert!(result.warnings.iter().any(|w| w.contains("load '/irs/greenback_57.wav' manually")));
+
+        // An IR loader takes over the cab and gets the IR file through its named config.
+        let result = map(vec![amp, ir_loader]);
+        assert!(set(&result, 0, 1).is_none());
+        assert!(set(&result, 1, 0).is_some());
+        assert!(result.actions.iter().any(|a| matches!(
+            a,
+            ParameterAction::SetNamedConfig { plugin_index: 1, key, value, .. }
+                if key == "FILE0" && value == "/irs/greenback_57.wav"
+        )));
+        assert!(result.warnings.iter().any(|w| w.contains("Unmapped cab param 'speaker'")));
+    }
+
+    #[test]
+    fn routing_builds_parallel_container_or_send_tracks() {
+        let plugin = |index: i32, name: &str| ReaperPlugin {
+            index,
+            name: name.to_string(),
+            enabled: true,
+            parameters: vec![],
+        };
+        let map = |routing: &str, plugins: Vec<ReaperPlugin>| {
+            let params = ToneParameters {
+                amp: HashMap::new(),
+                eq: HashMap::new(),
+                eq_bands: Vec::new(),
+                cab: Default::default(),
+                effects: vec![],
+                reverb: HashMap::new(),
+                delay: HashMap::new(),
+                routing: serde_json::from_str(routing).unwrap(),
+            };
+            let snapshot = ReaperSnapshot {
+                track_index: 2,
+                track_name: "Guitar".to_string(),
+                plugins,
+            };
+            ChainMapper::new(ChainMapperConfig::default()).map(&params, &snapshot)
+        };
+        let dual = r#"{"paths": [{"name": "A", "chain": ["amp"]}, {"name": "B", "chain": ["amp"]}]}"#;
+
+        // One amp on the track: a second instance is loaded first.
+        let result = map(dual, vec![plugin(0, "VST3: Archetype Plini (Neural DSP)")]);
+        assert!(result.requires_resnapshot);
+        assert!(matches!(
+            result.actions.as_slice(),
+            [ParameterAction::LoadPlugin { plugin_name, .. }] if plugin_name.contains("Archetype")
+        ));
+
+        let result = map(
+            dual,
+            vec![
+                plugin(0, "VST: ReaGate (Cockos)"),
+                plugin(1, "VST3: Archetype Plini (Neural DSP)"),
+                plugin(2, "VST3: Archetype Plini (Neural DSP)"),
+            ],
+        );
+        assert!(!result.requires_resnapshot);
+        assert!(matches!(
+            result.actions.as_slice(),
+            [ParameterAction::CreateContainer { plugin_indices, parallel: true, .. }] if plugin_indices == &[1, 2]
+        ));
+
+        // Wet/dry/wet: dry level on the track, one send track per wet side.
+        let result = map(
+            r#"{"kind": "sends", "dry_db": 0,
+                "paths": [{"name": "Wet L", "chain": ["delay"], "volume_db": -6, "pan": -1},
+                          {"name": "Wet R", "chain": ["delay", "reverb"], "volume_db": -6, "pan": 1}]}"#,
+            vec![plugin(0, "VST3: Archetype Plini (Neural DSP)")],
+        );
+        assert!(matches!(
+            result.actions.first(),
+            Some(ParameterAction::SetTrackMix { track: 2, volume_db: Some(v), pan: None, .. }) if *v == 0.0
+        ));
+        let sends: Vec<(&String, &Vec<String>, f64)> = result
+            .actions
+            .iter()
+            .filter_map(|a| match a {
+                ParameterAction::CreateSendTrack { name, plugins, pan, .. } => Some((name, plugins, *pan)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(sends.len(), 2);
+        assert_eq!(sends[0].0, "Guitar - Wet L");
+        assert_eq!(sends[1].1, &vec!["ReaDelay (Cockos)".to_string(), "ReaVerbate (Cockos)".to_string()]);
+        assert_eq!(sends[1].2, 1.0);
+        assert!(result.summary.contains("route 2 parallel path(s)"), "{}", result.summary);
     }
 }
@@ -0,0 +1,302 @@
+//! Chain Order
+//!
+//! Rule-based signal-chain ordering: every plugin on a track is classified by role (via the
+//! plugin profile registry) and the chain is brought into a role order with as few moves as
+//! possible. Plugins already in a correct relative order (the longest run of non-decreasing
+//! roles) stay put; only the others are moved. Plugins without a listed role are never
+//! moved.
+
+use crate::parameter_ai::ReaperSnapshot;
+use crate::plugin_profile::PluginProfileRegistry;
+use serde::{Deserialize, Serialize};
+
+/// Tuner first, space and limiting last.
+pub const CANONICAL_CHAIN_ORDER: &[&str] = &[
+    "tuner",
+    "gate",
+    "compressor",
+    "overdrive",
+    "distortion",
+    "amp",
+    "cab",
+    "eq",
+    "modulation",
+    "delay",
+    "reverb",
+    "limiter",
+];
+
+/// Role order a chain is sorted into.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainOrder {
+    roles: Vec<String>,
+}
+
+impl Default for ChainOrder {
+    fn default() -> Self {
+        Self::canonical()
+    }
+}
+
+impl ChainOrder {
+    pub fn canonical() -> Self {
+        Self {
+            roles: CANONICAL_CHAIN_ORDER
+                .iter()
+                .map(|r| r.to_string())
+                .collect(),
+        }
+    }
+
+    /// A user-configured order. Role aliases ("comp", "od") resolve through the registry;
+    /// roles it does not know match plugins by their own name ("drive").
+    pub fn new(roles: &[String], profiles: &PluginProfileRegistry) -> Result<Self, String> {
+        let mut canonical: Vec<String> = Vec::new();
+        for role in roles {
+            let role = profiles.canonical_role(role);
+            if role.is_empty() {
+                return Err("chain order contains an empty role".to_string());
+            }
+            if canonical.contains(&role) {
+                return Err(format!("chain order lists '{}' twice", role));
+            }
+            canonical.push(role);
+        }
+        if canonical.is_empty() {
+            return Err("chain order is empty".to_string());
+        }
+        Ok(Self { roles: canonical })
+    }
+
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
+
+    /// Role of a plugin among the listed ones; ties go to the role listed first.
+    pub fn classify(
+        &self,
+        profiles: &PluginProfileRegistry,
+        plugin_name: &str,
+    ) -> Option<(usize, String)> {
+        let mut best: Option<(usize, i32)> = None;
+        for (rank, role) in self.roles.iter().enumerate() {
+            let score = profiles.role_score(plugin_name, role);
+            if score > 0 && best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((rank, score));
+            }
+        }
+        best.map(|(rank, _)| (rank, self.roles[rank].clone()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChainSlot {
+    pub plugin_index: i32,
+    pub plugin_name: String,
+    pub role: Option<String>,
+}
+
+/// One move with REAPER move semantics: remove at `from`, insert at `to`, both relative to
+/// the chain after the previous moves.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChainMove {
+    pub from: i32,
+    pub to: i32,
+    pub plugin_name: String,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChainOrderPlan {
+    /// The chain as it is, with each plugin's role.
+    pub slots: Vec<ChainSlot>,
+    pub moves: Vec<ChainMove>,
+}
+
+pub fn plan_chain_order(
+    snapshot: &ReaperSnapshot,
+    profiles: &PluginProfileRegistry,
+    order: &ChainOrder,
+) -> ChainOrderPlan {
+    let mut plugins: Vec<_> = snapshot.plugins.iter().collect();
+    plugins.sort_by_key(|p| p.index);
+
+    let classified: Vec<Option<(usize, String)>> = plugins
+        .iter()
+        .map(|p| order.classify(profiles, &p.name))
+        .collect();
+    let slots = plugins
+        .iter()
+        .zip(&classified)
+        .map(|(p, class)| ChainSlot {
+            plugin_index: p.index,
+            plugin_name: p.name.clone(),
+            role: class.as_ref().map(|(_, role)| role.clone()),
+        })
+        .collect();
+
+    // Chain positions (ids) of the classified plugins and their ranks.
+    let ranked: Vec<(usize, usize)> = classified
+        .iter()
+        .enumerate()
+        .filter_map(|(id, class)| class.as_ref().map(|(rank, _)| (id, *rank)))
+        .collect();
+    let ranks: Vec<usize> = ranked.iter().map(|(_, rank)| *rank).collect();
+    let kept = longest_ordered_run(&ranks);
+
+    let mut settled: Vec<bool> = vec![false; plugins.len()];
+    for i in kept {
+        settled[ranked[i].0] = true;
+    }
+    let rank_of = |id: usize| classified[id].as_ref().map(|(rank, _)| *rank);
+
+    // Place the others in target order, each right after the last settled plugin whose
+    // rank does not exceed its own (or before the first settled one).
+    let mut movers: Vec<(usize, usize)> = ranked
+        .iter()
+        .copied()
+        .filter(|(id, _)| !settled[*id])
+        .collect();
+    movers.sort_by_key(|(id, rank)| (*rank, *id));
+
+    let mut chain: Vec<usize> = (0..plugins.len()).collect();
+    let mut moves = Vec::new();
+    for (id, rank) in movers {
+        let from = chain
+            .iter()
+            .position(|c| *c == id)
+            .expect("every plugin is in the chain");
+        chain.remove(from);
+        let to = match chain
+            .iter()
+            .rposition(|c| settled[*c] && rank_of(*c).is_some_and(|r| r <= rank))
+        {
+            Some(pos) => pos + 1,
+            None => chain.iter().position(|c| settled[*c]).unwrap_or(0),
+        };
+        chain.insert(to, id);
+        settled[id] = true;
+        if from != to {
+            let (_, role) = classified[id].as_ref().expect("movers are classified");
+            moves.push(ChainMove {
+                from: from as i32,
+                to: to as i32,
+                plugin_name: plugins[id].name.clone(),
+                role: role.clone(),
+            });
+        }
+    }
+
+    ChainOrderPlan { slots, moves }
+}
+
+/// Indices of a longest non-decreasing subsequence of `ranks` (earliest on ties).
+fn longest_ordered_run(ranks: &[usize]) -> Vec<usize> {
+    let mut length = vec![1usize; ranks.len()];
+    let mut prev: Vec<Option<usize>> = vec![None; ranks.len()];
+    for i in 0..ranks.len() {
+        for j in 0..i {
+            if ranks[j] <= ranks[i] && length[j] + 1 > length[i] {
+                length[i] = length[j] + 1;
+                prev[i] = Some(j);
+            }
+        }
+    }
+    let mut end = (0..ranks.len()).fold(None, |best: Option<usize>, i| match best {
+        Some(b) if length[b] >= length[i] => Some(b),
+        _ => Some(i),
+    });
+    let mut run = Vec::new();
+    while let Some(i) = end {
+        run.push(i);
+        end = prev[i];
+    }
+    run.reverse();
+    run
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::chain as snapshot;
+
+    /// Chain names after applying the plan's moves in sequence.
+    fn apply(snapshot: &ReaperSnapshot, plan: &ChainOrderPlan) -> Vec<String> {
+        let mut chain: Vec<String> = snapshot.plugins.iter().map(|p| p.name.clone()).collect();
+        for m in &plan.moves {
+            let name = chain.remove(m.from as usize);
+            assert_eq!(name, m.plugin_name);
+            chain.insert(m.to as usize, name);
+        }
+        chain
+    }
+
+    #[test]
+    fn moves_only_what_is_out_of_order() {
+        let profiles = PluginProfileRegistry::builtin();
+        let snap = snapshot(&[
+            "VST: ReaVerbate (Cockos)",
+            "VST3: Archetype Plini (Neural DSP)",
+            "JS: Utility Meter",
+            "VST: ReaGate (Cockos)",
+            "VST: ReaDelay (Cockos)",
+            "VST: ReaTune (Cockos)",
+        ]);
+        let plan = plan_chain_order(&snap, &profiles, &ChainOrder::canonical());
+        assert_eq!(plan.slots[2].role, None);
+        assert_eq!(plan.slots[5].role.as_deref(), Some("tuner"));
+
+        // amp -> delay is kept; tuner, gate and reverb move.
+        assert_eq!(plan.moves.len(), 3);
+        assert_eq!(
+            apply(&snap, &plan),
+            vec![
+                "VST: ReaTune (Cockos)",
+                "VST: ReaGate (Cockos)",
+                "VST3: Archetype Plini (Neural DSP)",
+                "JS: Utility Meter",
+                "VST: ReaDelay (Cockos)",
+                "VST: ReaVerbate (Cockos)",
+            ]
+        );
+
+        let ordered = snapshot(&[
+            "VST: ReaGate (Cockos)",
+            "VST: ReaEQ (Cockos)",
+            "VST: ReaDelay (Cockos)",
+        ]);
+        assert!(
+            plan_chain_order(&ordered, &profiles, &ChainOrder::canonical())
+                .moves
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn follows_a_user_order() {
+        let profiles = PluginProfileRegistry::builtin();
+        let order = ChainOrder::new(
+            &["eq".to_string(), "comp".to_string(), "amp".to_string()],
+            &profiles,
+        )
+        .unwrap();
+        assert_eq!(order.roles(), ["eq", "compressor", "amp"]);
+        assert!(ChainOrder::new(&["eq".to_string(), "EQ".to_string()], &profiles).is_err());
+
+        let snap = snapshot(&[
+            "VST3: Archetype Nolly (Neural DSP)",
+            "VST: ReaComp (Cockos)",
+            "VST: ReaEQ (Cockos)",
+        ]);
+        let plan = plan_chain_order(&snap, &profiles, &order);
+        assert_eq!(plan.moves.len(), 2);
+        assert_eq!(
+            apply(&snap, &plan),
+            vec![
+                "VST: ReaEQ (Cockos)",
+                "VST: ReaComp (Cockos)",
+                "VST3: Archetype Nolly (Neural DSP)"
+            ]
+        );
+    }
+}
@@ -0,0 +1,393 @@
+//! Encyclopedia Search
+//!
+//! Inverted index over the encyclopedia's text fields, built when the encyclopedia is
+//! loaded. Words are folded like `tone_intent::words`; a query word that is not indexed
+//! also matches indexed words a few edits away ("metalica", "batery"). Entries are ranked
+//! with BM25 per field, weighted by field (artist > album > song > genre > equipment >
+//! tags > description) and summed, each query word counting at most as much as an exact
+//! artist match. Scores are scaled to 0..1 against that maximum for every query word, so
+//! callers can keep a fixed confidence threshold.
+
+use crate::tone_intent::words;
+use std::collections::{HashMap, HashSet};
+
+const K1: f32 = 1.2;
+const B: f32 = 0.75;
+
+/// Weight of a match per edit between the query word and the indexed word.
+const TYPO_WEIGHT: f32 = 0.7;
+
+/// Words that say nothing about which entry is meant.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "the", "of", "in", "on", "to", "for", "with", "from", "by", "me", "my", "i",
+    "give", "want", "get", "make", "like", "tone", "tones", "sound",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchField {
+    Artist,
+    Album,
+    Song,
+    Genre,
+    Equipment,
+    Tags,
+    Description,
+}
+
+const FIELD_COUNT: usize = 7;
+
+impl SearchField {
+    pub fn boost(self) -> f32 {
+        match self {
+            SearchField::Artist => 5.0,
+            SearchField::Album => 4.0,
+            SearchField::Song => 3.5,
+            SearchField::Genre => 2.5,
+            SearchField::Equipment => 2.0,
+            SearchField::Tags => 1.5,
+            SearchField::Description => 1.0,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchField::Artist => "artist",
+            SearchField::Album => "album",
+            SearchField::Song => "song",
+            SearchField::Genre => "genre",
+            SearchField::Equipment => "equipment",
+            SearchField::Tags => "tags",
+            SearchField::Description => "description",
+        }
+    }
+}
+
+/// Occurrences of a word in one field of one document.
+#[derive(Debug, Clone, Copy)]
+struct Posting {
+    doc: usize,
+    field: SearchField,
+    tf: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    postings: HashMap<String, Vec<Posting>>,
+    /// Indexed words per document and field.
+    lengths: Vec<[u32; FIELD_COUNT]>,
+    total_lengths: [u64; FIELD_COUNT],
+}
+
+/// A matching document; `score` is 0..1 and `explanation` lists what contributed to it,
+/// largest first ("artist: metallica +0.52").
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub doc: usize,
+    pub score: f32,
+    pub explanation: Vec<String>,
+}
+
+/// One field's share of a document's score for one query word.
+struct Contribution {
+    field: SearchField,
+    term: String,
+    /// The query word, when it matched `term` only within typo reach.
+    typed: Option<String>,
+    score: f32,
+}
+
+impl Contribution {
+    fn describe(&self, scale: f32) -> String {
+        match &self.typed {
+            Some(typed) => format!(
+                "{}: {} (typed \"{}\") +{:.2}",
+                self.field.label(),
+                self.term,
+                typed,
+                self.score * scale
+            ),
+            None => format!(
+                "{}: {} +{:.2}",
+                self.field.label(),
+                self.term,
+                self.score * scale
+            ),
+        }
+    }
+}
+
+fn index_words(text: &str) -> Vec<String> {
+    words(text)
+        .into_iter()
+        .filter(|w| w.chars().all(char::is_alphanumeric) && !STOP_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Edits a query word of this length may be away from an indexed word.
+fn max_edits(word: &str) -> usize {
+    match word.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, `None` once it exceeds `max`.
+fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            row[j + 1] = (prev[j] + usize::from(ca != cb))
+                .min(prev[j + 1] + 1)
+                .min(row[j] + 1);
+        }
+        // Every later row is at least this row's minimum.
+        if row.iter().min().is_some_and(|&d| d > max) {
+            return None;
+        }
+        prev = row;
+    }
+    Some(prev[b.len()]).filter(|&d| d <= max)
+}
+
+impl SearchIndex {
+    pub fn build<'a, I>(docs: I) -> Self
+    where
+        I: IntoIterator<Item = Vec<(SearchField, &'a str)>>,
+    {
+        let mut index = Self::default();
+        for doc in docs {
+            index.add(&doc);
+        }
+        index
+    }
+
+    /// Index one more document; it gets the next number (`len()` before the call).
+    pub fn add(&mut self, fields: &[(SearchField, &str)]) {
+        let doc = self.lengths.len();
+        let mut lengths = [0u32; FIELD_COUNT];
+        let mut counts: HashMap<(String, SearchField), u32> = HashMap::new();
+        for (field, text) in fields {
+            for word in index_words(text) {
+                lengths[*field as usize] += 1;
+                *counts.entry((word, *field)).or_insert(0) += 1;
+            }
+        }
+        for ((word, field), tf) in counts {
+            self.postings
+                .entry(word)
+                .or_default()
+                .push(Posting { doc, field, tf });
+        }
+        for (total, len) in self.total_lengths.iter_mut().zip(lengths) {
+            *total += u64::from(len);
+        }
+        self.lengths.push(lengths);
+    }
+
+    /// Number of indexed documents.
+    pub fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    fn idf(&self, postings: &[Posting]) -> f32 {
+        let df = postings.iter().map(|p| p.doc).collect::<HashSet<_>>().len() as f32;
+        let n = self.len() as f32;
+        (1.0 + (n - df + 0.5) / (df + 0.5)).ln()
+    }
+
+    /// BM25 term-frequency part, normalized by the field's length against its average.
+    fn saturation(&self, posting: &Posting) -> f32 {
+        let field = posting.field as usize;
+        let len = self.lengths[posting.doc][field] as f32;
+        let avg = self.total_lengths[field] as f32 / self.len() as f32;
+        let tf = posting.tf as f32;
+        tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * len / avg))
+    }
+
+    /// Indexed words a query word stands for, with their edit distance: the word itself
+    /// if it is indexed, otherwise every word within typo reach.
+    fn variants(&self, word: &str) -> Vec<(&str, usize)> {
+        if let Some((term, _)) = self.postings.get_key_value(word) {
+            return vec![(term.as_str(), 0)];
+        }
+        let max = max_edits(word);
+        if max == 0 {
+            return Vec::new();
+        }
+        self.postings
+            .keys()
+            .filter_map(|term| edit_distance(word, term, max).map(|d| (term.as_str(), d)))
+            .collect()
+    }
+
+    /// Best matching documents, highest score first.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let mut seen = HashSet::new();
+        let mut totals: HashMap<usize, f32> = HashMap::new();
+        let mut contributions: HashMap<usize, Vec<Contribution>> = HashMap::new();
+        // What every query word would add as an exact artist match.
+        let mut ideal = 0.0f32;
+
+        for word in index_words(query) {
+            if !seen.insert(word.clone()) {
+                continue;
+            }
+            // Per document only the best variant of the word counts.
+            let mut best: HashMap<usize, (f32, Vec<Contribution>)> = HashMap::new();
+            let mut word_ideal = 0.0f32;
+            for (term, edits) in self.variants(&word) {
+                let postings = &self.postings[term];
+                let weight = TYPO_WEIGHT.powi(edits as i32) * self.idf(postings);
+                let cap = weight * SearchField::Artist.boost();
+                word_ideal = word_ideal.max(cap);
+                let mut per_doc: HashMap<usize, Vec<Contribution>> = HashMap::new();
+                for posting in postings {
+                    per_doc.entry(posting.doc).or_default().push(Contribution {
+                        field: posting.field,
+                        term: term.to_string(),
+                        typed: (edits > 0).then(|| word.clone()),
+                        score: weight * posting.field.boost() * self.saturation(posting),
+                    });
+                }
+                for (doc, mut list) in per_doc {
+                    // A word found in several fields ("Master of Puppets" as album and song)
+                    // counts at most as much as an exact artist match.
+                    let mut total: f32 = list.iter().map(|c| c.score).sum();
+                    if total > cap {
+                        list.iter_mut().for_each(|c| c.score *= cap / total);
+                        total = cap;
+                    }
+                    if best.get(&doc).is_none_or(|(b, _)| total > *b) {
+                        best.insert(doc, (total, list));
+                    }
+                }
+            }
+            ideal += word_ideal;
+            for (doc, (total, list)) in best {
+                *totals.entry(doc).or_insert(0.0) += total;
+                contributions.entry(doc).or_default().extend(list);
+            }
+        }
+
+        if ideal <= 0.0 {
+            return Vec::new();
+        }
+        let mut hits: Vec<SearchHit> = totals
+            .into_iter()
+            .map(|(doc, total)| {
+                let mut list = contributions.remove(&doc).unwrap_or_default();
+                list.sort_by(|a, b| b.score.total_cmp(&a.score));
+                SearchHit {
+                    doc,
+                    score: total / ideal,
+                    explanation: list.iter().map(|c| c.describe(1.0 / ideal)).collect(),
+                }
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.doc.cmp(&b.doc)));
+        hits.truncate(limit);
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> SearchIndex {
+        let docs = [
+            (
+                "Metallica",
+                "Master of Puppets",
+                "Battery",
+                "Thrash Metal",
+                "thrash, tight",
+            ),
+            (
+                "Metallica",
+                "Master of Puppets",
+                "Master of Puppets",
+                "Thrash Metal",
+                "lead, thrash",
+            ),
+            (
+                "Death",
+                "Symbolic",
+                "Crystal Mountain",
+                "Death Metal",
+                "death metal, tight",
+            ),
+            (
+                "Pink Floyd",
+                "The Wall",
+                "Comfortably Numb",
+                "Progressive Rock",
+                "lead, metal",
+            ),
+        ];
+        SearchIndex::build(docs.iter().map(|(artist, album, song, genre, tags)| {
+            vec![
+                (SearchField::Artist, *artist),
+                (SearchField::Album, *album),
+                (SearchField::Song, *song),
+                (SearchField::Genre, *genre),
+                (SearchField::Tags, *tags),
+                (SearchField::Description, "Mesa Boogie rhythm tone"),
+            ]
+        }))
+    }
+
+    #[test]
+    fn ranks_by_field_and_rarity() {
+        let index = index();
+        let hits = index.search("Metallica Battery", 10);
+        assert_eq!(hits.iter().map(|h| h.doc).collect::<Vec<_>>(), vec![0, 1]);
+        assert!(hits[0].score > 0.8, "{:?}", hits[0]);
+        assert_eq!(hits[0].explanation.len(), 2);
+        assert!(hits[0].explanation[0].starts_with("song: battery +"));
+        assert!(hits[1].score < 0.5);
+
+        // The title track's album and song both say "Master of Puppets"; that counts once.
+        let hits = index.search("Metallica Master of Puppets Battery", 10);
+        assert_eq!(hits[0].doc, 0);
+        assert!(hits[1].score < hits[0].score - 0.1);
+
+        // "metal" no longer hits Metallica, and a genre match outranks a tag.
+        let hits = index.search("metal", 10);
+        assert_eq!(
+            hits.iter().map(|h| h.doc).collect::<Vec<_>>(),
+            vec![2, 0, 1, 3]
+        );
+        assert!(hits[1].score > hits[3].score);
+
+        // Stop words and words that are not indexed find nothing.
+        assert!(index.search("give me the tone", 10).is_empty());
+        assert!(index.search("djent", 10).is_empty());
+    }
+
+    #[test]
+    fn tolerates_typos() {
+        let index = index();
+        let hits = index.search("metalica batery", 1);
+        assert_eq!(hits[0].doc, 0);
+        assert!(hits[0].score > 0.8);
+        assert!(hits[0].explanation[0].starts_with("song: battery (typed \"batery\") +"));
+
+        assert_eq!(edit_distance("comfortabley", "comfortably", 2), Some(1));
+        assert_eq!(edit_distance("orion", "onion", 1), Some(1));
+        assert_eq!(edit_distance("lead", "death", 1), None);
+        // Short words must match exactly.
+        assert!(index.search("wal", 10).is_empty());
+    }
+}
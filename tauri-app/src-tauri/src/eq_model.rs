@@ -0,0 +1,189 @@
+//! EQ Model
+//!
+//! Tones describe EQ either with the `eq` map ("800Hz" -> dB, plain boost/cut points) or
+//! with `eq_bands`, which also carry filter type, Q and slope. `tone_eq_bands` merges both
+//! into one frequency-sorted list for the mapper.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EqFilterType {
+    #[serde(alias = "bell")]
+    Peak,
+    LowShelf,
+    HighShelf,
+    #[serde(alias = "hpf", alias = "low_cut")]
+    HighPass,
+    #[serde(alias = "lpf", alias = "high_cut")]
+    LowPass,
+    Notch,
+    BandPass,
+}
+
+impl EqFilterType {
+    /// Boost/cut filters; the others ignore a gain setting.
+    pub fn has_gain(self) -> bool {
+        matches!(
+            self,
+            EqFilterType::Peak | EqFilterType::LowShelf | EqFilterType::HighShelf
+        )
+    }
+
+    pub fn has_slope(self) -> bool {
+        matches!(self, EqFilterType::HighPass | EqFilterType::LowPass)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EqFilterType::Peak => "peak",
+            EqFilterType::LowShelf => "low shelf",
+            EqFilterType::HighShelf => "high shelf",
+            EqFilterType::HighPass => "high-pass",
+            EqFilterType::LowPass => "low-pass",
+            EqFilterType::Notch => "notch",
+            EqFilterType::BandPass => "band-pass",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EqBand {
+    pub freq_hz: f64,
+    #[serde(default)]
+    pub gain_db: f64,
+    /// `None` keeps the plugin's current width.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub q: Option<f64>,
+    /// `None` fits any boost/cut band (peak or shelf), like an `eq` map point.
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub filter: Option<EqFilterType>,
+    /// dB per octave, for high/low-pass filters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slope_db_oct: Option<f64>,
+}
+
+impl EqBand {
+    pub fn point(freq_hz: f64, gain_db: f64) -> Self {
+        Self {
+            freq_hz,
+            gain_db,
+            q: None,
+            filter: None,
+            slope_db_oct: None,
+        }
+    }
+
+    /// Cut filters are structural (a missing HPF changes the tone more than a small bell).
+    pub fn is_cut(&self) -> bool {
+        self.filter.is_some_and(|f| !f.has_gain())
+    }
+
+    pub fn describe(&self) -> String {
+        let mut text = format!(
+            "{:.0} Hz {}",
+            self.freq_hz,
+            self.filter.map_or("point", |f| f.label())
+        );
+        if self.filter.is_none_or(|f| f.has_gain()) {
+            text.push_str(&format!(" {:+.1} dB", self.gain_db));
+        }
+        if let Some(q) = self.q {
+            text.push_str(&format!(" Q {:.2}", q));
+        }
+        if let Some(slope) = self.slope_db_oct {
+            text.push_str(&format!(" {:.0} dB/oct", slope));
+        }
+        text
+    }
+}
+
+pub fn parse_frequency_hz(text: &str) -> Option<f64> {
+    let s = text.trim().to_lowercase().replace(' ', "");
+    if let Some(khz_pos) = s.find("khz") {
+        let num = &s[..khz_pos];
+        let v: f64 = num.parse().ok()?;
+        return Some(v * 1000.0);
+    }
+    if let Some(hz_pos) = s.find("hz") {
+        let num = &s[..hz_pos];
+        let v: f64 = num.parse().ok()?;
+        return Some(v);
+    }
+    None
+}
+
+/// `eq` map points and `eq_bands` as one list sorted by frequency. Map keys that are not
+/// frequencies are dropped.
+pub fn tone_eq_bands(eq: &HashMap<String, f64>, eq_bands: &[EqBand]) -> Vec<EqBand> {
+    let mut bands: Vec<EqBand> = eq
+        .iter()
+        .filter_map(|(key, db)| parse_frequency_hz(key).map(|hz| EqBand::point(hz, *db)))
+        .chain(eq_bands.iter().cloned())
+        .collect();
+    sort_by_frequency(&mut bands);
+    bands
+}
+
+/// Keep at most `max` bands (cut filters first, then the largest |gain|), sorted by frequency.
+pub fn strongest_bands(mut bands: Vec<EqBand>, max: usize) -> Vec<EqBand> {
+    bands.sort_by(|a, b| {
+        b.is_cut()
+            .cmp(&a.is_cut())
+            .then(b.gain_db.abs().total_cmp(&a.gain_db.abs()))
+    });
+    bands.truncate(max);
+    sort_by_frequency(&mut bands);
+    bands
+}
+
+fn sort_by_frequency(bands: &mut [EqBand]) {
+    bands.sort_by(|a, b| a.freq_hz.total_cmp(&b.freq_hz));
+}
+
+/// Bandwidth in octaves for a Q, for EQs whose width knob is in octaves.
+pub fn q_to_octaves(q: f64) -> f64 {
+    2.0 / std::f64::consts::LN_2 * (1.0 / (2.0 * q)).asinh()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_map_points_and_typed_bands_by_frequency() {
+        let eq = HashMap::from([("2kHz".to_string(), 3.0), ("warmth".to_string(), 1.0)]);
+        let typed: Vec<EqBand> = serde_json::from_str(
+            r#"[{"freq_hz": 80, "type": "hpf", "slope_db_oct": 24},
+                {"freq_hz": 400, "gain_db": -5, "q": 2.0, "type": "bell"}]"#,
+        )
+        .unwrap();
+        let bands = tone_eq_bands(&eq, &typed);
+        let freqs: Vec<f64> = bands.iter().map(|b| b.freq_hz).collect();
+        assert_eq!(freqs, vec![80.0, 400.0, 2000.0]);
+        assert_eq!(bands[0].filter, Some(EqFilterType::HighPass));
+        assert_eq!(bands[1].filter, Some(EqFilterType::Peak));
+        assert_eq!(bands[0].describe(), "80 Hz high-pass 24 dB/oct");
+
+        // The HPF survives truncation even though it has no gain.
+        let kept = strongest_bands(bands, 2);
+        assert_eq!(
+            kept.iter().map(|b| b.freq_hz).collect::<Vec<_>>(),
+            vec![80.0, 400.0]
+        );
+    }
+
+    #[test]
+    fn parses_frequency_strings() {
+        assert_eq!(parse_frequency_hz("800Hz").unwrap() as i32, 800);
+        assert_eq!(parse_frequency_hz("2kHz").unwrap() as i32, 2000);
+        assert!(parse_frequency_hz("abc").is_none());
+    }
+
+    #[test]
+    fn converts_q_to_octaves() {
+        assert!((q_to_octaves(std::f64::consts::SQRT_2) - 1.0).abs() < 1e-6);
+        assert!((q_to_octaves(2.0 / 3.0) - 2.0).abs() < 1e-6);
+    }
+}
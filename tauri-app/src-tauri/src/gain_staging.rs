@@ -0,0 +1,228 @@
+//! Gain Staging
+//!
+//! Level checks between plugins. While a reference passage plays, the track meter is read
+//! with the chain cut off after each plugin, so every stage's input and output level is
+//! known. A stage that lands too far from the target gets its output/level control nudged:
+//! through the learned display curve when the control is in dB, otherwise by re-measuring
+//! and following the measured slope.
+
+use crate::param_curve::{DisplayUnit, ParamCurve};
+use crate::parameter_ai::{ReaperParameter, ReaperPlugin};
+use crate::plugin_profile::normalize_token;
+use serde::{Deserialize, Serialize};
+
+/// Below this RMS the meter is treated as silence (nothing playing).
+pub const SILENCE_DB: f64 = -90.0;
+
+/// Assumed range of a level knob without a dB display (+-12 dB), for the first step.
+const DEFAULT_DB_PER_UNIT: f64 = 24.0;
+
+/// Output/level controls, best first (normalized names).
+const LEVEL_CONTROL_NAMES: &[&str] = &[
+    "output",
+    "outputgain",
+    "outputlevel",
+    "outgain",
+    "out",
+    "level",
+    "volume",
+    "master",
+    "makeup",
+    "makeupgain",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MeterReading {
+    pub peak_db: f64,
+    pub rms_db: f64,
+}
+
+impl MeterReading {
+    pub fn is_silent(&self) -> bool {
+        self.rms_db <= SILENCE_DB
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{:.1} dB RMS / {:.1} dB peak", self.rms_db, self.peak_db)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GainStagingConfig {
+    /// Level every stage should hand to the next one.
+    pub target_rms_db: f64,
+    /// Stages within this distance of the target are left alone.
+    pub tolerance_db: f64,
+    /// Corrections never push a stage's peak above this.
+    pub peak_ceiling_db: f64,
+    /// Set-and-measure rounds per stage.
+    pub max_iterations: usize,
+    /// How long the meter is read for each measurement.
+    pub window_ms: u64,
+}
+
+impl Default for GainStagingConfig {
+    fn default() -> Self {
+        Self {
+            target_rms_db: -18.0,
+            tolerance_db: 1.5,
+            peak_ceiling_db: -3.0,
+            max_iterations: 3,
+            window_ms: 600,
+        }
+    }
+}
+
+impl GainStagingConfig {
+    /// dB to add to a stage's output to reach the target without crossing the ceiling.
+    pub fn correction_db(&self, reading: &MeterReading) -> f64 {
+        (self.target_rms_db - reading.rms_db).min(self.peak_ceiling_db - reading.peak_db)
+    }
+
+    pub fn within_tolerance(&self, reading: &MeterReading) -> bool {
+        self.correction_db(reading).abs() <= self.tolerance_db
+    }
+}
+
+/// The plugin's output level control, if it has one ("Output" before "Level", never
+/// "Input" or a drive knob).
+pub fn find_level_control(plugin: &ReaperPlugin) -> Option<&ReaperParameter> {
+    let rank = |param: &ReaperParameter| {
+        let name = normalize_token(&param.name);
+        LEVEL_CONTROL_NAMES
+            .iter()
+            .position(|candidate| *candidate == name)
+            .or_else(|| {
+                LEVEL_CONTROL_NAMES
+                    .iter()
+                    .position(|candidate| candidate.len() > 3 && name.ends_with(candidate))
+                    .map(|i| i + LEVEL_CONTROL_NAMES.len())
+            })
+    };
+    plugin
+        .parameters
+        .iter()
+        .filter_map(|param| rank(param).map(|r| (r, param)))
+        .min_by_key(|(r, param)| (*r, param.index))
+        .map(|(_, param)| param)
+}
+
+/// Next normalized value for a level control that should move the stage by
+/// `correction_db`. `previous` is an earlier (value, measured RMS) pair of the same
+/// control, used when there is no dB curve.
+pub fn next_level_value(
+    current: f64,
+    correction_db: f64,
+    curve: Option<&ParamCurve>,
+    previous: Option<(f64, f64)>,
+    measured_rms_db: f64,
+) -> f64 {
+    if let Some(curve) = curve.filter(|c| c.unit == DisplayUnit::Decibel) {
+        return curve.normalized_for(curve.value_at(current) + correction_db);
+    }
+    let db_per_unit = previous
+        .filter(|(value, rms)| {
+            (current - value).abs() > 1e-4 && (measured_rms_db - rms).abs() > 0.1
+        })
+        .map(|(value, rms)| (measured_rms_db - rms) / (current - value))
+        .filter(|slope| *slope > 0.0)
+        .unwrap_or(DEFAULT_DB_PER_UNIT);
+    (current + correction_db / db_per_unit).clamp(0.0, 1.0)
+}
+
+/// What happened at one stage, for the Act log.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StageReport {
+    pub plugin_index: i32,
+    pub plugin_name: String,
+    pub input: MeterReading,
+    pub output: MeterReading,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control: Option<String>,
+    /// (before, after) normalized value of the control, if it was moved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adjusted: Option<(f64, f64)>,
+    /// Level after the adjustment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adjusted_output: Option<MeterReading>,
+}
+
+impl StageReport {
+    pub fn gain_db(&self) -> f64 {
+        self.final_output().rms_db - self.input.rms_db
+    }
+
+    pub fn final_output(&self) -> MeterReading {
+        self.adjusted_output.unwrap_or(self.output)
+    }
+
+    pub fn describe(&self, config: &GainStagingConfig) -> String {
+        let mut line = format!(
+            "{}: in {} -> out {} ({:+.1} dB)",
+            self.plugin_name,
+            self.input.describe(),
+            self.output.describe(),
+            self.output.rms_db - self.input.rms_db
+        );
+        match (&self.control, self.adjusted, self.adjusted_output) {
+            (Some(control), Some((before, after)), Some(output)) => line.push_str(&format!(
+                "; {} {:.1}% -> {:.1}%, now {}",
+                control,
+                before * 100.0,
+                after * 100.0,
+                output.describe()
+            )),
+            _ if config.within_tolerance(&self.output) => line.push_str("; on target"),
+            (None, _, _) => line.push_str("; off target, no output control"),
+            (Some(control), _, _) => line.push_str(&format!("; off target, {} unchanged", control)),
+        }
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{params, plugin};
+
+    fn stage(names: &[&str]) -> ReaperPlugin {
+        plugin(0, "Test", true, params(names))
+    }
+
+    #[test]
+    fn finds_output_controls() {
+        let od = stage(&["Bypass", "Drive", "Tone", "Level"]);
+        assert_eq!(find_level_control(&od).unwrap().name, "Level");
+        let amp = stage(&["Input Gain", "Gain", "Master", "Output Gain"]);
+        assert_eq!(find_level_control(&amp).unwrap().name, "Output Gain");
+        let comp = stage(&["Threshold", "Ratio", "Makeup"]);
+        assert_eq!(find_level_control(&comp).unwrap().name, "Makeup");
+        assert!(find_level_control(&stage(&["Drive", "Input", "Tone"])).is_none());
+    }
+
+    #[test]
+    fn corrections_respect_target_and_ceiling() {
+        let config = GainStagingConfig::default();
+        let quiet = MeterReading {
+            peak_db: -14.0,
+            rms_db: -26.0,
+        };
+        assert_eq!(config.correction_db(&quiet), 8.0);
+        // Spiky signal: the peak ceiling wins over the RMS target.
+        let spiky = MeterReading {
+            peak_db: -4.0,
+            rms_db: -24.0,
+        };
+        assert_eq!(config.correction_db(&spiky), 1.0);
+        assert!(config.within_tolerance(&spiky));
+
+        // dB curve: exact inversion.
+        let curve =
+            ParamCurve::from_points(DisplayUnit::Decibel, vec![(0.0, -24.0), (1.0, 24.0)]).unwrap();
+        assert!((next_level_value(0.5, 6.0, Some(&curve), None, -24.0) - 0.625).abs() < 1e-9);
+        // No curve: default step, then the measured slope.
+        assert!((next_level_value(0.5, 6.0, None, None, -24.0) - 0.75).abs() < 1e-9);
+        let next = next_level_value(0.75, 2.0, None, Some((0.5, -24.0)), -20.0);
+        assert!((next - 0.875).abs() < 1e-9);
+    }
+}
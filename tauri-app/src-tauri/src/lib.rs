@@ -7,25 +7,51 @@
 //!
 //! Each mode operates in independent conversation rooms!
 
+mod ab_compare;
 mod act_mode;
 mod ai_chain_orchestrator;
 mod ai_client;
 mod audio;
+mod cab_model;
+mod chain_edit;
 mod chain_mapper;
+mod chain_order;
 mod conversation;
 mod dsp;
+mod encyclopedia_search;
+mod eq_model;
 mod errors;
+mod gain_staging;
+mod param_curve;
 mod parameter_ai;
 mod planner_mode;
+mod plugin_profile;
+mod preset_recall;
+mod reaper_changes;
 mod reaper_client;
+mod relative_edit;
 mod researcher_mode;
+mod rfxchain_export;
+mod routing;
+mod rpp_parser;
 mod secure_storage;
+mod snapshot_cache;
+mod snapshot_diff;
+mod tempo_sync;
+#[cfg(test)]
+mod test_fixtures;
 mod tone_ai;
 mod tone_sanitizer;
 mod tone_encyclopedia;
+mod tone_intent;
+mod tone_presets;
+mod tone_vocabulary;
 // undo/redo types live in toneforge-core (testable without tauri deps)
 
+use ab_compare::{AbSession, AbSide};
 use act_mode::ActMode;
+use act_mode::collect_reaper_snapshot;
+use chain_order::{plan_chain_order, ChainOrder};
 use act_mode::{ActProgressEvent, ActProgressSink};
 use ai_client::AIProvider;
 use audio::analyzer::{analyze_spectrum, AnalysisConfig};
@@ -33,26 +59,41 @@ use audio::loader::{load_audio_file, resample_audio};
 use audio::matcher::{match_profiles, MatchConfig as EqMatchConfig, MatchResult as EqMatchResult};
 use audio::profile::{extract_eq_profile, EQProfile};
 use conversation::{Conversation, ConversationManager, ConversationMode, ConversationSummary, Message, MessageMetadata, MessageRole};
+use gain_staging::GainStagingConfig;
 use planner_mode::PlannerMode;
+use plugin_profile::PluginProfileRegistry;
+use parameter_ai::ReaperSnapshot;
+use reaper_changes::SnapshotRevisions;
 use reaper_client::ReaperClient;
 use researcher_mode::ResearcherMode;
+use rfxchain_export::{extract_fx_chain, plan_export, RfxChainExport, EXPORT_TRACK_NAME, STOCK_CHAIN};
+use chain_edit::{ParamTarget, ParamValue};
+use rpp_parser::load_reaper_file;
+use snapshot_cache::SnapshotCache;
+use snapshot_diff::snapshot_diff;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, VecDeque};
 use std::fs;
 use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 use tokio::sync::Mutex as AsyncMutex;
 use std::sync::Arc;
 use tauri::State;
-use tone_encyclopedia::ToneEncyclopedia;
-use toneforge_core::{UndoManager, UndoState};
+use tone_encyclopedia::{ToneEncyclopedia, ToneParameters};
+use tone_presets::{PresetLibrary, TonePreset};
+use tone_vocabulary::ToneVocabulary;
+use toneforge_core::{
+    validate_jump, LiveChains, UndoAction, UndoExecution, UndoExecutor, UndoJump, UndoManager,
+    UndoState,
+};
 
 const ENCYCLOPEDIA_PATH: &str = "tone_encyclopedia.json";
+const VOCABULARY_PATH: &str = "tone_vocabulary.json";
 
-fn resolve_encyclopedia_path() -> std::path::PathBuf {
+fn resolve_data_path(name: &str) -> std::path::PathBuf {
     let manifest_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    let filename = std::path::PathBuf::from(ENCYCLOPEDIA_PATH);
+    let filename = std::path::PathBuf::from(name);
 
     let candidates = [
         std::env::current_dir().ok().map(|d| d.join(&filename)),
@@ -70,6 +111,22 @@ fn resolve_encyclopedia_path() -> std::path::PathBuf {
     manifest_dir.join("..").join("..").join(&filename)
 }
 
+/// `<config dir>/ToneForge/<name>` (home directory if the platform has no config dir).
+fn config_subdir(name: &str) -> std::path::PathBuf {
+    dirs::config_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("ToneForge")
+        .join(name)
+}
+
+/// Built-in plugin profiles plus the user's profile directory.
+fn load_plugin_profiles() -> (PluginProfileRegistry, Vec<String>) {
+    let mut profiles = PluginProfileRegistry::builtin();
+    let warnings = profiles.load_dir(&config_subdir("profiles"));
+    (profiles, warnings)
+}
+
 // ==================== APP STATE ====================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,6 +146,73 @@ struct AppState {
     undo_manager: Arc<AsyncMutex<UndoManager>>,
     conversation_manager: Mutex<ConversationManager>,
     recent_tones: Mutex<VecDeque<RecentTone>>,
+    preset_library: PresetLibrary,
+    /// Bumped by the REAPER change feed; snapshots older than this are stale.
+    snapshot_revisions: Arc<Mutex<SnapshotRevisions>>,
+    /// Plugin parameter lists reused across Act runs until their revision moves.
+    snapshot_cache: Arc<SnapshotCache>,
+    /// Plugin profiles used by the deterministic chain mapper.
+    plugin_profiles: Mutex<PluginProfileRegistry>,
+    /// Descriptive words ("glassy", "brown sound") for Tone AI and relative edits.
+    tone_vocabulary: ToneVocabulary,
+    /// Running before/after comparison of the last undo action, if any.
+    ab_session: AsyncMutex<Option<AbSession>>,
+}
+
+// ==================== REAPER CHANGE FEED ====================
+
+const CHANGE_POLL_TIMEOUT_MS: u64 = 25_000;
+const CHANGE_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Long-poll the extension's change feed for the lifetime of the app, bump snapshot
+/// revisions and forward every batch to the UI as `toneforge:reaper_changed`.
+async fn run_change_feed(app: tauri::AppHandle) {
+    let mut since: Option<u64> = None;
+    loop {
+        let (reaper, revisions, cache) = {
+            let state = app.state::<AppState>();
+            let reaper = state.reaper.lock().unwrap().clone();
+            (
+                reaper,
+                state.snapshot_revisions.clone(),
+                state.snapshot_cache.clone(),
+            )
+        };
+
+        // On (re)connect only learn the current sequence: whatever happened while we were
+        // not listening is unknown, so everything is treated as changed.
+        let timeout_ms = if since.is_some() { CHANGE_POLL_TIMEOUT_MS } else { 0 };
+        let mut batch = match reaper.poll_changes(since.unwrap_or(0), timeout_ms).await {
+            Ok(batch) => batch,
+            Err(_) => {
+                // Nobody reports REAPER-side edits now: stop trusting cached snapshots.
+                revisions.lock().unwrap().set_feed_live(false);
+                since = None;
+                tokio::time::sleep(CHANGE_RETRY_DELAY).await;
+                continue;
+            }
+        };
+
+        match since {
+            // The extension restarted (sequence went backwards) or we just connected.
+            Some(seen) if batch.seq >= seen => {}
+            _ => {
+                batch.changes.clear();
+                batch.reset = true;
+            }
+        }
+        since = Some(batch.seq);
+
+        if let Some(event) = batch.summarize() {
+            revisions.lock().unwrap().apply(&event);
+            if event.all_tracks {
+                // Possibly a different project: don't keep stale entries around.
+                cache.clear();
+            }
+            let _ = app.emit("toneforge:reaper_changed", &event);
+        }
+        revisions.lock().unwrap().set_feed_live(true);
+    }
 }
 
 // ==================== AI CONFIGURATION ====================
@@ -96,7 +220,16 @@ struct AppState {
 #[tauri::command]
 async fn check_reaper_connection(state: State<'_, AppState>) -> Result<bool, String> {
     let reaper = state.reaper.lock().unwrap().clone();
-    reaper.ping().await.map_err(|e| e.to_string())
+    let connected = reaper.ping().await.map_err(|e| e.to_string())?;
+
+    // Restore the project's undo history on (re)connect.
+    if connected {
+        if let Err(e) = sync_undo_history(&reaper, &state.undo_manager).await {
+            eprintln!("[UNDO] Failed to sync history: {}", e);
+        }
+    }
+
+    Ok(connected)
 }
 
 #[tauri::command]
@@ -215,6 +348,9 @@ async fn send_message(
         let history: Vec<Message> = conversation.messages.clone();
         (conversation.mode, history)
     };
+    if matches!(mode, ConversationMode::Act) {
+        ensure_no_ab_compare(&state).await?;
+    }
 
     // Add user message to conversation
     {
@@ -332,7 +468,10 @@ async fn process_act_message(
     let encyclopedia = state.tone_encyclopedia.lock().unwrap().clone();
     let reaper = state.reaper.lock().unwrap().clone();
 
-    let act_mode = ActMode::new(encyclopedia, reaper, ai_provider);
+    let act_mode = ActMode::new(encyclopedia, reaper, ai_provider)
+        .with_snapshot_cache(state.snapshot_cache.clone())
+        .with_profiles(state.plugin_profiles.lock().unwrap().clone())
+        .with_vocabulary(state.tone_vocabulary.clone());
 
     let mut undo_manager = state.undo_manager.clone().lock_owned().await;
     let response = act_mode
@@ -349,6 +488,9 @@ async fn process_act_message(
 
     let mut notes = response.action_logs;
     notes.extend(response.warnings);
+    if let Some(e) = undo_manager.persist_error() {
+        notes.push(format!("Undo history not saved: {}", e));
+    }
 
     let metadata = MessageMetadata {
         actions_count: Some(response.actions_count),
@@ -435,11 +577,10 @@ async fn set_fx_enabled(
     enabled: bool,
     state: State<'_, AppState>,
 ) -> Result<bool, String> {
+    ensure_no_ab_compare(&state).await?;
     let reaper = state.reaper.lock().unwrap().clone();
-    reaper
-        .set_fx_enabled(track, fx, enabled)
-        .await
-        .map_err(|e| e.to_string())
+    state.snapshot_cache.invalidate_fx(track, fx);
+    chain_edit::set_enabled_with_undo(&reaper, &state.undo_manager, track, fx, enabled).await
 }
 
 #[tauri::command]
@@ -488,9 +629,11 @@ async fn process_chat_message(
     message: String,
     track: i32,
     custom_instructions: Option<String>,
+    gain_staging: Option<bool>,
     state: State<'_, AppState>,
     app: tauri::AppHandle,
 ) -> Result<String, String> {
+    ensure_no_ab_compare(&state).await?;
     let user_message = if let Some(ci) = custom_instructions
         .as_ref()
         .map(|s| s.trim())
@@ -510,7 +653,13 @@ async fn process_chat_message(
 
     let encyclopedia = state.tone_encyclopedia.lock().unwrap().clone();
     let reaper = state.reaper.lock().unwrap().clone();
-    let act_mode = ActMode::new(encyclopedia, reaper, ai_provider);
+    let mut act_mode = ActMode::new(encyclopedia, reaper, ai_provider)
+        .with_snapshot_cache(state.snapshot_cache.clone())
+        .with_profiles(state.plugin_profiles.lock().unwrap().clone())
+        .with_vocabulary(state.tone_vocabulary.clone());
+    if gain_staging.unwrap_or(false) {
+        act_mode = act_mode.with_gain_staging(GainStagingConfig::default());
+    }
 
     let sink = TauriActProgress {
         app,
@@ -553,10 +702,14 @@ async fn process_chat_message(
     let mut engine_report_lines = Vec::new();
     engine_report_lines.push(format!("tone_source: {}", response.tone_source));
     engine_report_lines.push(format!("confidence: {:.0}%", response.confidence * 100.0));
-    if !response.warnings.is_empty() {
+    let mut warnings = response.warnings.clone();
+    if let Some(e) = undo_manager.persist_error() {
+        warnings.push(format!("Undo history not saved: {}", e));
+    }
+    if !warnings.is_empty() {
         engine_report_lines.push(String::new());
         engine_report_lines.push("warnings:".to_string());
-        for w in &response.warnings {
+        for w in &warnings {
             engine_report_lines.push(format!("- {}", w));
         }
     }
@@ -618,6 +771,33 @@ async fn get_encyclopedia_stats(state: State<'_, AppState>) -> Result<String, St
     serde_json::to_string(&stats).map_err(|e| e.to_string())
 }
 
+// ==================== PLUGIN PROFILES ====================
+
+/// Re-read the user's plugin profile directory so new profiles apply without a restart.
+#[tauri::command]
+fn reload_plugin_profiles(state: State<'_, AppState>) -> Result<String, String> {
+    let (profiles, warnings) = load_plugin_profiles();
+    let names: Vec<serde_json::Value> = profiles
+        .profiles()
+        .iter()
+        .map(|p| {
+            serde_json::json!({
+                "name": p.name,
+                "roles": p.roles,
+                "source": p.source.as_ref().map(|path| path.display().to_string()),
+            })
+        })
+        .collect();
+    *state.plugin_profiles.lock().unwrap() = profiles;
+
+    let result = serde_json::json!({
+        "directory": config_subdir("profiles").display().to_string(),
+        "profiles": names,
+        "warnings": warnings,
+    });
+    serde_json::to_string(&result).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn search_encyclopedia(
     query: String,
@@ -654,80 +834,568 @@ async fn search_encyclopedia(
     serde_json::to_string(&response).map_err(|e| e.to_string())
 }
 
-// ==================== UNDO/REDO ====================
+// ==================== SNAPSHOTS ====================
 
 #[tauri::command]
-async fn get_undo_state(state: State<'_, AppState>) -> Result<String, String> {
-    let manager = state.undo_manager.lock().await;
-    let undo_state = UndoState::from(&*manager);
-    serde_json::to_string(&undo_state).map_err(|e| e.to_string())
+async fn get_track_snapshot(track: i32, state: State<'_, AppState>) -> Result<String, String> {
+    let reaper = state.reaper.lock().unwrap().clone();
+    let snapshot = collect_reaper_snapshot(&reaper, track)
+        .await
+        .map_err(|e| e.to_string())?;
+    serde_json::to_string(&snapshot).map_err(|e| e.to_string())
 }
 
+/// Diff a stored snapshot (JSON from `get_track_snapshot`) against the live track.
 #[tauri::command]
-async fn perform_undo(state: State<'_, AppState>) -> Result<String, String> {
-    let action = {
-        let mut manager = state.undo_manager.lock().await;
-        manager.pop_undo()
-    };
+async fn diff_track_snapshot(
+    track: i32,
+    snapshot: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let stored: ReaperSnapshot =
+        serde_json::from_str(&snapshot).map_err(|e| format!("Invalid snapshot: {}", e))?;
 
-    let Some(action) = action else {
-        return Err("Nothing to undo".to_string());
+    let reaper = state.reaper.lock().unwrap().clone();
+    let live = collect_reaper_snapshot(&reaper, track)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let diff = snapshot_diff(&stored, &live);
+    serde_json::to_string(&diff).map_err(|e| e.to_string())
+}
+
+// ==================== TONE PRESETS ====================
+
+#[tauri::command]
+async fn save_tone_preset(
+    name: String,
+    track: i32,
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    if name.trim().is_empty() {
+        return Err("Preset name is required".to_string());
+    }
+
+    let reaper = state.reaper.lock().unwrap().clone();
+    let snapshot = collect_reaper_snapshot(&reaper, track)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let preset = TonePreset::from_snapshot(
+        &name,
+        description.as_deref().unwrap_or(""),
+        &tags.unwrap_or_default(),
+        &snapshot,
+    );
+    state.preset_library.save(&preset)?;
+
+    serde_json::to_string(&preset.summary()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn list_tone_presets(tag: Option<String>, state: State<'_, AppState>) -> Result<String, String> {
+    let presets = state.preset_library.list(tag.as_deref())?;
+    serde_json::to_string(&presets).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_tone_preset(id: String, state: State<'_, AppState>) -> Result<String, String> {
+    let preset = state.preset_library.load(&id)?;
+    serde_json::to_string(&preset).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn delete_tone_preset(id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.preset_library.delete(&id)
+}
+
+#[tauri::command]
+async fn recall_tone_preset(id: String, track: i32, state: State<'_, AppState>) -> Result<String, String> {
+    ensure_no_ab_compare(&state).await?;
+    let preset = state.preset_library.load(&id)?;
+    let reaper = state.reaper.lock().unwrap().clone();
+
+    let mut undo_manager = state.undo_manager.clone().lock_owned().await;
+    let mut report =
+        preset_recall::recall_preset(&reaper, &mut undo_manager, &preset, track).await?;
+    if let Some(e) = undo_manager.persist_error() {
+        report.warnings.push(format!("Undo history not saved: {}", e));
+    }
+
+    serde_json::to_string(&report).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn diff_track_preset(id: String, track: i32, state: State<'_, AppState>) -> Result<String, String> {
+    let preset = state.preset_library.load(&id)?;
+    let reaper = state.reaper.lock().unwrap().clone();
+    let live = collect_reaper_snapshot(&reaper, track)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let diff = snapshot_diff(&preset.to_snapshot(track), &live);
+    serde_json::to_string(&diff).map_err(|e| e.to_string())
+}
+
+// ==================== REAPER FILES ====================
+
+fn reaper_file_track(path: &str, file_track: usize) -> Result<ReaperSnapshot, String> {
+    let mut tracks = load_reaper_file(path)?;
+    if file_track >= tracks.len() {
+        return Err(format!(
+            "Track {} not found in file ({} track(s))",
+            file_track,
+            tracks.len()
+        ));
+    }
+    Ok(tracks.swap_remove(file_track))
+}
+
+/// Parse a `.RPP` / `.RfxChain` file offline and return its track snapshots.
+#[tauri::command]
+fn browse_reaper_file(path: String) -> Result<String, String> {
+    let tracks = load_reaper_file(&path)?;
+    serde_json::to_string(&tracks).map_err(|e| e.to_string())
+}
+
+/// Diff a track from a `.RPP` / `.RfxChain` file against the live track.
+#[tauri::command]
+async fn diff_reaper_file_track(
+    path: String,
+    file_track: usize,
+    track: i32,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let stored = reaper_file_track(&path, file_track)?;
+
+    let reaper = state.reaper.lock().unwrap().clone();
+    let live = collect_reaper_snapshot(&reaper, track)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let diff = snapshot_diff(&stored, &live);
+    serde_json::to_string(&diff).map_err(|e| e.to_string())
+}
+
+/// Import a track from a `.RPP` / `.RfxChain` file into the preset library.
+#[tauri::command]
+fn import_reaper_file_preset(
+    path: String,
+    file_track: usize,
+    name: String,
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    if name.trim().is_empty() {
+        return Err("Preset name is required".to_string());
+    }
+
+    let snapshot = reaper_file_track(&path, file_track)?;
+    let preset = TonePreset::from_snapshot(
+        &name,
+        description.as_deref().unwrap_or(""),
+        &tags.unwrap_or_default(),
+        &snapshot,
+    );
+    state.preset_library.save(&preset)?;
+
+    serde_json::to_string(&preset.summary()).map_err(|e| e.to_string())
+}
+
+/// Export an encyclopedia tone as a `.RfxChain` built from stock REAPER plugins. The chain
+/// is rendered on a scratch track so the file carries the plugin state REAPER itself saves;
+/// the scratch track is removed afterwards.
+#[tauri::command]
+async fn export_tone_rfxchain(
+    tone_id: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let parameters = {
+        let encyclopedia = state.tone_encyclopedia.lock().unwrap();
+        encyclopedia
+            .get_by_id(&tone_id)
+            .map(|tone| tone.tone_parameters())
+            .ok_or_else(|| format!("Tone '{}' not found", tone_id))?
     };
 
     let reaper = state.reaper.lock().unwrap().clone();
+    let profiles = state.plugin_profiles.lock().unwrap().clone();
+    let track = reaper
+        .add_track(EXPORT_TRACK_NAME)
+        .await
+        .map_err(|e| format!("Failed to create export track: {}", e))?;
 
-    // Revert in an order that keeps indices as stable as possible:
-    // - parameters, toggles
-    // - moves (reverse)
-    // - plugin loads/removals (reverse)
-    for change in &action.parameter_changes {
-        if let Err(e) = reaper
-            .set_param_by_index(change.track, change.fx_index, change.param_index, change.old_value)
-            .await
-        {
-            eprintln!("[UNDO] Failed to revert param: {}", e);
-        }
+    let result = render_rfxchain(&reaper, track, &parameters, profiles).await;
+    if let Err(e) = reaper.remove_track(track).await {
+        eprintln!("[EXPORT] Failed to remove export track {}: {}", track, e);
     }
+    let export = result?;
+    fs::write(&path, &export.content).map_err(|e| format!("Failed to write FX chain: {}", e))?;
 
-    for toggle in &action.fx_toggles {
-        if let Err(e) = reaper
-            .set_fx_enabled(toggle.track, toggle.fx_index, toggle.was_enabled)
+    serde_json::to_string(&export).map_err(|e| e.to_string())
+}
+
+/// Load the stock chain on `track`, apply the tone and read the chain back from REAPER.
+async fn render_rfxchain(
+    reaper: &ReaperClient,
+    track: i32,
+    parameters: &ToneParameters,
+    profiles: PluginProfileRegistry,
+) -> Result<RfxChainExport, String> {
+    for name in STOCK_CHAIN {
+        reaper
+            .add_plugin(track, name)
             .await
-        {
-            eprintln!("[UNDO] Failed to revert toggle: {}", e);
+            .map_err(|e| format!("Failed to load {}: {}", name, e))?;
+    }
+    let snapshot = collect_reaper_snapshot(reaper, track)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let tempo_bpm = reaper.get_bpm().await.ok();
+    let plan = plan_export(parameters, &snapshot, tempo_bpm, reaper.learned_curves(), profiles)?;
+    for plugin in &plan.plugins {
+        for param in &plugin.params {
+            reaper
+                .set_param_by_index(track, plugin.index, param.index, param.value)
+                .await
+                .map_err(|e| format!("Failed to set {} / {}: {}", plugin.name, param.name, e))?;
         }
     }
+    // Highest index first so the remaining indices stay valid.
+    for fx in plan.unused_plugins.iter().rev() {
+        reaper
+            .remove_plugin(track, *fx)
+            .await
+            .map_err(|e| format!("Failed to remove unused plugin {}: {}", fx, e))?;
+    }
+
+    let chunk = reaper.get_track_chunk(track).await.map_err(|e| e.to_string())?;
+    Ok(RfxChainExport {
+        plugins: plan.plugins,
+        warnings: plan.warnings,
+        content: extract_fx_chain(&chunk)?,
+    })
+}
 
-    for mv in action.fx_moves.iter().rev() {
-        if let Err(e) = reaper.move_fx(mv.track, mv.to_fx_index, mv.from_fx_index).await {
-            eprintln!("[UNDO] Failed to revert FX move: {}", e);
+// ==================== UNDO/REDO ====================
+
+/// Attach the undo history of REAPER's active project and mark entries that no longer
+/// match the live FX chains as stale.
+async fn sync_undo_history(
+    reaper: &ReaperClient,
+    undo_manager: &AsyncMutex<UndoManager>,
+) -> Result<(), String> {
+    let project_path = match reaper.get_project_path().await {
+        Ok(path) => Some(path),
+        Err(e) => {
+            eprintln!("[UNDO] Could not read project path: {}", e);
+            None
+        }
+    };
+    let live = live_chains(reaper).await?;
+
+    let mut manager = undo_manager.lock().await;
+    match project_path.as_deref() {
+        Some("") => manager.detach_project(),
+        Some(path) if manager.project_path() != Some(path) => {
+            manager.attach_project(path, &config_subdir("undo"))?;
+            println!("[UNDO] Restored history for {} ({} action(s))", path, manager.undo_count());
         }
+        _ => {}
     }
 
-    for change in action.plugin_changes.iter().rev() {
-        if change.was_loaded {
-            if let Err(e) = reaper.remove_plugin(change.track, change.fx_index).await {
-                eprintln!("[UNDO] Failed to remove loaded plugin: {}", e);
-            }
-        } else {
-            if let Err(e) = reaper.add_plugin(change.track, &change.plugin_name).await {
-                eprintln!("[UNDO] Failed to restore removed plugin: {}", e);
+    let stale = manager.validate_against(&live);
+    if stale > 0 {
+        println!("[UNDO] {} history entr(ies) no longer match the project", stale);
+    }
+    if let Some(e) = manager.persist_error() {
+        eprintln!("[UNDO] Failed to persist history: {}", e);
+    }
+    Ok(())
+}
+
+/// Add a failed history save to a command's message. `UndoState` reports it as well, for
+/// commands whose result has no room for it.
+fn with_persist_warning(manager: &UndoManager, message: String) -> String {
+    match manager.persist_error() {
+        Some(e) => format!("{} (undo history not saved: {})", message, e),
+        None => message,
+    }
+}
+
+async fn live_chains(reaper: &ReaperClient) -> Result<LiveChains, String> {
+    let tracks = reaper.get_tracks().await.map_err(|e| e.to_string())?;
+    Ok(tracks
+        .tracks
+        .iter()
+        .map(|t| (t.index, t.fx_list.iter().map(|fx| fx.name.clone()).collect()))
+        .collect())
+}
+
+#[tauri::command]
+async fn set_undo_checkpoint(state: State<'_, AppState>, name: String) -> Result<String, String> {
+    let mut manager = state.undo_manager.lock().await;
+    manager.set_checkpoint(&name)?;
+    Ok(format!("Checkpoint '{}' saved", name.trim()))
+}
+
+#[tauri::command]
+async fn remove_undo_checkpoint(state: State<'_, AppState>, name: String) -> Result<String, String> {
+    let mut manager = state.undo_manager.lock().await;
+    if manager.remove_checkpoint(&name) {
+        Ok(format!("Checkpoint '{}' removed", name))
+    } else {
+        Err(format!("No checkpoint named '{}'", name))
+    }
+}
+
+/// Jump to any node of the history tree (`None` = before the first recorded action).
+#[tauri::command]
+async fn jump_to_undo_node(
+    state: State<'_, AppState>,
+    node_id: Option<String>,
+) -> Result<String, String> {
+    ensure_no_ab_compare(&state).await?;
+    let reaper = state.reaper.lock().unwrap().clone();
+    sync_undo_history(&reaper, &state.undo_manager).await?;
+    let jump = state.undo_manager.lock().await.plan_jump(node_id.as_deref())?;
+    execute_jump(&reaper, &state.undo_manager, &state.snapshot_cache, jump).await
+}
+
+#[tauri::command]
+async fn jump_to_undo_checkpoint(state: State<'_, AppState>, name: String) -> Result<String, String> {
+    ensure_no_ab_compare(&state).await?;
+    let reaper = state.reaper.lock().unwrap().clone();
+    sync_undo_history(&reaper, &state.undo_manager).await?;
+    let jump = state.undo_manager.lock().await.plan_checkpoint_jump(&name)?;
+    execute_jump(&reaper, &state.undo_manager, &state.snapshot_cache, jump).await
+}
+
+/// Revert up to the common ancestor, then apply down to the target, moving the cursor after
+/// every step so a failure part-way leaves the history matching what was replayed.
+async fn execute_jump(
+    reaper: &ReaperClient,
+    undo_manager: &AsyncMutex<UndoManager>,
+    cache: &SnapshotCache,
+    jump: UndoJump,
+) -> Result<String, String> {
+    if jump.is_empty() {
+        return Ok("Already there".to_string());
+    }
+
+    let (revert, apply) = undo_manager.lock().await.jump_actions(&jump);
+    let live = live_chains(reaper).await?;
+    validate_jump(&revert, &apply, &live)
+        .map_err(|e| format!("History path no longer matches the REAPER project: {}", e))?;
+    cache.invalidate_tracks(revert.iter().chain(&apply).flat_map(action_tracks));
+
+    let executor = UndoExecutor::new(reaper);
+    let mut messages = Vec::new();
+    for action in &revert {
+        let execution = executor.undo(action).await;
+        undo_manager.lock().await.step_back();
+        messages.push(execution_message("Undone", &execution));
+    }
+    for action in &apply {
+        let execution = executor.redo(action).await;
+        undo_manager.lock().await.step_into(&action.id)?;
+        messages.push(execution_message("Redone", &execution));
+    }
+
+    let message = format!("Jumped {} step(s): {}", jump.len(), messages.join("; "));
+    Ok(with_persist_warning(&*undo_manager.lock().await, message))
+}
+
+// ============================================================================
+// MANUAL CHAIN EDITS
+// ============================================================================
+
+/// Set a parameter by index or fuzzy name, either normalized (0..1) or as a display
+/// target like "-42 dB" / "375 ms". Returns the edit as JSON.
+#[tauri::command]
+async fn set_track_param(
+    state: State<'_, AppState>,
+    track: i32,
+    fx: i32,
+    param_index: Option<i32>,
+    param_name: Option<String>,
+    value: Option<f64>,
+    display: Option<String>,
+) -> Result<String, String> {
+    ensure_no_ab_compare(&state).await?;
+    let target = ParamTarget::from_args(param_index, param_name)?;
+    let value = ParamValue::from_args(value, display)?;
+    let reaper = state.reaper.lock().unwrap().clone();
+    state.snapshot_cache.invalidate_fx(track, fx);
+    let edit =
+        chain_edit::set_param_with_undo(&reaper, &state.undo_manager, track, fx, target, value)
+            .await?;
+    serde_json::to_string(&edit).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn add_track_plugin(
+    state: State<'_, AppState>,
+    track: i32,
+    plugin_name: String,
+    position: Option<i32>,
+) -> Result<i32, String> {
+    ensure_no_ab_compare(&state).await?;
+    let reaper = state.reaper.lock().unwrap().clone();
+    state.snapshot_cache.invalidate_track(track);
+    chain_edit::add_plugin_with_undo(&reaper, &state.undo_manager, track, &plugin_name, position)
+        .await
+}
+
+#[tauri::command]
+async fn move_track_plugin(
+    state: State<'_, AppState>,
+    track: i32,
+    from: i32,
+    to: i32,
+) -> Result<String, String> {
+    ensure_no_ab_compare(&state).await?;
+    let reaper = state.reaper.lock().unwrap().clone();
+    state.snapshot_cache.invalidate_track(track);
+    let name =
+        chain_edit::move_plugin_with_undo(&reaper, &state.undo_manager, track, from, to).await?;
+    let manager = state.undo_manager.lock().await;
+    Ok(with_persist_warning(&manager, format!("Moved {}", name)))
+}
+
+/// Plan (and with `apply`, perform) the moves that bring a track's chain into role order:
+/// the canonical tuner -> gate -> comp -> drive -> amp -> cab -> EQ -> mod -> delay ->
+/// reverb -> limiter order, or `order` when given.
+#[tauri::command]
+async fn order_track_chain(
+    track: i32,
+    order: Option<Vec<String>>,
+    apply: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let profiles = state.plugin_profiles.lock().unwrap().clone();
+    let order = match order {
+        Some(roles) => ChainOrder::new(&roles, &profiles)?,
+        None => ChainOrder::canonical(),
+    };
+
+    let reaper = state.reaper.lock().unwrap().clone();
+    let live = collect_reaper_snapshot(&reaper, track)
+        .await
+        .map_err(|e| e.to_string())?;
+    let plan = plan_chain_order(&live, &profiles, &order);
+
+    let mut moved = 0;
+    let mut warnings = Vec::new();
+    if apply.unwrap_or(false) && !plan.moves.is_empty() {
+        ensure_no_ab_compare(&state).await?;
+        state.snapshot_cache.invalidate_track(track);
+        let mut undo_manager = state.undo_manager.lock().await;
+        undo_manager.begin_action("Reorder FX chain");
+        for m in &plan.moves {
+            match reaper.move_fx(track, m.from, m.to).await {
+                Ok(true) => {
+                    undo_manager.record_fx_move(track, m.from, m.to);
+                    moved += 1;
+                }
+                result => {
+                    // Later moves are relative to this one; stop rather than scramble the chain.
+                    let error = match result {
+                        Err(e) => e.to_string(),
+                        _ => "REAPER rejected the move".to_string(),
+                    };
+                    warnings.push(format!(
+                        "Failed to move '{}' {} -> {}: {}; reorder stopped",
+                        m.plugin_name, m.from, m.to, error
+                    ));
+                    break;
+                }
             }
         }
+        undo_manager.commit_action();
     }
 
-    {
+    let result = serde_json::json!({
+        "order": order.roles(),
+        "slots": plan.slots,
+        "moves": plan.moves,
+        "moved": moved,
+        "warnings": warnings,
+    });
+    serde_json::to_string(&result).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn remove_track_plugin(
+    state: State<'_, AppState>,
+    track: i32,
+    fx: i32,
+) -> Result<String, String> {
+    ensure_no_ab_compare(&state).await?;
+    let reaper = state.reaper.lock().unwrap().clone();
+    state.snapshot_cache.invalidate_track(track);
+    let name = chain_edit::remove_plugin_with_undo(&reaper, &state.undo_manager, track, fx).await?;
+    let manager = state.undo_manager.lock().await;
+    Ok(with_persist_warning(&manager, format!("Removed {}", name)))
+}
+
+#[tauri::command]
+async fn discard_stale_undo(state: State<'_, AppState>) -> Result<String, String> {
+    ensure_no_ab_compare(&state).await?;
+    let mut manager = state.undo_manager.lock().await;
+    let removed = manager.discard_stale();
+    Ok(format!("Discarded {} stale undo entr(ies)", removed))
+}
+
+#[tauri::command]
+async fn get_undo_state(state: State<'_, AppState>) -> Result<String, String> {
+    let manager = state.undo_manager.lock().await;
+    let undo_state = UndoState::from(&*manager);
+    serde_json::to_string(&undo_state).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn perform_undo(state: State<'_, AppState>) -> Result<String, String> {
+    let reaper = state.reaper.lock().unwrap().clone();
+    ensure_no_ab_compare(&state).await?;
+    sync_undo_history(&reaper, &state.undo_manager).await?;
+
+    let action = {
         let mut manager = state.undo_manager.lock().await;
-        manager.push_redo(action.clone());
-    }
+        if let Some(reason) = manager.peek_undo().and_then(|a| a.stale_reason.clone()) {
+            return Err(format!("Undo entry no longer matches the REAPER project: {}", reason));
+        }
+        manager.pop_undo()
+    };
+
+    let Some(action) = action else {
+        return Err("Nothing to undo".to_string());
+    };
 
-    Ok(format!("Undone: {}", action.description))
+    state.snapshot_cache.invalidate_tracks(action_tracks(&action));
+    let execution = UndoExecutor::new(&reaper).undo(&action).await;
+
+    let mut manager = state.undo_manager.lock().await;
+    manager.push_redo(action);
+    Ok(with_persist_warning(&manager, execution_message("Undone", &execution)))
 }
 
 #[tauri::command]
 async fn perform_redo(state: State<'_, AppState>) -> Result<String, String> {
+    let reaper = state.reaper.lock().unwrap().clone();
+    ensure_no_ab_compare(&state).await?;
+    sync_undo_history(&reaper, &state.undo_manager).await?;
+
     let action = {
         let mut manager = state.undo_manager.lock().await;
+        if let Some(reason) = manager.peek_redo().and_then(|a| a.stale_reason.clone()) {
+            return Err(format!("Redo entry no longer matches the REAPER project: {}", reason));
+        }
         manager.pop_redo()
     };
 
@@ -735,50 +1403,220 @@ async fn perform_redo(state: State<'_, AppState>) -> Result<String, String> {
         return Err("Nothing to redo".to_string());
     };
 
+    state.snapshot_cache.invalidate_tracks(action_tracks(&action));
+    let execution = UndoExecutor::new(&reaper).redo(&action).await;
+
+    let mut manager = state.undo_manager.lock().await;
+    manager.push_undo(action);
+    Ok(with_persist_warning(&manager, execution_message("Redone", &execution)))
+}
+
+/// Every track an undo action touches.
+fn action_tracks(action: &UndoAction) -> BTreeSet<i32> {
+    let params = action.parameter_changes.iter().map(|c| c.track);
+    let toggles = action.fx_toggles.iter().map(|c| c.track);
+    let plugins = action.plugin_changes.iter().map(|c| c.track);
+    let moves = action.fx_moves.iter().map(|c| c.track);
+    params.chain(toggles).chain(plugins).chain(moves).collect()
+}
+
+fn execution_message(verb: &str, execution: &UndoExecution) -> String {
+    for failure in &execution.failures {
+        eprintln!("[UNDO] {} failed: {}", failure.op.describe(), failure.error);
+    }
+    if execution.is_complete() {
+        format!("{}: {}", verb, execution.description)
+    } else {
+        format!(
+            "{}: {} ({} of {} step(s) failed: {})",
+            verb,
+            execution.description,
+            execution.failures.len(),
+            execution.applied + execution.failures.len(),
+            execution
+                .failures
+                .iter()
+                .map(|f| format!("{} ({})", f.op.describe(), f.error))
+                .collect::<Vec<_>>()
+                .join("; ")
+        )
+    }
+}
+
+// ==================== A/B COMPARE ====================
+
+/// Compare the last undo action (normally the last Act run) with the chain it replaced.
+/// Both sides are metered while the item under the edit cursor plays; the session starts
+/// on the "after" side with the louder side turned down.
+#[tauri::command]
+async fn start_ab_compare(state: State<'_, AppState>) -> Result<String, String> {
     let reaper = state.reaper.lock().unwrap().clone();
+    let mut session = state.ab_session.lock().await;
+    if session.is_some() {
+        return Err("An A/B comparison is already running".to_string());
+    }
+    sync_undo_history(&reaper, &state.undo_manager).await?;
 
-    for change in &action.parameter_changes {
-        if let Err(e) = reaper
-            .set_param_by_index(change.track, change.fx_index, change.param_index, change.new_value)
-            .await
-        {
-            eprintln!("[REDO] Failed to reapply param: {}", e);
+    let action = {
+        let manager = state.undo_manager.lock().await;
+        let action = manager
+            .peek_undo()
+            .cloned()
+            .ok_or_else(|| "Nothing to compare: the undo history is empty".to_string())?;
+        if let Some(reason) = &action.stale_reason {
+            return Err(format!("Last action no longer matches the REAPER project: {}", reason));
+        }
+        action
+    };
+    let tracks: Vec<i32> = action_tracks(&action).into_iter().collect();
+    let track = match tracks.as_slice() {
+        [track] => *track,
+        [] => return Err(format!("'{}' changed nothing to compare", action.description)),
+        _ => {
+            return Err(format!(
+                "'{}' touches {} tracks; A/B compares single-track changes",
+                action.description,
+                tracks.len()
+            ))
         }
+    };
+
+    let window_ms = GainStagingConfig::default().window_ms;
+    let (base_volume_db, _) = reaper.get_track_mix(track).await.map_err(|e| e.to_string())?;
+    let after_level = reaper
+        .get_track_meter(track, window_ms)
+        .await
+        .map_err(|e| e.to_string())?;
+    if after_level.is_silent() {
+        return Err("No signal on the track; put the edit cursor on a reference item".to_string());
     }
+    switch_ab_side(&reaper, &state.snapshot_cache, &action, AbSide::Before).await?;
+    let before_level = reaper.get_track_meter(track, window_ms).await;
+    switch_ab_side(&reaper, &state.snapshot_cache, &action, AbSide::After).await?;
+    let before_level = before_level.map_err(|e| e.to_string())?;
+
+    let new_session = AbSession {
+        action_id: action.id.clone(),
+        description: action.description.clone(),
+        track,
+        base_volume_db,
+        active: AbSide::After,
+        before_level,
+        after_level,
+    };
+    reaper
+        .set_track_mix(track, Some(new_session.volume_db(AbSide::After)), None)
+        .await
+        .map_err(|e| e.to_string())?;
+    let message = new_session.describe();
+    *session = Some(new_session);
+    Ok(message)
+}
 
-    for toggle in &action.fx_toggles {
-        if let Err(e) = reaper
-            .set_fx_enabled(toggle.track, toggle.fx_index, !toggle.was_enabled)
-            .await
-        {
-            eprintln!("[REDO] Failed to reapply toggle: {}", e);
-        }
+/// Switch to `side` (or the other side), with that side's loudness compensation.
+#[tauri::command]
+async fn switch_ab_compare(
+    state: State<'_, AppState>,
+    side: Option<AbSide>,
+) -> Result<String, String> {
+    let reaper = state.reaper.lock().unwrap().clone();
+    let mut guard = state.ab_session.lock().await;
+    let session = guard
+        .as_mut()
+        .ok_or_else(|| "No A/B comparison running".to_string())?;
+    let target = side.unwrap_or(session.active.other());
+    if target != session.active {
+        let action = ab_action(&state.undo_manager, session).await?;
+        switch_ab_side(&reaper, &state.snapshot_cache, &action, target).await?;
+        session.active = target;
     }
+    reaper
+        .set_track_mix(session.track, Some(session.volume_db(target)), None)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(session.describe())
+}
 
-    for change in &action.plugin_changes {
-        if change.was_loaded {
-            if let Err(e) = reaper.add_plugin(change.track, &change.plugin_name).await {
-                eprintln!("[REDO] Failed to re-add plugin: {}", e);
-            }
-        } else {
-            if let Err(e) = reaper.remove_plugin(change.track, change.fx_index).await {
-                eprintln!("[REDO] Failed to re-remove plugin: {}", e);
+/// Stop comparing: keep one side and put the fader back. Keeping "before" leaves the
+/// action on the redo stack, as if it had been undone.
+#[tauri::command]
+async fn end_ab_compare(state: State<'_, AppState>, keep: AbSide) -> Result<String, String> {
+    let reaper = state.reaper.lock().unwrap().clone();
+    let Some(session) = state.ab_session.lock().await.take() else {
+        return Err("No A/B comparison running".to_string());
+    };
+
+    let kept = async {
+        let action = ab_action(&state.undo_manager, &session).await?;
+        if session.active != keep {
+            switch_ab_side(&reaper, &state.snapshot_cache, &action, keep).await?;
+        }
+        if keep == AbSide::Before {
+            let mut manager = state.undo_manager.lock().await;
+            if let Some(action) = manager.pop_undo() {
+                manager.push_redo(action);
             }
         }
+        Ok::<_, String>(())
     }
+    .await;
+    reaper
+        .set_track_mix(session.track, Some(session.base_volume_db), None)
+        .await
+        .map_err(|e| e.to_string())?;
+    kept?;
 
-    for mv in &action.fx_moves {
-        if let Err(e) = reaper.move_fx(mv.track, mv.from_fx_index, mv.to_fx_index).await {
-            eprintln!("[REDO] Failed to reapply FX move: {}", e);
-        }
-    }
+    Ok(format!(
+        "A/B ended: kept the {} chain of '{}'",
+        if keep == AbSide::Before { "before" } else { "after" },
+        session.description
+    ))
+}
 
-    {
-        let mut manager = state.undo_manager.lock().await;
-        manager.push_undo(action.clone());
+/// Refuse while an A/B comparison runs. Its two sides are the last undo step, so any
+/// other chain edit or history move would desync what switching the sides toggles.
+async fn ensure_no_ab_compare(state: &AppState) -> Result<(), String> {
+    if state.ab_session.lock().await.is_some() {
+        return Err("End the A/B comparison first".to_string());
     }
+    Ok(())
+}
 
-    Ok(format!("Redone: {}", action.description))
+/// The compared action, if it is still the last undo step.
+async fn ab_action(
+    undo_manager: &AsyncMutex<UndoManager>,
+    session: &AbSession,
+) -> Result<UndoAction, String> {
+    undo_manager
+        .lock()
+        .await
+        .peek_undo()
+        .filter(|action| action.id == session.action_id)
+        .cloned()
+        .ok_or_else(|| {
+            "The compared action is no longer the last undo step; end the A/B comparison"
+                .to_string()
+        })
+}
+
+/// Replay `action` toward one side without moving the undo cursor.
+async fn switch_ab_side(
+    reaper: &ReaperClient,
+    cache: &SnapshotCache,
+    action: &UndoAction,
+    side: AbSide,
+) -> Result<(), String> {
+    cache.invalidate_tracks(action_tracks(action));
+    let executor = UndoExecutor::new(reaper);
+    let (verb, execution) = match side {
+        AbSide::Before => ("Reverted", executor.undo(action).await),
+        AbSide::After => ("Reapplied", executor.redo(action).await),
+    };
+    if execution.is_complete() {
+        Ok(())
+    } else {
+        Err(execution_message(verb, &execution))
+    }
 }
 
 // ==================== AUDIO ANALYSIS (EQ MATCH) ====================
@@ -853,7 +1691,7 @@ fn has_saved_api_config() -> bool {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Load encyclopedia on startup
-    let encyclopedia_path = resolve_encyclopedia_path();
+    let encyclopedia_path = resolve_data_path(ENCYCLOPEDIA_PATH);
     let encyclopedia = ToneEncyclopedia::load_from_file(&encyclopedia_path)
         .unwrap_or_else(|e| {
             println!("[STARTUP] Failed to load encyclopedia: {}", e);
@@ -866,9 +1704,29 @@ pub fn run() {
     println!("[STARTUP] Multi-mode conversation system initialized");
     println!("[STARTUP] Modes: 🔍 Researcher | 📋 Planner | ⚡ Act");
 
+    let vocabulary_path = resolve_data_path(VOCABULARY_PATH);
+    let tone_vocabulary = ToneVocabulary::load_from_file(&vocabulary_path).unwrap_or_else(|e| {
+        println!("[STARTUP] Failed to load tone vocabulary: {}", e);
+        println!("[STARTUP] Using built-in tone vocabulary");
+        ToneVocabulary::builtin()
+    });
+    println!("[STARTUP] Tone vocabulary loaded: {} terms", tone_vocabulary.terms.len());
+
+    let (plugin_profiles, profile_warnings) = load_plugin_profiles();
+    for warning in &profile_warnings {
+        println!("[STARTUP] Skipped plugin profile: {}", warning);
+    }
+    println!("[STARTUP] Plugin profiles loaded: {}", plugin_profiles.profiles().len());
+
+    let snapshot_revisions = Arc::new(Mutex::new(SnapshotRevisions::new()));
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
+            tauri::async_runtime::spawn(run_change_feed(app.handle().clone()));
+            Ok(())
+        })
         .manage(AppState {
             reaper: Mutex::new(ReaperClient::new()),
             ai_provider: Mutex::new(None),
@@ -876,6 +1734,12 @@ pub fn run() {
             undo_manager: Arc::new(AsyncMutex::new(UndoManager::new())),
             conversation_manager: Mutex::new(ConversationManager::new()),
             recent_tones: Mutex::new(VecDeque::new()),
+            preset_library: PresetLibrary::new(config_subdir("presets")),
+            snapshot_revisions: snapshot_revisions.clone(),
+            snapshot_cache: Arc::new(SnapshotCache::new(snapshot_revisions)),
+            plugin_profiles: Mutex::new(plugin_profiles),
+            tone_vocabulary,
+            ab_session: AsyncMutex::new(None),
         })
         .invoke_handler(tauri::generate_handler![
             // Connection
@@ -894,10 +1758,41 @@ pub fn run() {
             load_encyclopedia,
             get_encyclopedia_stats,
             search_encyclopedia,
+            // Plugin Profiles
+            reload_plugin_profiles,
+            // Snapshots
+            get_track_snapshot,
+            diff_track_snapshot,
+            // Tone Presets
+            save_tone_preset,
+            list_tone_presets,
+            get_tone_preset,
+            delete_tone_preset,
+            recall_tone_preset,
+            diff_track_preset,
+            // REAPER Files
+            browse_reaper_file,
+            diff_reaper_file_track,
+            import_reaper_file_preset,
+            export_tone_rfxchain,
             // Undo/Redo
             get_undo_state,
             perform_undo,
             perform_redo,
+            discard_stale_undo,
+            set_track_param,
+            add_track_plugin,
+            move_track_plugin,
+            order_track_chain,
+            remove_track_plugin,
+            set_undo_checkpoint,
+            remove_undo_checkpoint,
+            jump_to_undo_node,
+            jump_to_undo_checkpoint,
+            // A/B Compare
+            start_ab_compare,
+            switch_ab_compare,
+            end_ab_compare,
             // Audio Analysis
             load_reference_audio,
             load_input_audio,
@@ -0,0 +1,378 @@
+//! Parameter Curves
+//!
+//! Plugins expose parameters as normalized 0..1 values, while tones are described in dB,
+//! Hz and ms. A curve is learned per plugin parameter by sampling the display string REAPER
+//! shows at evenly spaced normalized values; a target such as "-42 dB" is then inverted by
+//! interpolating between the two samples that bracket it (in log space for Hz and ms, which
+//! plugins almost always map exponentially).
+
+use std::collections::HashMap;
+
+/// Number of probe points used to learn a curve (0.0, 1/32, ... 1.0).
+pub const CURVE_PROBE_STEPS: usize = 33;
+
+/// Stand-in for "-inf dB" so silent ends of gain curves stay invertible.
+const DB_FLOOR: f64 = -150.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisplayUnit {
+    Decibel,
+    Hertz,
+    Milliseconds,
+    Percent,
+    Unitless,
+}
+
+impl DisplayUnit {
+    pub fn label(self) -> &'static str {
+        match self {
+            DisplayUnit::Decibel => "dB",
+            DisplayUnit::Hertz => "Hz",
+            DisplayUnit::Milliseconds => "ms",
+            DisplayUnit::Percent => "%",
+            DisplayUnit::Unitless => "",
+        }
+    }
+
+    fn is_logarithmic(self) -> bool {
+        matches!(self, DisplayUnit::Hertz | DisplayUnit::Milliseconds)
+    }
+}
+
+/// A value in physical units, canonicalized to dB / Hz / ms / %.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayValue {
+    pub value: f64,
+    pub unit: DisplayUnit,
+}
+
+impl DisplayValue {
+    pub fn new(value: f64, unit: DisplayUnit) -> Self {
+        Self { value, unit }
+    }
+}
+
+impl std::fmt::Display for DisplayValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.unit {
+            DisplayUnit::Percent => write!(f, "{}%", self.value),
+            DisplayUnit::Unitless => write!(f, "{}", self.value),
+            unit => write!(f, "{} {}", self.value, unit.label()),
+        }
+    }
+}
+
+/// Map a unit suffix ("db", "khz", "sec", ...) to its canonical unit and scale factor.
+fn unit_from_suffix(suffix: &str) -> Option<(DisplayUnit, f64)> {
+    Some(match suffix {
+        "db" => (DisplayUnit::Decibel, 1.0),
+        "hz" => (DisplayUnit::Hertz, 1.0),
+        "khz" | "k" => (DisplayUnit::Hertz, 1000.0),
+        "ms" => (DisplayUnit::Milliseconds, 1.0),
+        "s" | "sec" | "secs" | "seconds" => (DisplayUnit::Milliseconds, 1000.0),
+        "%" | "pct" | "percent" => (DisplayUnit::Percent, 1.0),
+        "" => (DisplayUnit::Unitless, 1.0),
+        _ => return None,
+    })
+}
+
+/// Parse a display string or user target: "-42.0 dB", "1.2 kHz", "375ms", "1.5 s", "-inf".
+pub fn parse_display(text: &str) -> Option<DisplayValue> {
+    let s = text.trim().to_lowercase();
+    if s.starts_with("-inf") {
+        return Some(DisplayValue::new(DB_FLOOR, DisplayUnit::Decibel));
+    }
+
+    let number_end = s
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || ((c == '-' || c == '+') && i == 0)))
+        .map(|(i, _)| i)
+        .unwrap_or(s.len());
+    let value: f64 = s[..number_end].parse().ok()?;
+    let suffix: String = s[number_end..]
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let (unit, scale) = unit_from_suffix(&suffix)?;
+    Some(DisplayValue::new(value * scale, unit))
+}
+
+/// Split a tone key carrying its unit ("threshold_db", "time_ms", "cutoff_khz") into the
+/// bare key and the unit/scale of its value.
+pub fn split_unit_key(key: &str) -> Option<(&str, DisplayUnit, f64)> {
+    let (base, suffix) = key.trim().rsplit_once(['_', ' ', '-'])?;
+    if base.is_empty() || suffix.is_empty() {
+        return None;
+    }
+    match unit_from_suffix(&suffix.to_lowercase())? {
+        (DisplayUnit::Unitless, _) | (DisplayUnit::Percent, _) => None,
+        (unit, scale) => Some((base, unit, scale)),
+    }
+}
+
+/// Normalized positions to probe when learning a curve.
+pub fn probe_points(steps: usize) -> Vec<f64> {
+    let steps = steps.max(2);
+    (0..steps).map(|i| i as f64 / (steps - 1) as f64).collect()
+}
+
+/// Learned value-to-display mapping of one parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamCurve {
+    pub unit: DisplayUnit,
+    /// (normalized, value) sorted by normalized position; values are monotonic.
+    points: Vec<(f64, f64)>,
+}
+
+impl ParamCurve {
+    /// Build a curve from (normalized, display) samples. `None` if the displays are not
+    /// numeric, mix units or do not move monotonically (e.g. enum-like parameters).
+    pub fn from_samples(samples: &[(f64, String)]) -> Option<Self> {
+        let mut unit = None;
+        let mut points = Vec::with_capacity(samples.len());
+        for (norm, display) in samples {
+            let parsed = parse_display(display)?;
+            // "-inf" parses as dB; accept it on any dB curve.
+            if *unit.get_or_insert(parsed.unit) != parsed.unit {
+                return None;
+            }
+            points.push((*norm, parsed.value));
+        }
+        Self::from_points(unit?, points)
+    }
+
+    /// Build a curve from (normalized, value) points already in `unit`, e.g. a curve
+    /// declared in a plugin profile. Same validation as `from_samples`.
+    pub fn from_points(unit: DisplayUnit, mut points: Vec<(f64, f64)>) -> Option<Self> {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        points.dedup_by(|a, b| a.0 == b.0);
+        let first = points.first()?.1;
+        let last = points.last()?.1;
+        if points.len() < 2 || first == last {
+            return None;
+        }
+        let rising = last > first;
+        let monotonic = points.windows(2).all(|w| {
+            if rising {
+                w[1].1 >= w[0].1
+            } else {
+                w[1].1 <= w[0].1
+            }
+        });
+        if !monotonic {
+            return None;
+        }
+        Some(Self { unit, points })
+    }
+
+    /// Lowest and highest value the parameter can display.
+    pub fn range(&self) -> (f64, f64) {
+        let first = self.points[0].1;
+        let last = self.points[self.points.len() - 1].1;
+        (first.min(last), first.max(last))
+    }
+
+    /// Displayed value at a normalized position.
+    pub fn value_at(&self, normalized: f64) -> f64 {
+        let n = normalized.clamp(0.0, 1.0);
+        let i = self
+            .points
+            .windows(2)
+            .position(|w| n <= w[1].0)
+            .unwrap_or(self.points.len() - 2);
+        let (n0, v0) = self.points[i];
+        let (n1, v1) = self.points[i + 1];
+        let t = if n1 > n0 { (n - n0) / (n1 - n0) } else { 0.0 };
+        if self.use_log(v0, v1) {
+            (v0.ln() + t * (v1.ln() - v0.ln())).exp()
+        } else {
+            v0 + t * (v1 - v0)
+        }
+    }
+
+    /// Normalized position that displays `value` (clamped to the parameter's range).
+    pub fn normalized_for(&self, value: f64) -> f64 {
+        let (min, max) = self.range();
+        let value = value.clamp(min, max);
+        for w in self.points.windows(2) {
+            let ((n0, v0), (n1, v1)) = (w[0], w[1]);
+            if v0 == v1 || value < v0.min(v1) || value > v0.max(v1) {
+                continue;
+            }
+            let t = if self.use_log(v0, v1) {
+                (value.ln() - v0.ln()) / (v1.ln() - v0.ln())
+            } else {
+                (value - v0) / (v1 - v0)
+            };
+            return (n0 + t * (n1 - n0)).clamp(0.0, 1.0);
+        }
+        // Only reachable on a flat run at the requested value.
+        self.points
+            .iter()
+            .find(|(_, v)| *v == value)
+            .map(|(n, _)| *n)
+            .unwrap_or(0.0)
+    }
+
+    /// Invert a target given in physical units.
+    pub fn normalized_for_target(&self, target: &DisplayValue) -> Result<f64, String> {
+        if target.unit != self.unit {
+            return Err(format!(
+                "target {} does not match the parameter's unit ({})",
+                target,
+                self.unit.label()
+            ));
+        }
+        Ok(self.normalized_for(target.value))
+    }
+
+    /// True if `value` is inside the displayable range (otherwise it will be clamped).
+    pub fn contains(&self, value: f64) -> bool {
+        let (min, max) = self.range();
+        value >= min && value <= max
+    }
+
+    fn use_log(&self, v0: f64, v1: f64) -> bool {
+        self.unit.is_logarithmic() && v0 > 0.0 && v1 > 0.0
+    }
+}
+
+/// Learned curves keyed by plugin name (curves belong to the plugin, not the slot).
+#[derive(Debug, Clone, Default)]
+pub struct ParamCurves {
+    plugins: HashMap<String, HashMap<i32, ParamCurve>>,
+}
+
+/// "VST3: ReaDelay (Cockos)" and "ReaDelay (Cockos)" share curves.
+fn plugin_key(plugin_name: &str) -> String {
+    let name = plugin_name.trim().to_lowercase();
+    match name.split_once(": ") {
+        Some((format, rest)) if !format.contains(' ') => rest.trim().to_string(),
+        _ => name,
+    }
+}
+
+impl ParamCurves {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, plugin_name: &str, param_index: i32) -> Option<&ParamCurve> {
+        self.plugins
+            .get(&plugin_key(plugin_name))?
+            .get(&param_index)
+    }
+
+    pub fn insert(&mut self, plugin_name: &str, param_index: i32, curve: ParamCurve) {
+        self.plugins
+            .entry(plugin_key(plugin_name))
+            .or_default()
+            .insert(param_index, curve);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Add every curve from `other`, replacing curves this set already has.
+    pub fn overlay(&mut self, other: ParamCurves) {
+        for (plugin, curves) in other.plugins {
+            self.plugins.entry(plugin).or_default().extend(curves);
+        }
+    }
+
+    /// Normalized value for `target` on a plugin parameter, if its curve is known.
+    pub fn normalized(
+        &self,
+        plugin_name: &str,
+        param_index: i32,
+        target: &DisplayValue,
+    ) -> Option<Result<f64, String>> {
+        self.get(plugin_name, param_index)
+            .map(|curve| curve.normalized_for_target(target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(f: impl Fn(f64) -> String) -> Vec<(f64, String)> {
+        probe_points(CURVE_PROBE_STEPS)
+            .into_iter()
+            .map(|n| (n, f(n)))
+            .collect()
+    }
+
+    #[test]
+    fn parses_display_strings_and_unit_keys() {
+        assert_eq!(
+            parse_display("-42.0 dB"),
+            Some(DisplayValue::new(-42.0, DisplayUnit::Decibel))
+        );
+        assert_eq!(
+            parse_display("1.2 kHz"),
+            Some(DisplayValue::new(1200.0, DisplayUnit::Hertz))
+        );
+        assert_eq!(
+            parse_display("1.5s"),
+            Some(DisplayValue::new(1500.0, DisplayUnit::Milliseconds))
+        );
+        assert_eq!(parse_display("-inf").unwrap().unit, DisplayUnit::Decibel);
+        assert!(parse_display("Off").is_none());
+
+        assert_eq!(
+            split_unit_key("threshold_db"),
+            Some(("threshold", DisplayUnit::Decibel, 1.0))
+        );
+        assert_eq!(
+            split_unit_key("time_s"),
+            Some(("time", DisplayUnit::Milliseconds, 1000.0))
+        );
+        assert!(split_unit_key("gain").is_none());
+        assert!(split_unit_key("room_size").is_none());
+    }
+
+    #[test]
+    fn inverts_learned_curves() {
+        // Threshold: -60..0 dB, linear.
+        let threshold =
+            ParamCurve::from_samples(&samples(|n| format!("{:.1} dB", -60.0 + 60.0 * n))).unwrap();
+        assert!((threshold.normalized_for(-42.0) - 0.3).abs() < 1e-3);
+
+        // Delay: quadratic 0..2000 ms, displays rounded like a real plugin.
+        let delay =
+            ParamCurve::from_samples(&samples(|n| format!("{:.1} ms", 2000.0 * n * n))).unwrap();
+        let n = delay.normalized_for(375.0);
+        assert!((2000.0 * n * n - 375.0).abs() < 10.0, "{}", n);
+
+        // Frequency: 20 Hz..20 kHz exponential, shown in Hz then kHz.
+        let freq = ParamCurve::from_samples(&samples(|n| {
+            let hz = 20.0 * 1000f64.powf(n);
+            if hz >= 1000.0 {
+                format!("{:.2} kHz", hz / 1000.0)
+            } else {
+                format!("{:.0} Hz", hz)
+            }
+        }))
+        .unwrap();
+        let n = freq
+            .normalized_for_target(&parse_display("800 Hz").unwrap())
+            .unwrap();
+        assert!((20.0 * 1000f64.powf(n) - 800.0).abs() < 8.0);
+        assert!(freq
+            .normalized_for_target(&parse_display("3 dB").unwrap())
+            .is_err());
+
+        // Out of range clamps; enum-like parameters are rejected.
+        assert_eq!(threshold.normalized_for(12.0), 1.0);
+        assert!(ParamCurve::from_samples(&samples(|n| {
+            if n < 0.5 {
+                "Off".into()
+            } else {
+                "On".into()
+            }
+        }))
+        .is_none());
+    }
+}
@@ -27,14 +27,14 @@ impl Default for ParameterAIOptions {
 }
 
 /// REAPER track snapshot (simplified for parameter mapping)
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReaperSnapshot {
     pub track_index: i32,
     pub track_name: String,
     pub plugins: Vec<ReaperPlugin>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReaperPlugin {
     pub index: i32,
     pub name: String,
@@ -42,7 +42,7 @@ pub struct ReaperPlugin {
     pub parameters: Vec<ReaperParameter>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReaperParameter {
     pub index: i32,
     pub name: String,
@@ -53,7 +53,7 @@ pub struct ReaperParameter {
 }
 
 /// Mapping action to apply to REAPER
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ParameterAction {
     #[serde(rename = "set_param")]
@@ -86,6 +86,40 @@ pub enum ParameterAction {
         to_plugin_index: i32,
         reason: String,
     },
+    #[serde(rename = "set_named_config")]
+    SetNamedConfig {
+        track: i32,
+        plugin_index: i32,
+        key: String,
+        value: String,
+        reason: String,
+    },
+    /// Group plugins into one FX container, in this order (parallel: each child runs
+    /// alongside the previous one and the outputs are summed).
+    #[serde(rename = "create_container")]
+    CreateContainer {
+        track: i32,
+        plugin_indices: Vec<i32>,
+        parallel: bool,
+        reason: String,
+    },
+    /// New track fed by a send from `track`, with `plugins` loaded on it.
+    #[serde(rename = "create_send_track")]
+    CreateSendTrack {
+        track: i32,
+        name: String,
+        plugins: Vec<String>,
+        volume_db: f64,
+        pan: f64,
+        reason: String,
+    },
+    #[serde(rename = "set_track_mix")]
+    SetTrackMix {
+        track: i32,
+        volume_db: Option<f64>,
+        pan: Option<f64>,
+        reason: String,
+    },
 }
 
 /// Result from Tier 2 Parameter AI
@@ -181,6 +215,11 @@ impl ParameterAI {
         } else {
             "You MUST NOT include any 'load_plugin' actions in this phase. Use only plugins already present in the provided snapshot."
         };
+        let routing_rule = if options.allow_load_plugins {
+            "You MAY include 'create_container' (dual amps, parallel chains) and 'create_send_track' (wet/dry rigs) actions if the tone calls for them.\nPut 'create_container' actions before any 'load_plugin' or 'move_plugin' actions; their plugin_indices refer to the provided snapshot."
+        } else {
+            "You MUST NOT include 'create_container' or 'create_send_track' actions in this phase; routing was already applied."
+        };
 
         format!(
             r#"You are a senior REAPER automation and mix engineer agent.
@@ -189,12 +228,13 @@ Your job is to map abstract tone parameters to concrete REAPER actions.
 Core rules:
 - Only use plugin_index values that exist in the provided snapshot.
 - Only use param_index values that exist under that plugin in the provided snapshot.
-- Keep values in [0.0, 1.0] (normalized).
+- Keep values in [0.0, 1.0] (normalized). Track volume is in dB; pan is in [-1.0, 1.0].
 - Enable plugins before setting their parameters.
 - Keep the action list under {} actions.
 
 Phase rule:
 {}
+{}
 
 Output JSON schema:
 {{
@@ -203,13 +243,17 @@ Output JSON schema:
     {{"type":"enable_plugin","track":0,"plugin_index":0,"plugin_name":"...","reason":"..."}},
     {{"type":"set_param","track":0,"plugin_index":0,"param_index":1,"param_name":"...","value":0.5,"reason":"..."}},
     {{"type":"load_plugin","track":0,"plugin_name":"...","position":null,"reason":"..."}},
-    {{"type":"move_plugin","track":0,"from_plugin_index":2,"to_plugin_index":0,"reason":"Reorder for better signal flow"}}
+    {{"type":"move_plugin","track":0,"from_plugin_index":2,"to_plugin_index":0,"reason":"Reorder for better signal flow"}},
+    {{"type":"set_named_config","track":0,"plugin_index":0,"key":"...","value":"...","reason":"Select IR file / mode"}},
+    {{"type":"create_container","track":0,"plugin_indices":[1,2],"parallel":true,"reason":"Run both amps in parallel"}},
+    {{"type":"create_send_track","track":0,"name":"...","plugins":["..."],"volume_db":-6.0,"pan":0.0,"reason":"..."}},
+    {{"type":"set_track_mix","track":0,"volume_db":null,"pan":-0.5,"reason":"..."}}
   ],
   "warnings": []
 }}
 
 Return ONLY valid JSON. No markdown."#,
-            options.max_actions, load_rule
+            options.max_actions, load_rule, routing_rule
         )
     }
 
@@ -245,6 +289,20 @@ Return ONLY valid JSON. No markdown."#,
             }
             prompt.push('\n');
         }
+        if !tone_params.eq_bands.is_empty() {
+            prompt.push_str("EQ Bands:\n");
+            for band in &tone_params.eq_bands {
+                prompt.push_str(&format!("  - {}\n", band.describe()));
+            }
+            prompt.push('\n');
+        }
+
+        if !tone_params.cab.is_empty() {
+            prompt.push_str(&format!("Cab: {}\n\n", tone_params.cab.describe()));
+        }
+        if !tone_params.routing.is_empty() {
+            prompt.push_str(&format!("Routing: {}\n\n", tone_params.routing.describe()));
+        }
 
         // Effects
         if !tone_params.effects.is_empty() {
@@ -448,6 +506,76 @@ Return ONLY valid JSON. No markdown."#,
                         issues.push("MovePlugin indices must be >= 0".to_string());
                     }
                 }
+                ParameterAction::SetNamedConfig {
+                    track,
+                    plugin_index,
+                    key,
+                    ..
+                } => {
+                    if *track != reaper_snapshot.track_index {
+                        issues.push(format!(
+                            "SetNamedConfig uses track {} but snapshot track is {}",
+                            track, reaper_snapshot.track_index
+                        ));
+                    }
+                    if !reaper_snapshot.plugins.iter().any(|p| p.index == *plugin_index) {
+                        issues.push(format!(
+                            "SetNamedConfig references missing plugin_index {}",
+                            plugin_index
+                        ));
+                    }
+                    if key.trim().is_empty() {
+                        issues.push("SetNamedConfig has empty key".to_string());
+                    }
+                }
+                ParameterAction::CreateContainer {
+                    track,
+                    plugin_indices,
+                    ..
+                } => {
+                    if *track != reaper_snapshot.track_index {
+                        issues.push(format!(
+                            "CreateContainer uses track {} but snapshot track is {}",
+                            track, reaper_snapshot.track_index
+                        ));
+                    }
+                    if plugin_indices.len() < 2 {
+                        issues.push("CreateContainer needs at least two plugins".to_string());
+                    }
+                    for plugin_index in plugin_indices {
+                        if !reaper_snapshot.plugins.iter().any(|p| p.index == *plugin_index) {
+                            issues.push(format!(
+                                "CreateContainer references missing plugin_index {}",
+                                plugin_index
+                            ));
+                        }
+                    }
+                }
+                ParameterAction::CreateSendTrack { track, name, pan, .. } => {
+                    if *track != reaper_snapshot.track_index {
+                        issues.push(format!(
+                            "CreateSendTrack uses track {} but snapshot track is {}",
+                            track, reaper_snapshot.track_index
+                        ));
+                    }
+                    if name.trim().is_empty() {
+                        issues.push("CreateSendTrack has empty name".to_string());
+                    }
+                    if !(-1.0..=1.0).contains(pan) {
+                        issues.push(format!("CreateSendTrack pan {} out of range [-1.0, 1.0]", pan));
+                    }
+                }
+                ParameterAction::SetTrackMix { track, pan, .. } => {
+                    if *track != reaper_snapshot.track_index {
+                        issues.push(format!(
+                            "SetTrackMix uses track {} but snapshot track is {}",
+                            track, reaper_snapshot.track_index
+                        ));
+                    }
+                    if pan.is_some_and(|pan| !(-1.0..=1.0).contains(&pan)) {
+                        issues.push(format!("SetTrackMix pan {:?} out of range [-1.0, 1.0]", pan));
+                    }
+                }
             }
         }
 
@@ -0,0 +1,720 @@
+//! Plugin Profiles
+//!
+//! Data the `ChainMapper` uses to drive plugins: which plugin fills a role (amp, eq, gate,
+//! ...), which parameter answers to a tone key, declared value curves and section gates.
+//! The built-in set (`plugin_profiles.json`) holds the generic role keywords and parameter
+//! synonyms plus the stock Cockos plugins; user profiles are `*.json` / `*.toml` files in
+//! a profile directory and take precedence over the built-ins.
+//!
+//! A profile file holds either a single profile or a `profiles` list, optionally with
+//! `roles` and `param_synonyms` tables that replace the built-in entries of the same name:
+//!
+//! ```toml
+//! name = "Archetype Gojira"
+//! matches = ["archetypegojira"]
+//! roles = ["amp"]
+//!
+//! [params]
+//! gain = ["Amp Gain"]
+//! presence = ["Amp Presence"]
+//!
+//! [curves."Amp Gain"]
+//! min = "0 dB"
+//! max = "60 dB"
+//!
+//! [[gates]]
+//! param = "Amp On"
+//! kind = "enable"
+//! section = ["amp"]
+//! ```
+
+use crate::eq_model::EqFilterType;
+use crate::param_curve::{parse_display, probe_points, ParamCurve, ParamCurves, CURVE_PROBE_STEPS};
+use crate::parameter_ai::{ReaperParameter, ReaperPlugin, ReaperSnapshot};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const BUILTIN_PROFILES: &str = include_str!("plugin_profiles.json");
+
+/// Score bonus for a plugin whose profile declares the role being filled, so profiled
+/// plugins win over keyword guesses.
+const PROFILE_ROLE_BONUS: i32 = 100;
+
+/// How plugins for a role are recognized when no profile claims one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoleSpec {
+    /// Other effect type names for this role ("noisegate" for "gate").
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Plugin name keywords, strongest first.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Plugin to load when the chain has nothing for the role.
+    #[serde(default)]
+    pub load: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GateKind {
+    /// 1.0 = section on ("Amp On", "Delay Enable").
+    Enable,
+    /// 1.0 = section off ("Bypass").
+    Bypass,
+}
+
+/// A parameter switching a section of the plugin on or off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SectionGate {
+    pub param: String,
+    pub kind: GateKind,
+    /// Name tokens of the parameters the gate controls ("amp", "delay"); empty for a
+    /// whole-plugin switch.
+    #[serde(default)]
+    pub section: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CurveScale {
+    #[default]
+    Linear,
+    Log,
+}
+
+/// Display curve of a parameter, declared instead of probed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CurveSpec {
+    /// `{ min = "20 Hz", max = "20 kHz", scale = "log" }`
+    Range {
+        min: String,
+        max: String,
+        #[serde(default)]
+        scale: CurveScale,
+    },
+    /// `{ points = [[0.0, "-inf"], [0.5, "-6 dB"], [1.0, "12 dB"]] }`
+    Points { points: Vec<(f64, String)> },
+}
+
+impl CurveSpec {
+    pub fn to_curve(&self) -> Option<ParamCurve> {
+        match self {
+            CurveSpec::Points { points } => ParamCurve::from_samples(points),
+            CurveSpec::Range { min, max, scale } => {
+                let (min, max) = (parse_display(min)?, parse_display(max)?);
+                if min.unit != max.unit {
+                    return None;
+                }
+                let log = *scale == CurveScale::Log;
+                if log && (min.value <= 0.0 || max.value <= 0.0) {
+                    return None;
+                }
+                let points = probe_points(CURVE_PROBE_STEPS)
+                    .into_iter()
+                    .map(|n| {
+                        let value = if log {
+                            (min.value.ln() + n * (max.value.ln() - min.value.ln())).exp()
+                        } else {
+                            min.value + n * (max.value - min.value)
+                        };
+                        (n, value)
+                    })
+                    .collect();
+                ParamCurve::from_points(min.unit, points)
+            }
+        }
+    }
+}
+
+fn default_band_word() -> String {
+    "band".to_string()
+}
+
+fn default_freq_word() -> String {
+    "freq".to_string()
+}
+
+fn default_gain_word() -> String {
+    "gain".to_string()
+}
+
+fn default_gain_range() -> f64 {
+    24.0
+}
+
+fn default_q_word() -> String {
+    "q".to_string()
+}
+
+fn default_q_range() -> [f64; 2] {
+    [0.1, 10.0]
+}
+
+/// What a parametric EQ's width knob measures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EqWidthMode {
+    #[default]
+    Q,
+    /// Bandwidth in octaves (ReaEQ's "BW").
+    Octaves,
+}
+
+/// Which setting of a band a parameter controls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EqBandSlot {
+    Freq,
+    Gain,
+    Width,
+    Type,
+    Slope,
+    Enable,
+}
+
+/// Band parameter naming of a parametric EQ ("Band 3 Freq", "Band 3 Gain", "Band 3 Q")
+/// and how its filter types and slopes are set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EqBandLayout {
+    #[serde(default = "default_band_word")]
+    pub band: String,
+    #[serde(default = "default_freq_word")]
+    pub freq: String,
+    #[serde(default = "default_gain_word")]
+    pub gain: String,
+    /// Width parameter word ("q", "bw").
+    #[serde(default = "default_q_word")]
+    pub q: String,
+    #[serde(default)]
+    pub q_mode: EqWidthMode,
+    /// Gain knob range (+/- dB) assumed when no curve is known.
+    #[serde(default = "default_gain_range")]
+    pub gain_range_db: f64,
+    /// Width knob range (log scale) assumed when no curve is known, in `q_mode` units.
+    #[serde(default = "default_q_range")]
+    pub q_range: [f64; 2],
+    /// Filter type parameter word ("shape", "type") and its normalized value per type.
+    #[serde(default)]
+    pub type_param: Option<String>,
+    #[serde(default)]
+    pub types: HashMap<EqFilterType, f64>,
+    /// Band number -> fixed filter type, for EQs whose band types are not parameters.
+    #[serde(default)]
+    pub band_types: HashMap<String, EqFilterType>,
+    /// Slope parameter word and its normalized value per dB/octave ("24" -> 0.375).
+    #[serde(default)]
+    pub slope: Option<String>,
+    #[serde(default)]
+    pub slopes: HashMap<String, f64>,
+    /// Per-band switch ("used", "enabled") turned on for bands that get a filter.
+    #[serde(default)]
+    pub enable: Option<String>,
+}
+
+impl Default for EqBandLayout {
+    fn default() -> Self {
+        Self {
+            band: default_band_word(),
+            freq: default_freq_word(),
+            gain: default_gain_word(),
+            q: default_q_word(),
+            q_mode: EqWidthMode::default(),
+            gain_range_db: default_gain_range(),
+            q_range: default_q_range(),
+            type_param: None,
+            types: HashMap::new(),
+            band_types: HashMap::new(),
+            slope: None,
+            slopes: HashMap::new(),
+            enable: None,
+        }
+    }
+}
+
+impl EqBandLayout {
+    /// Band number and slot of a parameter: "Band 3 Freq" -> (3, Freq). The slot word is
+    /// matched against the start of what remains after the band word and number.
+    pub fn band_slot(&self, param_name: &str) -> Option<(i32, EqBandSlot)> {
+        let lower = param_name.to_lowercase();
+        let band = self.band.to_lowercase();
+        let start = lower.find(&band)?;
+        let after = lower[start + band.len()..].trim_start();
+        let digits: String = after.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let number = digits.parse().ok()?;
+        let rest = normalize_token(&format!("{}{}", &lower[..start], &after[digits.len()..]));
+
+        let words = [
+            (Some(&self.freq), EqBandSlot::Freq),
+            (Some(&self.gain), EqBandSlot::Gain),
+            (self.type_param.as_ref(), EqBandSlot::Type),
+            (self.slope.as_ref(), EqBandSlot::Slope),
+            (self.enable.as_ref(), EqBandSlot::Enable),
+            (Some(&self.q), EqBandSlot::Width),
+        ];
+        words.into_iter().find_map(|(word, slot)| {
+            let word = normalize_token(word?);
+            (!word.is_empty() && rest.starts_with(&word)).then_some((number, slot))
+        })
+    }
+
+    /// Filter type a band is stuck with, if the EQ cannot change it.
+    pub fn fixed_type(&self, band: i32) -> Option<EqFilterType> {
+        self.band_types.get(&band.to_string()).copied()
+    }
+
+    /// Normalized slope value for a dB/octave setting.
+    pub fn slope_value(&self, db_per_octave: f64) -> Option<f64> {
+        self.slopes
+            .iter()
+            .find(|(k, _)| {
+                k.trim()
+                    .parse::<f64>()
+                    .is_ok_and(|v| (v - db_per_octave).abs() < 0.5)
+            })
+            .map(|(_, v)| *v)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginProfile {
+    pub name: String,
+    /// Plugin name keywords; the profile applies when one of them appears in the
+    /// normalized plugin name.
+    pub matches: Vec<String>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Order among profiles matching the same plugin (higher first); also added to the
+    /// plugin's score when picking a plugin for one of `roles`.
+    #[serde(default)]
+    pub priority: i32,
+    /// Tone key -> parameter names, preferred first.
+    #[serde(default)]
+    pub params: HashMap<String, Vec<String>>,
+    /// Parameter name -> display curve.
+    #[serde(default)]
+    pub curves: HashMap<String, CurveSpec>,
+    /// Declared section gates; replace the name-based gate detection for this plugin.
+    #[serde(default)]
+    pub gates: Vec<SectionGate>,
+    #[serde(default)]
+    pub eq_bands: Option<EqBandLayout>,
+    /// Parameter name -> labels of a stepped parameter (cab type, mic), in value order.
+    #[serde(default)]
+    pub options: HashMap<String, Vec<String>>,
+    /// Named config key that takes an impulse response file path (see `/fx/named_config`).
+    #[serde(default)]
+    pub ir_file: Option<String>,
+    /// File the profile was loaded from (`None` for built-ins).
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+}
+
+impl PluginProfile {
+    pub fn matches_plugin(&self, plugin_name: &str) -> bool {
+        let name = normalize_token(plugin_name);
+        self.matches
+            .iter()
+            .map(|m| normalize_token(m))
+            .any(|m| !m.is_empty() && name.contains(&m))
+    }
+
+    /// Parameter declared for a tone key, by exact (normalized) name.
+    pub fn param_for<'a>(
+        &self,
+        plugin: &'a ReaperPlugin,
+        key: &str,
+    ) -> Option<&'a ReaperParameter> {
+        let key = normalize_token(key);
+        let (_, names) = self
+            .params
+            .iter()
+            .find(|(k, _)| normalize_token(k) == key)?;
+        names.iter().find_map(|name| find_param(plugin, name))
+    }
+
+    /// Declared labels of a stepped parameter.
+    pub fn options_for(&self, param_name: &str) -> Option<&[String]> {
+        let name = normalize_token(param_name);
+        self.options
+            .iter()
+            .find(|(k, labels)| normalize_token(k) == name && !labels.is_empty())
+            .map(|(_, labels)| labels.as_slice())
+    }
+}
+
+/// Contents of one profile file (and of the built-in set).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileFile {
+    #[serde(default)]
+    roles: HashMap<String, RoleSpec>,
+    #[serde(default)]
+    param_synonyms: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    profiles: Vec<PluginProfile>,
+}
+
+/// Parse a profile file; `toml` selects the format.
+fn parse_profile_file(text: &str, toml: bool) -> Result<ProfileFile, String> {
+    if toml {
+        let table: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+        // A profile's `roles` is a list; the file-level `roles` is a table.
+        let is_file = table.contains_key("profiles")
+            || table.contains_key("param_synonyms")
+            || table.get("roles").is_some_and(|r| r.is_table());
+        let value = toml::Value::Table(table);
+        if is_file {
+            value.try_into().map_err(|e: toml::de::Error| e.to_string())
+        } else {
+            let profile: PluginProfile = value
+                .try_into()
+                .map_err(|e: toml::de::Error| e.to_string())?;
+            Ok(ProfileFile {
+                profiles: vec![profile],
+                ..Default::default()
+            })
+        }
+    } else {
+        let value: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let is_file = value.get("profiles").is_some()
+            || value.get("param_synonyms").is_some()
+            || value.get("roles").is_some_and(|r| r.is_object());
+        if is_file {
+            serde_json::from_value(value).map_err(|e| e.to_string())
+        } else {
+            let profile: PluginProfile =
+                serde_json::from_value(value).map_err(|e| e.to_string())?;
+            Ok(ProfileFile {
+                profiles: vec![profile],
+                ..Default::default()
+            })
+        }
+    }
+}
+
+/// Problems that would make a profile silently do nothing.
+fn validate_profile(profile: &PluginProfile) -> Result<(), String> {
+    if profile
+        .matches
+        .iter()
+        .all(|m| normalize_token(m).is_empty())
+    {
+        return Err(format!(
+            "profile '{}' has no plugin name to match",
+            profile.name
+        ));
+    }
+    for (param, spec) in &profile.curves {
+        if spec.to_curve().is_none() {
+            return Err(format!(
+                "profile '{}': curve for '{}' is not a monotonic numeric curve",
+                profile.name, param
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct PluginProfileRegistry {
+    roles: HashMap<String, RoleSpec>,
+    synonyms: HashMap<String, Vec<String>>,
+    /// Highest priority first; user profiles before built-ins of equal priority.
+    profiles: Vec<PluginProfile>,
+}
+
+impl Default for PluginProfileRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl PluginProfileRegistry {
+    pub fn builtin() -> Self {
+        let file = parse_profile_file(BUILTIN_PROFILES, false)
+            .expect("built-in plugin_profiles.json is valid");
+        let mut registry = Self {
+            roles: HashMap::new(),
+            synonyms: HashMap::new(),
+            profiles: Vec::new(),
+        };
+        registry.add_file(file);
+        registry
+    }
+
+    /// Load every `*.json` / `*.toml` profile file in `dir` (a missing directory is not an
+    /// error). Files that fail to parse or validate are skipped and reported.
+    pub fn load_dir(&mut self, dir: &Path) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return warnings,
+            Err(e) => {
+                warnings.push(format!("{}: {}", dir.display(), e));
+                return warnings;
+            }
+        };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        paths.sort();
+
+        let mut loaded = ProfileFile::default();
+        for path in paths {
+            let toml = match path.extension().and_then(|e| e.to_str()) {
+                Some("json") => false,
+                Some("toml") => true,
+                _ => continue,
+            };
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| parse_profile_file(&text, toml));
+            let file = match parsed {
+                Ok(file) => file,
+                Err(e) => {
+                    warnings.push(format!("{}: {}", path.display(), e));
+                    continue;
+                }
+            };
+            loaded.roles.extend(file.roles);
+            loaded.param_synonyms.extend(file.param_synonyms);
+            for mut profile in file.profiles {
+                if let Err(e) = validate_profile(&profile) {
+                    warnings.push(format!("{}: {}", path.display(), e));
+                    continue;
+                }
+                profile.source = Some(path.clone());
+                loaded.profiles.push(profile);
+            }
+        }
+        self.add_file(loaded);
+        warnings
+    }
+
+    fn add_file(&mut self, file: ProfileFile) {
+        for (role, spec) in file.roles {
+            self.roles.insert(normalize_token(&role), spec);
+        }
+        for (key, synonyms) in file.param_synonyms {
+            self.synonyms.insert(normalize_token(&key), synonyms);
+        }
+        // New profiles go first so they win ties with what is already registered.
+        self.profiles.splice(0..0, file.profiles);
+        self.profiles.sort_by_key(|p| std::cmp::Reverse(p.priority));
+    }
+
+    pub fn profiles(&self) -> &[PluginProfile] {
+        &self.profiles
+    }
+
+    /// Role name for an effect type, resolving aliases ("Noise Gate" -> "gate").
+    pub fn canonical_role(&self, name: &str) -> String {
+        let norm = normalize_token(name);
+        if self.roles.contains_key(&norm) {
+            return norm;
+        }
+        self.roles
+            .iter()
+            .find(|(_, spec)| spec.aliases.iter().any(|a| normalize_token(a) == norm))
+            .map(|(role, _)| role.clone())
+            .unwrap_or(norm)
+    }
+
+    pub fn has_role(&self, name: &str) -> bool {
+        self.roles.contains_key(&self.canonical_role(name))
+    }
+
+    /// Plugin name keywords for a role; an unknown role matches its own name.
+    pub fn role_keywords(&self, role: &str) -> Vec<String> {
+        let role = self.canonical_role(role);
+        match self.roles.get(&role) {
+            Some(spec) if !spec.keywords.is_empty() => {
+                spec.keywords.iter().map(|k| normalize_token(k)).collect()
+            }
+            _ => vec![role],
+        }
+    }
+
+    /// Plugin to load when a chain has nothing for the role.
+    pub fn load_name(&self, role: &str) -> Option<&str> {
+        self.roles.get(&self.canonical_role(role))?.load.as_deref()
+    }
+
+    /// Parameter name candidates for a tone key, strongest first.
+    pub fn synonyms(&self, key: &str) -> Vec<String> {
+        let key = normalize_token(key);
+        match self.synonyms.get(&key) {
+            Some(list) => list.iter().map(|s| normalize_token(s)).collect(),
+            None => vec![key],
+        }
+    }
+
+    /// Highest-ranked profile matching a plugin name.
+    pub fn profile_for(&self, plugin_name: &str) -> Option<&PluginProfile> {
+        self.profiles.iter().find(|p| p.matches_plugin(plugin_name))
+    }
+
+    pub fn profile_has_role(&self, profile: &PluginProfile, role: &str) -> bool {
+        let role = self.canonical_role(role);
+        profile.roles.iter().any(|r| self.canonical_role(r) == role)
+    }
+
+    /// How well a plugin fits a role: role keywords found in its name (earlier keywords
+    /// weigh more) plus a bonus when its profile declares the role. 0 means no match.
+    pub fn role_score(&self, plugin_name: &str, role: &str) -> i32 {
+        let name = normalize_token(plugin_name);
+        let mut score: i32 = self
+            .role_keywords(role)
+            .iter()
+            .enumerate()
+            .filter(|(_, k)| name.contains(k.as_str()))
+            .map(|(i, _)| 10 - i as i32)
+            .sum();
+        if let Some(profile) = self.profile_for(plugin_name) {
+            if self.profile_has_role(profile, role) {
+                score += PROFILE_ROLE_BONUS + profile.priority;
+            }
+        }
+        score
+    }
+
+    /// Curves declared by the profiles of the plugins in a snapshot.
+    pub fn declared_curves(&self, snapshot: &ReaperSnapshot) -> ParamCurves {
+        let mut curves = ParamCurves::new();
+        for plugin in &snapshot.plugins {
+            let Some(profile) = self.profile_for(&plugin.name) else {
+                continue;
+            };
+            for (param_name, spec) in &profile.curves {
+                if let (Some(param), Some(curve)) =
+                    (find_param(plugin, param_name), spec.to_curve())
+                {
+                    curves.insert(&plugin.name, param.index, curve);
+                }
+            }
+        }
+        curves
+    }
+}
+
+/// Parameter with exactly this (normalized) name.
+pub fn find_param<'a>(plugin: &'a ReaperPlugin, name: &str) -> Option<&'a ReaperParameter> {
+    let name = normalize_token(name);
+    plugin
+        .parameters
+        .iter()
+        .find(|p| normalize_token(&p.name) == name)
+}
+
+pub fn normalize_token(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::param_curve::{DisplayUnit, DisplayValue};
+
+    #[test]
+    fn builtin_profiles_cover_stock_roles() {
+        let registry = PluginProfileRegistry::builtin();
+        assert_eq!(registry.canonical_role("Noise Gate"), "gate");
+        assert_eq!(registry.load_name("noisegate"), Some("ReaGate (Cockos)"));
+        assert_eq!(registry.role_keywords("phaser"), vec!["phaser".to_string()]);
+        assert_eq!(registry.synonyms("Mix")[1], "wet");
+
+        let reaeq = registry.profile_for("VST: ReaEQ (Cockos)").unwrap();
+        assert!(registry.profile_has_role(reaeq, "eq"));
+        let layout = reaeq.eq_bands.as_ref().unwrap();
+        assert_eq!(layout.band_slot("Band 3 Gain"), Some((3, EqBandSlot::Gain)));
+        assert_eq!(layout.fixed_type(1), Some(EqFilterType::LowShelf));
+
+        // "Frequency" starts with the freq word but must not be read as a Q knob.
+        let pro_q = EqBandLayout {
+            freq: "frequency".to_string(),
+            type_param: Some("shape".to_string()),
+            enable: Some("used".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            pro_q.band_slot("Band 12 Frequency"),
+            Some((12, EqBandSlot::Freq))
+        );
+        assert_eq!(pro_q.band_slot("Band 2 Q"), Some((2, EqBandSlot::Width)));
+        assert_eq!(pro_q.band_slot("Band 2 Shape"), Some((2, EqBandSlot::Type)));
+        assert_eq!(
+            pro_q.band_slot("Band 2 Used"),
+            Some((2, EqBandSlot::Enable))
+        );
+        assert_eq!(pro_q.band_slot("Output Gain"), None);
+    }
+
+    #[test]
+    fn parses_toml_and_json_profiles() {
+        let toml = r#"
+name = "Archetype Gojira"
+matches = ["archetypegojira"]
+roles = ["amp"]
+priority = 5
+
+[params]
+gain = ["Amp Gain"]
+
+[curves."Amp Gain"]
+min = "20 Hz"
+max = "20 kHz"
+scale = "log"
+
+[[gates]]
+param = "Amp On"
+kind = "enable"
+section = ["amp"]
+"#;
+        let file = parse_profile_file(toml, true).unwrap();
+        let profile = &file.profiles[0];
+        assert_eq!(profile.gates[0].kind, GateKind::Enable);
+        let curve = profile.curves["Amp Gain"].to_curve().unwrap();
+        let mid = curve
+            .normalized_for_target(&DisplayValue::new(632.5, DisplayUnit::Hertz))
+            .unwrap();
+        assert!((mid - 0.5).abs() < 1e-3);
+
+        let json = r#"{"profiles": [{"name": "Pro-Q", "matches": ["proq"], "roles": ["eq"], "eq_bands": {}}]}"#;
+        let file = parse_profile_file(json, false).unwrap();
+        assert_eq!(file.profiles[0].eq_bands.as_ref().unwrap().freq, "freq");
+
+        // Typos are rejected instead of being silently ignored.
+        assert!(
+            parse_profile_file(r#"{"name": "X", "matches": ["x"], "role": ["amp"]}"#, false)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn user_profiles_take_precedence() {
+        let dir = std::env::temp_dir().join(format!("toneforge-profiles-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("my-eq.json"),
+            r#"{"name": "My ReaEQ", "matches": ["reaeq"], "roles": ["eq"], "params": {"air": ["Band 4 Gain"]}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("broken.toml"), "name = ").unwrap();
+
+        let mut registry = PluginProfileRegistry::builtin();
+        let warnings = registry.load_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("broken.toml"));
+        let profile = registry.profile_for("ReaEQ (Cockos)").unwrap();
+        assert_eq!(profile.name, "My ReaEQ");
+        assert!(profile.source.is_some());
+    }
+}
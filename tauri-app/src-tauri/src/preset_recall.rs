@@ -0,0 +1,159 @@
+//! Preset Recall
+//!
+//! Rebuilds a `TonePreset` on any REAPER track:
+//! 1. load plugins the track is missing
+//! 2. reorder the chain to match the preset
+//! 3. set enable states and parameters
+//!
+//! Every change is recorded as a single undo action.
+
+use crate::act_mode::collect_reaper_snapshot;
+use crate::reaper_client::ReaperClient;
+use crate::tone_presets::{missing_plugins, plan_reorder, plan_settings, TonePreset};
+use serde::Serialize;
+use toneforge_core::UndoManager;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RecallReport {
+    pub preset_id: String,
+    pub track: i32,
+    pub loaded: usize,
+    pub moved: usize,
+    pub toggled: usize,
+    pub params_set: usize,
+    pub logs: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+pub async fn recall_preset(
+    reaper: &ReaperClient,
+    undo_manager: &mut UndoManager,
+    preset: &TonePreset,
+    track: i32,
+) -> Result<RecallReport, String> {
+    let mut report = RecallReport {
+        preset_id: preset.id.clone(),
+        track,
+        ..Default::default()
+    };
+
+    undo_manager.begin_action(&format!("Recall preset: {}", preset.name));
+    let result = run_recall(reaper, undo_manager, preset, track, &mut report).await;
+
+    // Commit whatever was applied so a partial recall can still be undone.
+    if let Some(action_id) = undo_manager.commit_action() {
+        println!("[UNDO] Recorded action: {}", action_id);
+    }
+
+    result.map(|_| report)
+}
+
+async fn run_recall(
+    reaper: &ReaperClient,
+    undo_manager: &mut UndoManager,
+    preset: &TonePreset,
+    track: i32,
+    report: &mut RecallReport,
+) -> Result<(), String> {
+    // 1. Loads
+    let live = collect_reaper_snapshot(reaper, track)
+        .await
+        .map_err(|e| format!("Failed to get REAPER state: {}", e))?;
+
+    for plugin_name in missing_plugins(preset, &live) {
+        match reaper.add_plugin(track, &plugin_name).await {
+            Ok(slot) => {
+                undo_manager.record_plugin_change(track, slot, &plugin_name, true);
+                report.loaded += 1;
+                report.logs.push(format!("✓ Loaded '{}' at slot {}", plugin_name, slot));
+            }
+            Err(e) => report
+                .warnings
+                .push(format!("Failed to load '{}': {}", plugin_name, e)),
+        }
+    }
+
+    // 2. Reorder
+    let live = collect_reaper_snapshot(reaper, track)
+        .await
+        .map_err(|e| format!("Failed to refresh REAPER state: {}", e))?;
+
+    for (from, to) in plan_reorder(preset, &live) {
+        match reaper.move_fx(track, from, to).await {
+            Ok(true) => {
+                undo_manager.record_fx_move(track, from, to);
+                report.moved += 1;
+                report.logs.push(format!("✓ Moved FX {} -> {}", from, to));
+            }
+            result => {
+                // Later moves are relative to this one; stop reordering rather than scramble.
+                let error = match result {
+                    Err(e) => e.to_string(),
+                    _ => "REAPER rejected the move".to_string(),
+                };
+                report
+                    .warnings
+                    .push(format!("Failed to move FX {} -> {}: {}; reorder stopped", from, to, error));
+                break;
+            }
+        }
+    }
+
+    // 3. Enable states + parameters
+    let live = collect_reaper_snapshot(reaper, track)
+        .await
+        .map_err(|e| format!("Failed to refresh REAPER state: {}", e))?;
+    let settings = plan_settings(preset, &live);
+
+    for toggle in &settings.toggles {
+        match reaper.set_fx_enabled(track, toggle.fx_index, toggle.enabled).await {
+            Ok(_) => {
+                undo_manager.record_fx_toggle(track, toggle.fx_index, &toggle.fx_name, toggle.was_enabled);
+                report.toggled += 1;
+                report.logs.push(format!(
+                    "✓ {} '{}'",
+                    if toggle.enabled { "Enabled" } else { "Bypassed" },
+                    toggle.fx_name
+                ));
+            }
+            Err(e) => report
+                .warnings
+                .push(format!("Failed to toggle '{}': {}", toggle.fx_name, e)),
+        }
+    }
+
+    for write in &settings.params {
+        match reaper
+            .set_param_by_index(track, write.fx_index, write.param_index, write.new_value)
+            .await
+        {
+            Ok(()) => {
+                undo_manager.record_param_change(
+                    track,
+                    write.fx_index,
+                    &write.fx_name,
+                    write.param_index,
+                    &write.param_name,
+                    write.old_value,
+                    write.new_value,
+                );
+                report.params_set += 1;
+            }
+            Err(e) => report.warnings.push(format!(
+                "Failed to set {} :: {}: {}",
+                write.fx_name, write.param_name, e
+            )),
+        }
+    }
+    if report.params_set > 0 {
+        report.logs.push(format!("✓ Set {} parameter(s)", report.params_set));
+    }
+
+    for missing in settings.unmatched_params {
+        report
+            .warnings
+            .push(format!("Preset parameter not found on live plugin: {}", missing));
+    }
+
+    Ok(())
+}
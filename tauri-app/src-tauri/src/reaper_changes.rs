@@ -0,0 +1,251 @@
+//! REAPER Change Feed
+//!
+//! Types for the extension's long-poll `GET /changes` feed and the revision counters that
+//! tell whether a snapshot taken earlier is still current. Every change bumps a global
+//! counter and stamps the affected track/FX with it; a snapshot remembers the revision it
+//! was taken at and is stale once anything it covers has a newer stamp.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// One change reported by the extension (or the mock).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReaperChange {
+    pub seq: u64,
+    /// "param", "enabled", "chain" (FX added/removed/moved) or "tracks".
+    pub kind: String,
+    #[serde(default)]
+    pub track: Option<i32>,
+    #[serde(default)]
+    pub fx: Option<i32>,
+    #[serde(default)]
+    pub param: Option<i32>,
+    #[serde(default)]
+    pub value: Option<f64>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+impl ReaperChange {
+    /// Changes that shift FX indices or tracks, not just values.
+    pub fn is_structural(&self) -> bool {
+        matches!(self.kind.as_str(), "chain" | "tracks") || self.track.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangeBatch {
+    pub seq: u64,
+    #[serde(default)]
+    pub changes: Vec<ReaperChange>,
+    /// The client fell behind the extension's buffer; everything must be refreshed.
+    #[serde(default)]
+    pub reset: bool,
+}
+
+/// Payload of the `toneforge:reaper_changed` event.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ReaperChangedEvent {
+    pub seq: u64,
+    /// Tracks with any change, ascending.
+    pub tracks: Vec<i32>,
+    /// Tracks whose FX chain changed shape (indices are no longer valid).
+    pub structural_tracks: Vec<i32>,
+    /// Everything should be treated as changed (reset, track list changed, feed restarted).
+    pub all_tracks: bool,
+    pub changes: Vec<ReaperChange>,
+}
+
+impl ChangeBatch {
+    /// Summarize the batch for the UI/caches. `None` when nothing changed.
+    pub fn summarize(self) -> Option<ReaperChangedEvent> {
+        if self.changes.is_empty() && !self.reset {
+            return None;
+        }
+
+        let mut tracks = BTreeSet::new();
+        let mut structural = BTreeSet::new();
+        let mut all_tracks = self.reset;
+        for change in &self.changes {
+            match change.track {
+                Some(track) => {
+                    tracks.insert(track);
+                    if change.is_structural() {
+                        structural.insert(track);
+                    }
+                }
+                None => all_tracks = true,
+            }
+        }
+
+        Some(ReaperChangedEvent {
+            seq: self.seq,
+            tracks: tracks.into_iter().collect(),
+            structural_tracks: structural.into_iter().collect(),
+            all_tracks,
+            changes: self.changes,
+        })
+    }
+}
+
+/// Change stamps per track and per FX slot.
+#[derive(Debug, Default)]
+pub struct SnapshotRevisions {
+    current: u64,
+    all: u64,
+    tracks: HashMap<i32, u64>,
+    fx: HashMap<(i32, i32), u64>,
+    /// The change feed is connected; without it outside edits go unnoticed.
+    feed_live: bool,
+}
+
+impl SnapshotRevisions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Revision to remember alongside a snapshot taken now.
+    pub fn current(&self) -> u64 {
+        self.current
+    }
+
+    fn bump(&mut self) -> u64 {
+        self.current += 1;
+        self.current
+    }
+
+    /// Last revision at which anything on `track` changed shape (chain or track list).
+    pub fn track_revision(&self, track: i32) -> u64 {
+        self.tracks.get(&track).copied().unwrap_or(0).max(self.all)
+    }
+
+    /// Last revision at which anything about `fx` on `track` changed.
+    pub fn fx_revision(&self, track: i32, fx: i32) -> u64 {
+        self.fx
+            .get(&(track, fx))
+            .copied()
+            .unwrap_or(0)
+            .max(self.track_revision(track))
+    }
+
+    /// True if a snapshot of `track` taken at `seen` no longer matches REAPER.
+    pub fn is_track_stale(&self, track: i32, seen: u64) -> bool {
+        self.track_revision(track) > seen
+            || self
+                .fx
+                .iter()
+                .any(|(&(t, _), &rev)| t == track && rev > seen)
+    }
+
+    pub fn is_fx_stale(&self, track: i32, fx: i32, seen: u64) -> bool {
+        self.fx_revision(track, fx) > seen
+    }
+
+    /// A value on one plugin changed (our own write or a knob turned in REAPER).
+    pub fn mark_fx(&mut self, track: i32, fx: i32) {
+        let rev = self.bump();
+        self.fx.insert((track, fx), rev);
+    }
+
+    /// The chain of `track` changed shape; every FX on it is stale.
+    pub fn mark_track(&mut self, track: i32) {
+        let rev = self.bump();
+        self.tracks.insert(track, rev);
+    }
+
+    pub fn mark_all(&mut self) {
+        self.all = self.bump();
+    }
+
+    /// Only while the feed is live do the stamps cover edits made in REAPER itself.
+    pub fn is_feed_live(&self) -> bool {
+        self.feed_live
+    }
+
+    /// Report the feed's state. While it is down everything is marked changed, so
+    /// nothing cached before or during the outage is trusted afterwards.
+    pub fn set_feed_live(&mut self, live: bool) {
+        if !live {
+            self.mark_all();
+        }
+        self.feed_live = live;
+    }
+
+    pub fn apply(&mut self, event: &ReaperChangedEvent) {
+        if event.all_tracks {
+            self.mark_all();
+        }
+        for &track in &event.structural_tracks {
+            self.mark_track(track);
+        }
+        for change in &event.changes {
+            if let (Some(track), Some(fx)) = (change.track, change.fx) {
+                if !change.is_structural() {
+                    self.mark_fx(track, fx);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(seq: u64, kind: &str, track: i32, fx: Option<i32>) -> ReaperChange {
+        ReaperChange {
+            seq,
+            kind: kind.to_string(),
+            track: Some(track),
+            fx,
+            param: None,
+            value: None,
+            enabled: None,
+        }
+    }
+
+    #[test]
+    fn batch_summary_groups_tracks() {
+        let batch = ChangeBatch {
+            seq: 3,
+            changes: vec![
+                change(1, "param", 2, Some(0)),
+                change(2, "chain", 0, None),
+                change(3, "enabled", 2, Some(1)),
+            ],
+            reset: false,
+        };
+        let event = batch.summarize().unwrap();
+        assert_eq!(event.tracks, vec![0, 2]);
+        assert_eq!(event.structural_tracks, vec![0]);
+        assert!(!event.all_tracks);
+
+        let empty = ChangeBatch {
+            seq: 3,
+            changes: vec![],
+            reset: false,
+        };
+        assert!(empty.summarize().is_none());
+    }
+
+    #[test]
+    fn revisions_mark_snapshots_stale() {
+        let mut revisions = SnapshotRevisions::new();
+        let seen = revisions.current();
+
+        revisions.mark_fx(0, 1);
+        assert!(revisions.is_fx_stale(0, 1, seen));
+        assert!(!revisions.is_fx_stale(0, 0, seen));
+        assert!(revisions.is_track_stale(0, seen));
+        assert!(!revisions.is_track_stale(1, seen));
+
+        let seen = revisions.current();
+        revisions.mark_track(1);
+        assert!(revisions.is_fx_stale(1, 5, seen));
+        assert!(!revisions.is_track_stale(0, seen));
+
+        let seen = revisions.current();
+        revisions.mark_all();
+        assert!(revisions.is_track_stale(7, seen));
+    }
+}
@@ -3,6 +3,10 @@ use reqwest;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::error::Error;
+use std::sync::{Arc, Mutex};
+use crate::param_curve::{parse_display, probe_points, ParamCurve, ParamCurves, CURVE_PROBE_STEPS};
+use crate::gain_staging::MeterReading;
+use crate::reaper_changes::ChangeBatch;
 
 fn normalize_param_token(text: &str) -> String {
     text.to_lowercase()
@@ -15,6 +19,8 @@ fn normalize_param_token(text: &str) -> String {
 pub struct ReaperClient {
     base_url: String,
     client: reqwest::Client,
+    /// Plugin bazında öğrenilmiş değer->görüntü eğrileri (klonlar arasında paylaşılır)
+    curves: Arc<Mutex<ParamCurves>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -55,11 +61,29 @@ pub struct FXParamSnapshot {
     pub params: Vec<FXParamEntry>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ParamProbeSample {
+    pub value: f64,
+    pub display: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ParamProbe {
+    pub param_index: i32,
+    pub samples: Vec<ParamProbeSample>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ParamProbeResponse {
+    params: Vec<ParamProbe>,
+}
+
 impl ReaperClient {
     pub fn new() -> Self {
         Self {
             base_url: "http://127.0.0.1:8888".to_string(),
             client: reqwest::Client::new(),
+            curves: Arc::new(Mutex::new(ParamCurves::new())),
         }
     }
 
@@ -242,6 +266,225 @@ impl ReaperClient {
         Ok(json["success"].as_bool().unwrap_or(false))
     }
 
+    /// Plugin'e isimli ayar gönder (TrackFX_SetNamedConfigParm, ör. IR dosyası)
+    pub async fn set_fx_named_config(
+        &self,
+        track: i32,
+        fx: i32,
+        key: &str,
+        value: &str,
+    ) -> Result<bool, Box<dyn Error>> {
+        let response = self
+            .client
+            .post(&format!("{}/fx/named_config", self.base_url))
+            .json(&json!({
+                "track": track,
+                "fx": fx,
+                "key": key,
+                "value": value
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(format!("Failed to set FX named config: {}", error_text).into());
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        Ok(json["success"].as_bool().unwrap_or(false))
+    }
+
+    /// FX'leri tek bir container'a taşı (parallel: her biri bir öncekiyle paralel çalışır)
+    pub async fn create_fx_container(
+        &self,
+        track: i32,
+        fx: &[i32],
+        parallel: bool,
+    ) -> Result<i32, Box<dyn Error>> {
+        let response = self
+            .client
+            .post(&format!("{}/fx/container", self.base_url))
+            .json(&json!({
+                "track": track,
+                "fx": fx,
+                "parallel": parallel
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(format!("Failed to create FX container: {}", error_text).into());
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let container = json["container_fx"].as_i64().ok_or("Invalid response")?;
+        let container = i32::try_from(container)
+            .map_err(|_| format!("FX index {} out of i32 range", container))?;
+        Ok(container)
+    }
+
+    /// Projenin sonuna yeni track ekle, index'ini döndür
+    pub async fn add_track(&self, name: &str) -> Result<i32, Box<dyn Error>> {
+        let response = self
+            .client
+            .post(&format!("{}/track/add", self.base_url))
+            .json(&json!({ "name": name }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(format!("Failed to add track: {}", error_text).into());
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let track = json["track"].as_i64().ok_or("Invalid response")?;
+        let track = i32::try_from(track)
+            .map_err(|_| format!("Track index {} out of i32 range", track))?;
+        Ok(track)
+    }
+
+    /// Track'i sil
+    pub async fn remove_track(&self, track: i32) -> Result<(), Box<dyn Error>> {
+        let response = self
+            .client
+            .post(&format!("{}/track/remove", self.base_url))
+            .json(&json!({ "track": track }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(format!("Failed to remove track: {}", error_text).into());
+        }
+
+        Ok(())
+    }
+
+    /// Track'in state chunk'ını al (REAPER'ın RPP'ye yazdığı `<TRACK>` bloğu, plugin
+    /// state'leri dahil)
+    pub async fn get_track_chunk(&self, track: i32) -> Result<String, Box<dyn Error>> {
+        let response = self
+            .client
+            .get(&format!("{}/track/chunk", self.base_url))
+            .query(&[("track", track)])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(format!("Failed to get track chunk: {}", error_text).into());
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        Ok(json["chunk"].as_str().ok_or("Invalid response")?.to_string())
+    }
+
+    /// Track'ten başka bir track'e send oluştur (dB ve pan -1..1)
+    pub async fn add_track_send(
+        &self,
+        track: i32,
+        dest: i32,
+        volume_db: f64,
+        pan: f64,
+    ) -> Result<i32, Box<dyn Error>> {
+        let response = self
+            .client
+            .post(&format!("{}/track/send", self.base_url))
+            .json(&json!({
+                "track": track,
+                "dest": dest,
+                "volume_db": volume_db,
+                "pan": pan
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(format!("Failed to add track send: {}", error_text).into());
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let send = json["send"].as_i64().ok_or("Invalid response")?;
+        let send = i32::try_from(send)
+            .map_err(|_| format!("Send index {} out of i32 range", send))?;
+        Ok(send)
+    }
+
+    /// Track volume (dB) / pan (-1..1) ayarla; None olan değişmez
+    pub async fn set_track_mix(
+        &self,
+        track: i32,
+        volume_db: Option<f64>,
+        pan: Option<f64>,
+    ) -> Result<bool, Box<dyn Error>> {
+        let response = self
+            .client
+            .post(&format!("{}/track/mix", self.base_url))
+            .json(&json!({
+                "track": track,
+                "volume_db": volume_db,
+                "pan": pan
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(format!("Failed to set track mix: {}", error_text).into());
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        Ok(json["success"].as_bool().unwrap_or(false))
+    }
+
+    /// Track'in güncel volume (dB) ve pan (-1..1) değerlerini al
+    pub async fn get_track_mix(&self, track: i32) -> Result<(f64, f64), Box<dyn Error>> {
+        let response = self
+            .client
+            .get(&format!("{}/track/mix", self.base_url))
+            .query(&[("track", track)])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(format!("Failed to get track mix: {}", error_text).into());
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let volume_db = json["volume_db"].as_f64().ok_or("Invalid response")?;
+        Ok((volume_db, json["pan"].as_f64().unwrap_or(0.0)))
+    }
+
+    /// Track meter'ını `window_ms` boyunca oku (peak/RMS, dBFS, fader hariç). Transport
+    /// duruyorsa edit cursor'dan çalınır ve sonra durdurulur, böylece her ölçüm aynı
+    /// referans bölümünü dinler.
+    pub async fn get_track_meter(
+        &self,
+        track: i32,
+        window_ms: u64,
+    ) -> Result<MeterReading, Box<dyn Error>> {
+        let response = self
+            .client
+            .get(&format!("{}/track/meter", self.base_url))
+            .query(&[("track", track as i64), ("window_ms", window_ms as i64)])
+            .timeout(std::time::Duration::from_millis(window_ms + 5_000))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(format!("Failed to read track meter: {}", error_text).into());
+        }
+
+        let reading: MeterReading = response.json().await?;
+        Ok(reading)
+    }
+
     /// Kurulu FX kataloğunu al (param meta dahil)
     pub async fn get_fx_catalog(&self, refresh: bool) -> Result<serde_json::Value, Box<dyn Error>> {
         let response = self
@@ -374,6 +617,45 @@ impl ReaperClient {
         Ok(())
     }
 
+    /// Aktif projenin .RPP yolu (kaydedilmemiş projede boş string)
+    pub async fn get_project_path(&self) -> Result<String, Box<dyn Error>> {
+        let response = self
+            .client
+            .get(&format!("{}/project/info", self.base_url))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err("Failed to get project info".into());
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let path = json["project_path"].as_str().unwrap_or("").to_string();
+        Ok(path)
+    }
+
+    /// Değişiklik akışını bekle (long-poll): `since`'ten sonraki olaylar ya da timeout
+    pub async fn poll_changes(
+        &self,
+        since: u64,
+        timeout_ms: u64,
+    ) -> Result<ChangeBatch, Box<dyn Error>> {
+        let response = self
+            .client
+            .get(&format!("{}/changes", self.base_url))
+            .query(&[("since", since), ("timeout_ms", timeout_ms)])
+            .timeout(std::time::Duration::from_millis(timeout_ms + 5_000))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err("Failed to poll changes".into());
+        }
+
+        let batch: ChangeBatch = response.json().await?;
+        Ok(batch)
+    }
+
     /// Parametre snapshot al
     pub async fn get_fx_params(
         &self,
@@ -395,6 +677,106 @@ impl ReaperClient {
         Ok(snapshot)
     }
 
+    /// Parametreyi değiştirmeden, verilen normalize değerlerde gösterilecek metni sor
+    pub async fn probe_param_displays(
+        &self,
+        track: i32,
+        fx: i32,
+        params: &[i32],
+        values: &[f64],
+    ) -> Result<Vec<ParamProbe>, Box<dyn Error>> {
+        let join = |items: Vec<String>| items.join(",");
+        let response = self
+            .client
+            .get(&format!("{}/fx/param_format", self.base_url))
+            .query(&[
+                ("track", track.to_string()),
+                ("fx", fx.to_string()),
+                ("params", join(params.iter().map(|p| p.to_string()).collect())),
+                ("values", join(values.iter().map(|v| format!("{:.6}", v)).collect())),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(format!("Failed to probe parameter displays: {}", error_text).into());
+        }
+
+        let probe: ParamProbeResponse = response.json().await?;
+        Ok(probe.params)
+    }
+
+    /// Öğrenilmiş eğrilerin kopyası (ChainMapper'a vermek için)
+    pub fn learned_curves(&self) -> ParamCurves {
+        self.curves.lock().unwrap().clone()
+    }
+
+    /// Eksik eğrileri öğren (plugin adına göre önbelleklenir). Sayısal olmayan ya da
+    /// monoton olmayan parametreler atlanır; öğrenilen parametre indeksleri döner.
+    pub async fn learn_param_curves(
+        &self,
+        track: i32,
+        fx: i32,
+        plugin_name: &str,
+        params: &[i32],
+    ) -> Result<Vec<i32>, Box<dyn Error>> {
+        let missing: Vec<i32> = {
+            let curves = self.curves.lock().unwrap();
+            params
+                .iter()
+                .copied()
+                .filter(|&p| curves.get(plugin_name, p).is_none())
+                .collect()
+        };
+        if !missing.is_empty() {
+            let probes = self
+                .probe_param_displays(track, fx, &missing, &probe_points(CURVE_PROBE_STEPS))
+                .await?;
+            let mut curves = self.curves.lock().unwrap();
+            for probe in probes {
+                let samples: Vec<(f64, String)> = probe
+                    .samples
+                    .into_iter()
+                    .map(|s| (s.value, s.display))
+                    .collect();
+                if let Some(curve) = ParamCurve::from_samples(&samples) {
+                    curves.insert(plugin_name, probe.param_index, curve);
+                }
+            }
+        }
+
+        let curves = self.curves.lock().unwrap();
+        Ok(params
+            .iter()
+            .copied()
+            .filter(|&p| curves.get(plugin_name, p).is_some())
+            .collect())
+    }
+
+    /// "-42 dB", "375 ms", "2.5 kHz" gibi bir hedefi öğrenilmiş eğriyle normalize değere çevir
+    pub async fn normalized_for_display(
+        &self,
+        track: i32,
+        fx: i32,
+        plugin_name: &str,
+        param_index: i32,
+        target: &str,
+    ) -> Result<f64, Box<dyn Error>> {
+        let target = parse_display(target)
+            .ok_or_else(|| format!("Cannot parse '{}' as a value with a unit", target))?;
+        self.learn_param_curves(track, fx, plugin_name, &[param_index])
+            .await?;
+        let curves = self.curves.lock().unwrap();
+        let curve = curves.get(plugin_name, param_index).ok_or_else(|| {
+            format!(
+                "Parameter {} of '{}' has no numeric display curve",
+                param_index, plugin_name
+            )
+        })?;
+        Ok(curve.normalized_for_target(&target)?)
+    }
+
     pub fn find_param_entry<'a>(
         &self,
         params: &'a [FXParamEntry],
@@ -407,6 +789,41 @@ impl ReaperClient {
     }
 }
 
+impl toneforge_core::UndoBackend for ReaperClient {
+    async fn set_param(&self, track: i32, fx_index: i32, param_index: i32, value: f64) -> Result<(), String> {
+        self.set_param_by_index(track, fx_index, param_index, value)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn set_fx_enabled(&self, track: i32, fx_index: i32, enabled: bool) -> Result<(), String> {
+        ReaperClient::set_fx_enabled(self, track, fx_index, enabled)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn move_fx(&self, track: i32, from: i32, to: i32) -> Result<(), String> {
+        match ReaperClient::move_fx(self, track, from, to).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(format!("REAPER rejected move {} -> {}", from, to)),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    async fn add_plugin(&self, track: i32, plugin_name: &str) -> Result<i32, String> {
+        ReaperClient::add_plugin(self, track, plugin_name)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn remove_plugin(&self, track: i32, fx_index: i32) -> Result<(), String> {
+        ReaperClient::remove_plugin(self, track, fx_index)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,485 @@
+//! Relative Edits
+//!
+//! "more mids", "less fizz", "tighter low end": instead of generating a whole new tone,
+//! the adjustments parsed into a `ToneIntent` become small moves on what the chain already
+//! has. Each descriptor's vocabulary entry lists EQ moves (a step on the enabled band
+//! nearest its frequency) and knob moves (the amp's tone stack or gain, the reverb/delay
+//! mix); "less" reverses them and "a bit" / "much" scale them. Nothing else in the chain
+//! is touched.
+
+use crate::eq_model::EqFilterType;
+use crate::param_curve::{parse_display, DisplayUnit, ParamCurves};
+use crate::parameter_ai::{ParameterAction, ReaperParameter, ReaperPlugin, ReaperSnapshot};
+use crate::plugin_profile::{normalize_token, EqBandLayout, EqBandSlot, PluginProfileRegistry};
+use crate::tone_intent::{Adjustment, Direction, ToneIntent};
+use crate::tone_vocabulary::ToneVocabulary;
+use std::collections::{BTreeMap, HashMap};
+
+/// dB per EQ step at normal amount.
+pub const EQ_STEP_DB: f64 = 3.0;
+/// Normalized knob travel per step at normal amount.
+pub const KNOB_STEP: f64 = 0.1;
+/// EQ bands further than this from a descriptor's frequency are not used.
+const MAX_BAND_DISTANCE_OCT: f64 = 1.0;
+
+/// Whether a request only adjusts the current tone: every adjustment is a vocabulary
+/// word with moves, and nothing asks for a different chain (artists, forbidden sections,
+/// sections beyond the ones being adjusted).
+pub fn is_relative_request(intent: &ToneIntent, vocabulary: &ToneVocabulary) -> bool {
+    let targets = intent.target_sections();
+    !intent.adjustments.is_empty()
+        && intent.artists.is_empty()
+        && intent.forbidden.is_empty()
+        && !intent.no_effects
+        && intent.requested.iter().all(|s| targets.contains(s))
+        && intent
+            .adjustments
+            .iter()
+            .all(|a| vocabulary.lookup(&a.term).is_some_and(|t| t.has_moves()))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RelativeEditPlan {
+    pub actions: Vec<ParameterAction>,
+    pub warnings: Vec<String>,
+}
+
+/// Parameter values the plan has set so far, so moves on the same parameter add up.
+struct Pending {
+    track: i32,
+    /// (plugin index, param index) -> position in `actions`.
+    slots: HashMap<(i32, i32), usize>,
+    actions: Vec<ParameterAction>,
+}
+
+impl Pending {
+    fn current(&self, plugin: &ReaperPlugin, param: &ReaperParameter) -> f64 {
+        match self.slots.get(&(plugin.index, param.index)) {
+            Some(&i) => match &self.actions[i] {
+                ParameterAction::SetParameter { value, .. } => *value,
+                _ => param.current_value,
+            },
+            None => param.current_value,
+        }
+    }
+
+    fn set(&mut self, plugin: &ReaperPlugin, param: &ReaperParameter, value: f64, reason: String) {
+        let action = ParameterAction::SetParameter {
+            track: self.track,
+            plugin_index: plugin.index,
+            param_index: param.index,
+            param_name: param.name.clone(),
+            value,
+            reason,
+        };
+        match self.slots.get(&(plugin.index, param.index)) {
+            Some(&i) => self.actions[i] = action,
+            None => {
+                self.slots
+                    .insert((plugin.index, param.index), self.actions.len());
+                self.actions.push(action);
+            }
+        }
+    }
+}
+
+/// Plan the moves for every adjustment in `intent` against the current chain.
+pub fn plan_relative_edit(
+    intent: &ToneIntent,
+    snapshot: &ReaperSnapshot,
+    profiles: &PluginProfileRegistry,
+    curves: &ParamCurves,
+    vocabulary: &ToneVocabulary,
+) -> RelativeEditPlan {
+    let mut pending = Pending {
+        track: snapshot.track_index,
+        slots: HashMap::new(),
+        actions: Vec::new(),
+    };
+    let mut warnings = Vec::new();
+    let eq = find_eq(snapshot, profiles);
+
+    for adjustment in &intent.adjustments {
+        let Some(term) = vocabulary.lookup(&adjustment.term) else {
+            warnings.push(format!(
+                "'{}' is not a known tone descriptor; skipped",
+                adjustment.term
+            ));
+            continue;
+        };
+        let sign = match adjustment.direction {
+            Direction::More => 1.0,
+            Direction::Less => -1.0,
+        };
+        let scale = sign * adjustment.amount.scale();
+        let mut results = Vec::new();
+        for m in &term.eq {
+            results.push(match &eq {
+                Some((plugin, layout)) => move_eq_band(
+                    plugin,
+                    layout,
+                    curves,
+                    m.freq_hz,
+                    m.steps * scale * EQ_STEP_DB,
+                    adjustment,
+                    &mut pending,
+                ),
+                None => Err(format!(
+                    "no EQ with a known band layout for {:.0} Hz",
+                    m.freq_hz
+                )),
+            });
+        }
+        for m in &term.knobs {
+            results.push(move_knob(
+                snapshot,
+                profiles,
+                &m.role,
+                &m.key,
+                m.steps * scale * KNOB_STEP,
+                adjustment,
+                &mut pending,
+            ));
+        }
+        let mut applied = false;
+        for result in results {
+            match result {
+                Ok(()) => applied = true,
+                Err(e) => warnings.push(format!("{}: {}", adjustment.describe(), e)),
+            }
+        }
+        if !applied {
+            warnings.push(format!(
+                "{}: nothing in the chain to adjust",
+                adjustment.describe()
+            ));
+        }
+    }
+
+    RelativeEditPlan {
+        actions: pending.actions,
+        warnings,
+    }
+}
+
+/// The best enabled EQ plugin whose profile declares a band layout.
+fn find_eq<'a>(
+    snapshot: &'a ReaperSnapshot,
+    profiles: &'a PluginProfileRegistry,
+) -> Option<(&'a ReaperPlugin, &'a EqBandLayout)> {
+    snapshot
+        .plugins
+        .iter()
+        .filter(|p| p.enabled)
+        .filter_map(|p| {
+            let layout = profiles.profile_for(&p.name)?.eq_bands.as_ref()?;
+            Some((profiles.role_score(&p.name, "eq"), p, layout))
+        })
+        .filter(|(score, _, _)| *score > 0)
+        .max_by_key(|(score, p, _)| (*score, -p.index))
+        .map(|(_, p, layout)| (p, layout))
+}
+
+/// Current value of a parameter in `unit`: learned/declared curve, else its display text.
+fn current_display(
+    curves: &ParamCurves,
+    plugin: &ReaperPlugin,
+    param: &ReaperParameter,
+    normalized: f64,
+    unit: DisplayUnit,
+) -> Option<f64> {
+    if let Some(curve) = curves
+        .get(&plugin.name, param.index)
+        .filter(|c| c.unit == unit)
+    {
+        return Some(curve.value_at(normalized));
+    }
+    // The display string is only current if nothing moved the parameter yet.
+    if (normalized - param.current_value).abs() > 1e-9 {
+        return None;
+    }
+    parse_display(&format!("{} {}", param.display_value, param.unit))
+        .or_else(|| parse_display(&param.display_value))
+        .filter(|v| v.unit == unit)
+        .map(|v| v.value)
+}
+
+fn move_eq_band(
+    plugin: &ReaperPlugin,
+    layout: &EqBandLayout,
+    curves: &ParamCurves,
+    freq_hz: f64,
+    delta_db: f64,
+    adjustment: &Adjustment,
+    pending: &mut Pending,
+) -> Result<(), String> {
+    // number -> (freq, gain, type, enable)
+    let mut bands: BTreeMap<i32, [Option<&ReaperParameter>; 4]> = BTreeMap::new();
+    for p in &plugin.parameters {
+        let Some((number, slot)) = layout.band_slot(&p.name) else {
+            continue;
+        };
+        let i = match slot {
+            EqBandSlot::Freq => 0,
+            EqBandSlot::Gain => 1,
+            EqBandSlot::Type => 2,
+            EqBandSlot::Enable => 3,
+            _ => continue,
+        };
+        bands.entry(number).or_default()[i].get_or_insert(p);
+    }
+
+    let band_type = |number: i32, type_param: Option<&ReaperParameter>| {
+        layout.fixed_type(number).or_else(|| {
+            let value = type_param?.current_value;
+            layout
+                .types
+                .iter()
+                .find(|(_, v)| (**v - value).abs() < 1e-3)
+                .map(|(t, _)| *t)
+        })
+    };
+    let nearest = bands
+        .iter()
+        .filter_map(|(number, [freq, gain, filter, enable])| {
+            let (freq, gain) = ((*freq)?, (*gain)?);
+            if enable.is_some_and(|e| e.current_value < 0.5)
+                || !band_type(*number, *filter)
+                    .unwrap_or(EqFilterType::Peak)
+                    .has_gain()
+            {
+                return None;
+            }
+            let hz = current_display(curves, plugin, freq, freq.current_value, DisplayUnit::Hertz)?;
+            let distance = (hz / freq_hz).log2().abs();
+            (distance <= MAX_BAND_DISTANCE_OCT).then_some((distance, *number, gain))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+    let Some((_, number, gain)) = nearest else {
+        return Err(format!(
+            "'{}' has no enabled band near {:.0} Hz",
+            plugin.name, freq_hz
+        ));
+    };
+
+    let normalized = pending.current(plugin, gain);
+    let range = layout.gain_range_db;
+    let curve = curves
+        .get(&plugin.name, gain.index)
+        .filter(|c| c.unit == DisplayUnit::Decibel);
+    let current_db = current_display(curves, plugin, gain, normalized, DisplayUnit::Decibel)
+        .unwrap_or(normalized * 2.0 * range - range);
+    let target_db = (current_db + delta_db).clamp(-range, range);
+    let value = match curve {
+        Some(curve) => curve.normalized_for(target_db),
+        None => (target_db + range) / (2.0 * range),
+    };
+    pending.set(
+        plugin,
+        gain,
+        value,
+        format!(
+            "{} :: band {} gain {:+.1} -> {:+.1} dB",
+            adjustment.describe(),
+            number,
+            current_db,
+            target_db
+        ),
+    );
+    Ok(())
+}
+
+/// Control for a tone key: the profile's declared parameter, else a synonym by name.
+fn knob_param<'a>(
+    profiles: &PluginProfileRegistry,
+    plugin: &'a ReaperPlugin,
+    key: &str,
+) -> Option<&'a ReaperParameter> {
+    profiles
+        .profile_for(&plugin.name)
+        .and_then(|profile| profile.param_for(plugin, key))
+        .or_else(|| {
+            profiles.synonyms(key).iter().find_map(|synonym| {
+                plugin
+                    .parameters
+                    .iter()
+                    .find(|p| normalize_token(&p.name) == *synonym)
+            })
+        })
+}
+
+fn move_knob(
+    snapshot: &ReaperSnapshot,
+    profiles: &PluginProfileRegistry,
+    role: &str,
+    key: &str,
+    delta: f64,
+    adjustment: &Adjustment,
+    pending: &mut Pending,
+) -> Result<(), String> {
+    let (plugin, param) = snapshot
+        .plugins
+        .iter()
+        .filter(|p| p.enabled)
+        .filter_map(|p| {
+            let score = profiles.role_score(&p.name, role);
+            if score <= 0 {
+                return None;
+            }
+            knob_param(profiles, p, key).map(|param| (score, p, param))
+        })
+        .max_by_key(|(score, p, _)| (*score, -p.index))
+        .map(|(_, p, param)| (p, param))
+        .ok_or_else(|| format!("no {} plugin with a '{}' control", role, key))?;
+
+    let before = pending.current(plugin, param);
+    let value = (before + delta).clamp(0.0, 1.0);
+    if (value - before).abs() < 1e-9 {
+        return Err(format!(
+            "'{}' {} is already at its limit",
+            plugin.name, param.name
+        ));
+    }
+    pending.set(
+        plugin,
+        param,
+        value,
+        format!(
+            "{} :: {} {:.0}% -> {:.0}%",
+            adjustment.describe(),
+            param.name,
+            before * 100.0,
+            value * 100.0
+        ),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{self as fixtures, param_with_display as param, plugin};
+
+    fn snapshot() -> ReaperSnapshot {
+        fixtures::snapshot(vec![
+            plugin(
+                0,
+                "VST3: Amp Sim",
+                true,
+                vec![
+                    param(0, "Gain", 0.6, "6.0"),
+                    param(1, "Bass", 0.5, "5.0"),
+                    param(2, "Mid", 0.5, "5.0"),
+                    param(3, "Treble", 0.98, "9.8"),
+                    param(4, "Presence", 0.5, "5.0"),
+                ],
+            ),
+            plugin(
+                1,
+                "VST: ReaEQ (Cockos)",
+                true,
+                vec![
+                    param(0, "Band 1 Freq", 0.2, "120 Hz"),
+                    param(1, "Band 1 Gain", 0.5, "0.0 dB"),
+                    param(3, "Band 2 Freq", 0.4, "800 Hz"),
+                    param(4, "Band 2 Gain", 0.5, "0.0 dB"),
+                    param(6, "Band 3 Freq", 0.6, "3000 Hz"),
+                    param(7, "Band 3 Gain", 0.5, "0.0 dB"),
+                    param(9, "Band 4 Freq", 0.8, "6000 Hz"),
+                    param(10, "Band 4 Gain", 0.5, "0.0 dB"),
+                ],
+            ),
+            plugin(
+                2,
+                "VST: ReaVerbate (Cockos)",
+                true,
+                vec![param(0, "Wet", 0.2, "-12 dB"), param(1, "Dry", 1.0, "0 dB")],
+            ),
+        ])
+    }
+
+    fn plan(text: &str) -> RelativeEditPlan {
+        let intent = ToneIntent::parse(text);
+        let vocabulary = ToneVocabulary::builtin();
+        assert!(
+            is_relative_request(&intent, &vocabulary),
+            "{:?} should be relative",
+            text
+        );
+        plan_relative_edit(
+            &intent,
+            &snapshot(),
+            &PluginProfileRegistry::builtin(),
+            &ParamCurves::new(),
+            &vocabulary,
+        )
+    }
+
+    fn values(plan: &RelativeEditPlan) -> Vec<(i32, i32, f64)> {
+        plan.actions
+            .iter()
+            .filter_map(|a| match a {
+                ParameterAction::SetParameter {
+                    plugin_index,
+                    param_index,
+                    value,
+                    ..
+                } => Some((
+                    *plugin_index,
+                    *param_index,
+                    (value * 1000.0).round() / 1000.0,
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn recognizes_relative_requests() {
+        let vocabulary = ToneVocabulary::builtin();
+        let relative = |text: &str| is_relative_request(&ToneIntent::parse(text), &vocabulary);
+        assert!(relative("a bit less fizz"));
+        assert!(relative("tighter low end, more mids"));
+        assert!(relative("a little more glassy"));
+        assert!(!relative("Metallica Battery rhythm tone"));
+        assert!(!relative("more gain but no reverb"));
+        assert!(!relative("add a tube screamer"));
+        // Whole-tone styles carry no moves: the full pipeline handles them.
+        assert!(!relative("more brown sound"));
+    }
+
+    #[test]
+    fn maps_descriptors_to_band_and_knob_deltas() {
+        // Fizz: ReaEQ band 4 (6 kHz) cut 3 dB on a +-24 dB knob, presence down half a step.
+        let less_fizz = plan("less fizz");
+        assert_eq!(values(&less_fizz), vec![(1, 10, 0.438), (0, 4, 0.45)]);
+
+        // "a bit" halves the moves; the treble knob stops at its limit.
+        let brighter = plan("a bit brighter");
+        assert_eq!(values(&brighter), vec![(1, 7, 0.531), (0, 3, 1.0)]);
+
+        // Moves on the same parameter add up; the reverb's wet knob is its mix.
+        let combined = plan("more mids and much more mids, a bit more reverb");
+        assert_eq!(
+            values(&combined),
+            vec![(1, 4, 0.688), (0, 2, 0.8), (2, 0, 0.25)]
+        );
+        assert!(combined.warnings.is_empty());
+    }
+
+    #[test]
+    fn reports_what_it_cannot_move() {
+        let mut snapshot = snapshot();
+        snapshot.plugins.retain(|p| p.index == 0);
+        let intent = ToneIntent::parse("less mud");
+        let plan = plan_relative_edit(
+            &intent,
+            &snapshot,
+            &PluginProfileRegistry::builtin(),
+            &ParamCurves::new(),
+            &ToneVocabulary::builtin(),
+        );
+        assert!(plan.actions.is_empty());
+        assert_eq!(plan.warnings.len(), 2);
+    }
+}
@@ -0,0 +1,401 @@
+//! RfxChain Export
+//!
+//! Turns a `ToneParameters` tone into a REAPER `.RfxChain` file built from stock plugins
+//! (ReaGate -> ReaComp -> ReaEQ -> ReaDelay -> ReaVerbate).
+//!
+//! ReaPlugs store their state as an opaque binary chunk, so the file cannot be synthesized
+//! offline. Instead the chain is loaded on a scratch track in REAPER, `plan_export` maps the
+//! tone against that live snapshot (real parameter names and indices), the caller applies
+//! the writes and removes the unused plugins, and `extract_fx_chain` cuts the FX chain out
+//! of the track state chunk REAPER returns. What ends up in the file is exactly what REAPER
+//! itself would save with "Save FX chain".
+
+use crate::chain_mapper::{ChainMapper, ChainMapperConfig};
+use crate::param_curve::ParamCurves;
+use crate::parameter_ai::{ParameterAction, ReaperSnapshot};
+use crate::plugin_profile::PluginProfileRegistry;
+use crate::tone_encyclopedia::ToneParameters;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Stock plugins loaded on the scratch track, in chain order.
+pub const STOCK_CHAIN: &[&str] = &[
+    "ReaGate (Cockos)",
+    "ReaComp (Cockos)",
+    "ReaEQ (Cockos)",
+    "ReaDelay (Cockos)",
+    "ReaVerbate (Cockos)",
+];
+
+/// Name of the scratch track the chain is rendered on.
+pub const EXPORT_TRACK_NAME: &str = "ToneForge Export";
+
+/// Window/UI lines of an FX chain that "Save FX chain" leaves out of `.RfxChain` files.
+const CHAIN_UI_KEYS: &[&str] = &["WNDRECT", "SHOW", "LASTSEL", "DOCKED"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedParam {
+    pub index: i32,
+    pub name: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedPlugin {
+    /// FX index on the scratch track.
+    pub index: i32,
+    pub name: String,
+    pub params: Vec<ExportedParam>,
+}
+
+/// Parameter writes for the scratch track, plus the plugins the tone does not use.
+#[derive(Debug, Clone)]
+pub struct ExportPlan {
+    pub plugins: Vec<ExportedPlugin>,
+    /// FX indices to remove before the chunk is read, ascending.
+    pub unused_plugins: Vec<i32>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RfxChainExport {
+    pub plugins: Vec<ExportedPlugin>,
+    pub warnings: Vec<String>,
+    /// Full `.RfxChain` file content.
+    #[serde(skip)]
+    pub content: String,
+}
+
+/// Map a tone onto the scratch track's live snapshot. Curves learned from REAPER resolve
+/// dB/Hz/ms targets, the tempo resolves note-division delays.
+pub fn plan_export(
+    tone: &ToneParameters,
+    scratch: &ReaperSnapshot,
+    tempo_bpm: Option<f64>,
+    curves: ParamCurves,
+    profiles: PluginProfileRegistry,
+) -> Result<ExportPlan, String> {
+    let mut mapper = ChainMapper::new(ChainMapperConfig {
+        allow_load_plugins: false,
+        ..Default::default()
+    })
+    .with_curves(curves)
+    .with_profiles(profiles);
+    if let Some(bpm) = tempo_bpm {
+        mapper = mapper.with_tempo(bpm);
+    }
+    let mapping = mapper.map(tone, scratch);
+
+    // plugin index -> (param index -> (name, value)); BTreeMap keeps chain/param order.
+    let mut touched: BTreeMap<i32, BTreeMap<i32, (String, f64)>> = BTreeMap::new();
+    for action in &mapping.actions {
+        if let ParameterAction::SetParameter {
+            plugin_index,
+            param_index,
+            param_name,
+            value,
+            ..
+        } = action
+        {
+            touched
+                .entry(*plugin_index)
+                .or_default()
+                .insert(*param_index, (param_name.clone(), *value));
+        }
+    }
+
+    if touched.is_empty() {
+        return Err("Tone has no parameters the stock plugin chain can represent".to_string());
+    }
+
+    let unused_plugins = scratch
+        .plugins
+        .iter()
+        .map(|plugin| plugin.index)
+        .filter(|index| !touched.contains_key(index))
+        .collect();
+
+    let plugins = scratch
+        .plugins
+        .iter()
+        .filter_map(|plugin| {
+            let params = touched.remove(&plugin.index)?;
+            Some(ExportedPlugin {
+                index: plugin.index,
+                name: plugin.name.clone(),
+                params: params
+                    .into_iter()
+                    .map(|(index, (name, value))| ExportedParam { index, name, value })
+                    .collect(),
+            })
+        })
+        .collect();
+
+    Ok(ExportPlan {
+        plugins,
+        unused_plugins,
+        warnings: mapping.warnings,
+    })
+}
+
+/// Cut the `<FXCHAIN>` body out of a track state chunk (as returned by `/track/chunk`) and
+/// return it as `.RfxChain` content: window/UI lines dropped, re-indented from the chain root,
+/// plugin blocks and their base64 state kept verbatim.
+pub fn extract_fx_chain(track_chunk: &str) -> Result<String, String> {
+    let mut lines = track_chunk.lines();
+    let mut depth = 0usize;
+    let mut found = false;
+    for line in lines.by_ref() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('<') {
+            depth += 1;
+            // The chain sits directly inside <TRACK>; deeper FXCHAINs belong to containers.
+            if depth == 2 && trimmed.split_whitespace().next() == Some("<FXCHAIN") {
+                found = true;
+                break;
+            }
+        } else if trimmed == ">" {
+            depth = depth.saturating_sub(1);
+        }
+    }
+    if !found {
+        return Err("Track chunk has no FX chain".to_string());
+    }
+
+    let mut out = String::new();
+    let mut depth = 0usize;
+    let mut has_plugin = false;
+    for line in lines {
+        let trimmed = line.trim();
+        if trimmed == ">" {
+            if depth == 0 {
+                if !has_plugin {
+                    return Err("FX chain is empty".to_string());
+                }
+                return Ok(out);
+            }
+            depth -= 1;
+        } else if trimmed.starts_with('<') {
+            if depth == 0 {
+                has_plugin = true;
+            }
+            depth += 1;
+        } else if depth == 0 {
+            let key = trimmed.split_whitespace().next().unwrap_or("");
+            if CHAIN_UI_KEYS.contains(&key) {
+                continue;
+            }
+        }
+        // Block openers were already counted; closers and plain lines sit at `depth`.
+        let indent = if trimmed.starts_with('<') {
+            depth - 1
+        } else {
+            depth
+        };
+        out.push_str(&"  ".repeat(indent));
+        out.push_str(trimmed);
+        out.push('\n');
+    }
+    Err("Track chunk ends inside the FX chain".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpp_parser::parse_rfxchain;
+    use crate::test_fixtures::{params, plugin};
+    use crate::tone_encyclopedia::EffectParameters;
+    use std::collections::HashMap;
+
+    /// Track chunk in the layout `GetTrackStateChunk` returns: ReaEQ and ReaDelay left on
+    /// the scratch track, with their state blobs shortened.
+    const TRACK_CHUNK: &str = r#"<TRACK {6E3F7A52-0C1D-4B8E-9A51-2F0D8C1B7E44}
+  NAME "ToneForge Export"
+  PEAKCOL 16576
+  BEAT -1
+  AUTOMODE 0
+  VOLPAN 1 0 -1 -1 1
+  MUTESOLO 0 0 0
+  IPHASE 0
+  ISBUS 0 0
+  BUSCOMP 0 0 0 0 0
+  SHOWINMIX 1 0.6667 0.5 1 0.5 0 0 0
+  FREEMODE 0
+  SEL 0
+  REC 0 0 1 0 0 0 0 0
+  VU 2
+  TRACKHEIGHT 0 0 0 0 0 0 0
+  INQ 0 0 0 0.5 100 0 0 100
+  NCHAN 2
+  FX 1
+  TRACKID {6E3F7A52-0C1D-4B8E-9A51-2F0D8C1B7E44}
+  PERF 0
+  MIDIOUT -1
+  MAINSEND 1 0
+  <FXCHAIN
+    WNDRECT 24 52 655 408
+    SHOW 0
+    LASTSEL 1
+    DOCKED 0
+    BYPASS 0 0 0
+    <VST "VST: ReaEQ (Cockos)" reaeq.dll 0 "" 1919247729<56535472656571726561657100000000> ""
+      cWVlcu5e7f4AAAAAAgAAAAEAAAAAAAAAAgAAAAAAAAACAAAAAQAAAAAAAAACAAAAAAAAAFwAAAABAAAA
+      AAAQAA==
+      AQAAAAAAAAA5GAIAAAAAAAAAAAAAAPA/AAAAAAAA4D8BAAAAAAAAAAAAAAAAAPA/AAAAAAAA4D8BAAAA
+      AAAAAAAAAAAAAAAA
+    >
+    FLOATPOS 0 0 0 0
+    FXID {0B5E2C1A-7D3F-4E6A-8C9B-1A2D3E4F5A6B}
+    WAK 0 0
+    BYPASS 0 0 0
+    <VST "VST: ReaDelay (Cockos)" readelay.dll 0 "" 1919247460<5653547265646C7265616465616C6179> ""
+      bGRlcu5e7f4AAAAAAgAAAAEAAAAAAAAAAgAAAAAAAAACAAAAAQAAAAAAAAACAAAAAAAAAEgAAAABAAAA
+      AAAQAA==
+      AAAAAAAA8D8AAAAAAADwPwAAAAAAAAAAmpmZmZmZ2T8AAAAAAAAAAA==
+      AAAQAAAA
+    >
+    FLOATPOS 0 0 0 0
+    FXID {5C4D3E2F-1A0B-4C9D-8E7F-6A5B4C3D2E1F}
+    WAK 0 0
+  >
+>"#;
+
+    fn tone() -> ToneParameters {
+        let mut gate = HashMap::new();
+        gate.insert("threshold".to_string(), 0.3);
+
+        let mut eq = HashMap::new();
+        eq.insert("800Hz".to_string(), -3.0);
+
+        let mut delay = HashMap::new();
+        delay.insert("feedback".to_string(), 0.4);
+
+        ToneParameters {
+            amp: HashMap::new(),
+            eq,
+            eq_bands: Vec::new(),
+            cab: Default::default(),
+            routing: Default::default(),
+            effects: vec![EffectParameters {
+                effect_type: "noise_gate".to_string(),
+                parameters: gate,
+            }],
+            reverb: HashMap::new(),
+            delay,
+        }
+    }
+
+    fn scratch() -> ReaperSnapshot {
+        ReaperSnapshot {
+            track_index: 4,
+            track_name: EXPORT_TRACK_NAME.to_string(),
+            plugins: vec![
+                plugin(
+                    0,
+                    "VST: ReaGate (Cockos)",
+                    true,
+                    params(&["Threshold", "Attack", "Release"]),
+                ),
+                plugin(
+                    1,
+                    "VST: ReaComp (Cockos)",
+                    true,
+                    params(&["Threshold", "Ratio", "Attack"]),
+                ),
+                plugin(
+                    2,
+                    "VST: ReaEQ (Cockos)",
+                    true,
+                    params(&[
+                        "Band 1 Freq",
+                        "Band 1 Gain",
+                        "Band 1 Q",
+                        "Band 2 Freq",
+                        "Band 2 Gain",
+                    ]),
+                ),
+                plugin(
+                    3,
+                    "VST: ReaDelay (Cockos)",
+                    true,
+                    params(&["Wet", "Dry", "1: Length (time)", "1: Feedback"]),
+                ),
+                plugin(
+                    4,
+                    "VST: ReaVerbate (Cockos)",
+                    true,
+                    params(&["Wet", "Dry", "Room size"]),
+                ),
+            ],
+        }
+    }
+
+    fn plan(tone: &ToneParameters) -> Result<ExportPlan, String> {
+        plan_export(
+            tone,
+            &scratch(),
+            None,
+            ParamCurves::new(),
+            PluginProfileRegistry::builtin(),
+        )
+    }
+
+    #[test]
+    fn plan_keeps_only_used_plugins() {
+        let plan = plan(&tone()).unwrap();
+        let names: Vec<&str> = plan.plugins.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "VST: ReaGate (Cockos)",
+                "VST: ReaEQ (Cockos)",
+                "VST: ReaDelay (Cockos)"
+            ]
+        );
+        assert_eq!(plan.unused_plugins, vec![1, 4]);
+
+        let gate = &plan.plugins[0];
+        assert_eq!(gate.params.len(), 1);
+        assert_eq!(gate.params[0].name, "Threshold");
+        assert_eq!(gate.params[0].value, 0.3);
+    }
+
+    #[test]
+    fn empty_tone_is_rejected() {
+        let empty = ToneParameters {
+            amp: HashMap::new(),
+            eq: HashMap::new(),
+            eq_bands: Vec::new(),
+            cab: Default::default(),
+            routing: Default::default(),
+            effects: vec![],
+            reverb: HashMap::new(),
+            delay: HashMap::new(),
+        };
+        assert!(plan(&empty).is_err());
+    }
+
+    #[test]
+    fn extracts_chain_with_plugin_state() {
+        let chain = extract_fx_chain(TRACK_CHUNK).unwrap();
+
+        assert!(chain.starts_with("BYPASS 0 0 0\n<VST \"VST: ReaEQ (Cockos)\""));
+        assert!(!chain.contains("WNDRECT"));
+        assert!(!chain.contains("NAME \"ToneForge Export\""));
+        assert!(chain.contains(
+            "  cWVlcu5e7f4AAAAAAgAAAAEAAAAAAAAAAgAAAAAAAAACAAAAAQAAAAAAAAACAAAAAAAAAFwAAAABAAAA\n"
+        ));
+        assert!(chain.ends_with("WAK 0 0\n"));
+
+        let snapshot = parse_rfxchain(&chain, "Export").unwrap();
+        let names: Vec<&str> = snapshot.plugins.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["VST: ReaEQ (Cockos)", "VST: ReaDelay (Cockos)"]);
+    }
+
+    #[test]
+    fn chunk_without_plugins_is_rejected() {
+        let chunk = "<TRACK\n  NAME \"x\"\n  <FXCHAIN\n    SHOW 0\n  >\n>";
+        assert!(extract_fx_chain(chunk).is_err());
+        assert!(extract_fx_chain("<TRACK\n  NAME \"x\"\n>").is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! Routing
+//!
+//! Parallel signal paths next to the track's serial chain: dual-amp blends inside an FX
+//! container (REAPER 7 runs container children in parallel when they carry the `parallel`
+//! flag) or wet/dry/wet rigs on send tracks with their own volume and pan.
+
+use serde::{Deserialize, Serialize};
+
+/// Paths beyond this are dropped by the sanitizer.
+pub const MAX_PARALLEL_PATHS: usize = 4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingKind {
+    /// Plugins already on the track are grouped into one parallel FX container; one plugin
+    /// per path, summed at unity.
+    #[default]
+    Container,
+    /// Each path gets its own track fed by a send, so it can carry a chain, level and pan.
+    Sends,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParallelPath {
+    pub name: String,
+    /// Roles ("amp", "delay") or plugin names, in signal order.
+    #[serde(default)]
+    pub chain: Vec<String>,
+    #[serde(default)]
+    pub volume_db: f64,
+    /// -1.0 (left) .. 1.0 (right).
+    #[serde(default)]
+    pub pan: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Routing {
+    #[serde(default)]
+    pub kind: RoutingKind,
+    #[serde(default)]
+    pub paths: Vec<ParallelPath>,
+    /// Level of the track itself (the dry path in a send rig).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dry_db: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dry_pan: Option<f64>,
+}
+
+impl Routing {
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.dry_db.is_none() && self.dry_pan.is_none()
+    }
+
+    /// One line for prompts and logs.
+    pub fn describe(&self) -> String {
+        let kind = match self.kind {
+            RoutingKind::Container => "parallel container",
+            RoutingKind::Sends => "send tracks",
+        };
+        let mut parts: Vec<String> = self
+            .paths
+            .iter()
+            .map(|p| {
+                format!(
+                    "{} [{}] {:+.1} dB {}",
+                    p.name,
+                    p.chain.join(" > "),
+                    p.volume_db,
+                    pan_label(p.pan)
+                )
+            })
+            .collect();
+        if self.dry_db.is_some() || self.dry_pan.is_some() {
+            parts.push(format!(
+                "dry {:+.1} dB {}",
+                self.dry_db.unwrap_or(0.0),
+                pan_label(self.dry_pan.unwrap_or(0.0))
+            ));
+        }
+        format!("{}: {}", kind, parts.join("; "))
+    }
+}
+
+/// "C", "L50", "R100".
+pub fn pan_label(pan: f64) -> String {
+    let percent = (pan.clamp(-1.0, 1.0) * 100.0).round() as i32;
+    match percent {
+        0 => "C".to_string(),
+        p if p < 0 => format!("L{}", -p),
+        p => format!("R{}", p),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_wet_dry_wet_rig() {
+        let routing: Routing = serde_json::from_str(
+            r#"{"kind": "sends", "dry_db": 0,
+                "paths": [{"name": "Wet L", "chain": ["delay", "reverb"], "volume_db": -6, "pan": -1},
+                          {"name": "Wet R", "chain": ["delay"], "volume_db": -6, "pan": 1}]}"#,
+        )
+        .unwrap();
+        assert_eq!(routing.kind, RoutingKind::Sends);
+        assert_eq!(
+            routing.describe(),
+            "send tracks: Wet L [delay > reverb] -6.0 dB L100; Wet R [delay] -6.0 dB R100; dry +0.0 dB C"
+        );
+
+        let dual: Routing = serde_json::from_str(
+            r#"{"paths": [{"name": "A", "chain": ["amp"]}, {"name": "B", "chain": ["amp"]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(dual.kind, RoutingKind::Container);
+        assert!(!dual.is_empty());
+        assert!(Routing::default().is_empty());
+    }
+}
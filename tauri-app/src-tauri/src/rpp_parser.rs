@@ -0,0 +1,408 @@
+//! REAPER Project / FX Chain Parser
+//!
+//! Reads `.RPP` projects and `.RfxChain` files offline (no REAPER required) and turns
+//! them into `ReaperSnapshot`s so they can be browsed, diffed and imported as presets.
+//!
+//! What is extracted:
+//! - tracks (name, index in project order)
+//! - FX chain order and bypass/offline state
+//! - parameter values where the chain stores them as plain text (JS plugins, and
+//!   parameter envelopes). VST/AU/CLAP state is an opaque binary blob, so otherwise
+//!   those plugins come back without parameters.
+//!
+//! JS slider values are in the slider's own units and its range lives in the JSFX source,
+//! so they cannot be normalized here. They are marked with `RAW_SLIDER_HINT` for browsing
+//! only; presets and diffs leave them out.
+
+use crate::parameter_ai::{ReaperParameter, ReaperPlugin, ReaperSnapshot};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// `format_hint` of JS slider values read from a file (not normalized 0..1).
+pub const RAW_SLIDER_HINT: &str = "raw_slider";
+
+pub fn is_raw_slider(param: &ReaperParameter) -> bool {
+    param.format_hint == RAW_SLIDER_HINT
+}
+
+/// One line of an RPP file, either a plain line or a nested `<BLOCK ... >`.
+#[derive(Debug, Clone)]
+pub enum RppItem {
+    Line(Vec<String>),
+    Block(RppNode),
+}
+
+#[derive(Debug, Clone)]
+pub struct RppNode {
+    pub name: String,
+    pub args: Vec<String>,
+    pub items: Vec<RppItem>,
+}
+
+impl RppNode {
+    pub fn blocks<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a RppNode> + 'a {
+        self.items.iter().filter_map(move |item| match item {
+            RppItem::Block(node) if node.name == name => Some(node),
+            _ => None,
+        })
+    }
+
+    /// First plain line starting with `key`, without the key token.
+    pub fn line(&self, key: &str) -> Option<&[String]> {
+        self.items.iter().find_map(|item| match item {
+            RppItem::Line(tokens) if tokens.first().map(String::as_str) == Some(key) => {
+                Some(&tokens[1..])
+            }
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RppProject {
+    pub tempo: Option<f64>,
+    pub tracks: Vec<ReaperSnapshot>,
+}
+
+/// Parse REAPER's block syntax into a tree. The returned root holds the top-level items
+/// (for an RPP that is a single `REAPER_PROJECT` block; for an RfxChain it is the chain body).
+pub fn parse_tree(text: &str) -> Result<RppNode, String> {
+    let mut stack: Vec<RppNode> = vec![RppNode {
+        name: String::new(),
+        args: Vec::new(),
+        items: Vec::new(),
+    }];
+
+    for (line_no, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix('<') {
+            let mut tokens = tokenize_line(rest);
+            if tokens.is_empty() {
+                return Err(format!("line {}: empty block header", line_no + 1));
+            }
+            let name = tokens.remove(0);
+            stack.push(RppNode {
+                name,
+                args: tokens,
+                items: Vec::new(),
+            });
+        } else if line == ">" {
+            if stack.len() < 2 {
+                return Err(format!("line {}: unbalanced '>'", line_no + 1));
+            }
+            let node = stack.pop().expect("stack has a parent");
+            stack
+                .last_mut()
+                .expect("stack has a parent")
+                .items
+                .push(RppItem::Block(node));
+        } else {
+            stack
+                .last_mut()
+                .expect("stack always has a root")
+                .items
+                .push(RppItem::Line(tokenize_line(line)));
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err(format!(
+            "unterminated block '{}'",
+            stack.last().map(|n| n.name.as_str()).unwrap_or("")
+        ));
+    }
+    Ok(stack.pop().expect("root"))
+}
+
+/// Split an RPP line into tokens. Strings may be quoted with `"`, `'` or `` ` ``.
+fn tokenize_line(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '"' || c == '\'' || c == '`' {
+            chars.next();
+            let mut token = String::new();
+            for ch in chars.by_ref() {
+                if ch == c {
+                    break;
+                }
+                token.push(ch);
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(&ch) = chars.peek() {
+                if ch.is_whitespace() {
+                    break;
+                }
+                token.push(ch);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+
+    tokens
+}
+
+pub fn parse_rpp(text: &str) -> Result<RppProject, String> {
+    let root = parse_tree(text)?;
+    let project = root
+        .blocks("REAPER_PROJECT")
+        .next()
+        .ok_or("Not a REAPER project (missing REAPER_PROJECT block)")?;
+
+    let tempo = project
+        .line("TEMPO")
+        .and_then(|args| args.first())
+        .and_then(|v| v.parse::<f64>().ok());
+
+    let tracks = project
+        .blocks("TRACK")
+        .enumerate()
+        .map(|(i, track)| {
+            let name = track
+                .line("NAME")
+                .and_then(|args| args.first())
+                .cloned()
+                .unwrap_or_default();
+            let plugins = track
+                .blocks("FXCHAIN")
+                .next()
+                .map(parse_fx_chain)
+                .unwrap_or_default();
+            ReaperSnapshot {
+                track_index: i as i32,
+                track_name: name,
+                plugins,
+            }
+        })
+        .collect();
+
+    Ok(RppProject { tempo, tracks })
+}
+
+/// Parse an `.RfxChain` file (an FXCHAIN body without the wrapper) as a single track.
+pub fn parse_rfxchain(text: &str, track_name: &str) -> Result<ReaperSnapshot, String> {
+    let root = parse_tree(text)?;
+    let plugins = parse_fx_chain(&root);
+    if plugins.is_empty() {
+        return Err("No plugins found in FX chain".to_string());
+    }
+    Ok(ReaperSnapshot {
+        track_index: 0,
+        track_name: track_name.to_string(),
+        plugins,
+    })
+}
+
+/// Parse a `.RPP` or `.RfxChain` file (by extension) into track snapshots.
+pub fn load_reaper_file<P: AsRef<Path>>(path: P) -> Result<Vec<ReaperSnapshot>, String> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    match ext.as_str() {
+        "rpp" => Ok(parse_rpp(&content)?.tracks),
+        "rfxchain" => {
+            let name = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("FX Chain");
+            Ok(vec![parse_rfxchain(&content, name)?])
+        }
+        _ => Err(format!(
+            "Unsupported file type '.{}' (expected .RPP or .RfxChain)",
+            ext
+        )),
+    }
+}
+
+fn parse_fx_chain(chain: &RppNode) -> Vec<ReaperPlugin> {
+    let mut plugins: Vec<ReaperPlugin> = Vec::new();
+    // BYPASS applies to the plugin block that follows it.
+    let mut pending_enabled = true;
+
+    for item in &chain.items {
+        match item {
+            RppItem::Line(tokens) if tokens.first().map(String::as_str) == Some("BYPASS") => {
+                let bypassed = tokens.get(1).map(|v| v == "1").unwrap_or(false);
+                let offline = tokens.get(2).map(|v| v == "1").unwrap_or(false);
+                pending_enabled = !(bypassed || offline);
+            }
+            RppItem::Block(node) if node.name == "PARMENV" => {
+                // Envelopes follow the plugin block they belong to.
+                if let (Some(plugin), Some(param)) = (plugins.last_mut(), envelope_param(node)) {
+                    if !plugin.parameters.iter().any(|p| p.index == param.index) {
+                        plugin.parameters.push(param);
+                    }
+                }
+            }
+            RppItem::Block(node) => {
+                let Some(name) = plugin_name(node) else {
+                    continue;
+                };
+                plugins.push(ReaperPlugin {
+                    index: plugins.len() as i32,
+                    name,
+                    enabled: pending_enabled,
+                    parameters: plain_text_params(node),
+                });
+                pending_enabled = true;
+            }
+            _ => {}
+        }
+    }
+
+    plugins
+}
+
+fn plugin_name(node: &RppNode) -> Option<String> {
+    let first = node.args.first()?;
+    match node.name.as_str() {
+        "VST" | "AU" | "CLAP" | "DX" | "LV2" => Some(first.clone()),
+        "JS" => Some(format!("JS: {}", first)),
+        "VIDEO_EFFECT" => Some(format!("Video: {}", first)),
+        _ => None,
+    }
+}
+
+/// Value of a parameter envelope at its first point, normalized to 0..1.
+/// Header: `<PARMENV index[:extra] min max default`; points: `PT time value shape`.
+fn envelope_param(node: &RppNode) -> Option<ReaperParameter> {
+    let index: i32 = node.args.first()?.split(':').next()?.parse().ok()?;
+    let min: f64 = node.args.get(1).and_then(|v| v.parse().ok()).unwrap_or(0.0);
+    let max: f64 = node.args.get(2).and_then(|v| v.parse().ok()).unwrap_or(1.0);
+    let raw: f64 = node.line("PT")?.get(1)?.parse().ok()?;
+    let value = if max > min {
+        (raw - min) / (max - min)
+    } else {
+        raw
+    };
+
+    Some(ReaperParameter {
+        index,
+        name: format!("Param {}", index + 1),
+        current_value: value,
+        display_value: String::new(),
+        unit: String::new(),
+        format_hint: "raw".to_string(),
+    })
+}
+
+/// JS plugins store slider values on the first body line ("-" marks unused sliders).
+/// Values are raw slider values, not normalized; see `RAW_SLIDER_HINT`.
+fn plain_text_params(node: &RppNode) -> Vec<ReaperParameter> {
+    if node.name != "JS" {
+        return Vec::new();
+    }
+    let Some(RppItem::Line(values)) = node.items.first() else {
+        return Vec::new();
+    };
+
+    values
+        .iter()
+        .enumerate()
+        .filter_map(|(i, raw)| {
+            let value = raw.parse::<f64>().ok()?;
+            Some(ReaperParameter {
+                index: i as i32,
+                name: format!("Slider {}", i + 1),
+                current_value: value,
+                display_value: raw.clone(),
+                unit: String::new(),
+                format_hint: RAW_SLIDER_HINT.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot_diff::snapshot_diff;
+    use crate::tone_presets::TonePreset;
+
+    #[test]
+    fn parses_shipped_project() {
+        let text = include_str!("../../../presets/metallica-master.RPP");
+        let project = parse_rpp(text).unwrap();
+
+        assert_eq!(project.tempo, Some(120.0));
+        assert_eq!(project.tracks.len(), 1);
+
+        let track = &project.tracks[0];
+        assert_eq!(track.track_name, "Guitar");
+        let names: Vec<&str> = track.plugins.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "VST3: Neural DSP Archetype Gojira (Neural DSP)",
+                "VST3: TSE 808 (TSE Audio)"
+            ]
+        );
+        assert!(track.plugins.iter().all(|p| p.enabled));
+    }
+
+    #[test]
+    fn parses_rfxchain_bypass_and_js_params() {
+        let text = r#"BYPASS 1 0 0
+<VST "VST: ReaEQ (Cockos)" reaeq.dll 0 "" 1919247729<56535472656571726561657100000000> ""
+  cWVlcu9e7f4AAAAAAgAAAAEAAAAAAAAAAgAAAAAAAAACAAAAAQAAAAAAAAACAAAAAAAAADwAAAAAAAAA
+>
+FLOATPOS 0 0 0 0
+FXID {11111111-0000-0000-0000-000000000000}
+WAK 0 0
+BYPASS 0 0 0
+<JS loser/3BandEQ ""
+  0.000000 200.000000 -3.500000 2000.000000 - - -
+>
+WAK 0 0
+"#;
+        let snapshot = parse_rfxchain(text, "Lead").unwrap();
+        assert_eq!(snapshot.plugins.len(), 2);
+
+        let eq = &snapshot.plugins[0];
+        assert_eq!(eq.name, "VST: ReaEQ (Cockos)");
+        assert!(!eq.enabled);
+        assert!(eq.parameters.is_empty());
+
+        let js = &snapshot.plugins[1];
+        assert_eq!(js.name, "JS: loser/3BandEQ");
+        assert!(js.enabled);
+        assert_eq!(js.parameters.len(), 4);
+        assert_eq!(js.parameters[2].current_value, -3.5);
+        assert!(js.parameters.iter().all(is_raw_slider));
+
+        // Raw slider values are neither stored in presets nor diffed.
+        let preset = TonePreset::from_snapshot("Lead", "", &[], &snapshot);
+        assert!(preset.plugins[1].params.is_empty());
+        let mut changed = snapshot.clone();
+        changed.plugins[1].parameters[1].current_value = 400.0;
+        assert!(snapshot_diff(&snapshot, &changed).is_empty());
+    }
+
+    #[test]
+    fn rejects_unbalanced_blocks() {
+        assert!(parse_tree("<REAPER_PROJECT 0.1\n  <TRACK\n  >\n").is_err());
+        assert!(parse_tree(">\n").is_err());
+        assert_eq!(
+            tokenize_line(r#"NAME "Lead Guitar" 'a b' `c`"#),
+            vec!["NAME", "Lead Guitar", "a b", "c"]
+        );
+    }
+}
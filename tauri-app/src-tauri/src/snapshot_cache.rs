@@ -0,0 +1,182 @@
+//! Snapshot Cache
+//!
+//! Per-track cache of plugin parameter lists, so a snapshot only refetches plugins that
+//! changed. Entries are keyed by plugin name + FX index (a plugin that moved or was replaced
+//! is a miss) and stamped with the `SnapshotRevisions` counter at fetch time; an entry is
+//! reused only while nothing newer was recorded for its slot. Revisions are bumped both by
+//! the REAPER change feed and by our own writes (`invalidate_*`), so the cache never has to
+//! wait for the feed to see what ToneForge itself just changed. While the feed is down
+//! nothing is served from the cache, since edits made in REAPER would go unnoticed.
+
+use crate::parameter_ai::ReaperPlugin;
+use crate::reaper_changes::SnapshotRevisions;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PluginKey {
+    pub name: String,
+    pub index: i32,
+}
+
+impl PluginKey {
+    pub fn new(name: &str, index: i32) -> Self {
+        Self {
+            name: name.to_string(),
+            index,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedPlugin {
+    plugin: ReaperPlugin,
+    revision: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+pub struct SnapshotCache {
+    revisions: Arc<Mutex<SnapshotRevisions>>,
+    tracks: Mutex<HashMap<i32, HashMap<PluginKey, CachedPlugin>>>,
+}
+
+impl SnapshotCache {
+    /// Share the revision counters the change feed updates.
+    pub fn new(revisions: Arc<Mutex<SnapshotRevisions>>) -> Self {
+        Self {
+            revisions,
+            tracks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Revision to stamp on plugins fetched from now on (take it *before* fetching so a
+    /// change that lands mid-fetch still invalidates the entry).
+    pub fn current_revision(&self) -> u64 {
+        self.revisions.lock().unwrap().current()
+    }
+
+    /// Cached parameters for a plugin if they are still current.
+    pub fn get(&self, track: i32, key: &PluginKey) -> Option<ReaperPlugin> {
+        let tracks = self.tracks.lock().unwrap();
+        let cached = tracks.get(&track)?.get(key)?;
+        let revisions = self.revisions.lock().unwrap();
+        if !revisions.is_feed_live() || revisions.is_fx_stale(track, key.index, cached.revision) {
+            return None;
+        }
+        Some(cached.plugin.clone())
+    }
+
+    pub fn insert(&self, track: i32, plugin: ReaperPlugin, revision: u64) {
+        let key = PluginKey::new(&plugin.name, plugin.index);
+        self.tracks
+            .lock()
+            .unwrap()
+            .entry(track)
+            .or_default()
+            .insert(key, CachedPlugin { plugin, revision });
+    }
+
+    /// Drop entries for slots that are no longer in the chain.
+    pub fn retain_track(&self, track: i32, live: &[PluginKey]) {
+        if let Some(entries) = self.tracks.lock().unwrap().get_mut(&track) {
+            entries.retain(|key, _| live.contains(key));
+        }
+    }
+
+    /// We changed a value on this plugin.
+    pub fn invalidate_fx(&self, track: i32, fx: i32) {
+        self.revisions.lock().unwrap().mark_fx(track, fx);
+    }
+
+    /// We changed the shape of this track's chain (load/remove/move).
+    pub fn invalidate_track(&self, track: i32) {
+        self.revisions.lock().unwrap().mark_track(track);
+    }
+
+    /// Undo/redo/jumps can touch values and chain shape anywhere on these tracks.
+    pub fn invalidate_tracks(&self, tracks: impl IntoIterator<Item = i32>) {
+        let mut revisions = self.revisions.lock().unwrap();
+        for track in tracks {
+            revisions.mark_track(track);
+        }
+    }
+
+    pub fn clear(&self) {
+        self.tracks.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures as fixtures;
+
+    fn plugin(index: i32, name: &str) -> ReaperPlugin {
+        fixtures::plugin(index, name, true, vec![])
+    }
+
+    fn live_revisions() -> Arc<Mutex<SnapshotRevisions>> {
+        let mut revisions = SnapshotRevisions::new();
+        revisions.set_feed_live(true);
+        Arc::new(Mutex::new(revisions))
+    }
+
+    #[test]
+    fn entries_are_keyed_by_name_and_index() {
+        let cache = SnapshotCache::new(live_revisions());
+        let rev = cache.current_revision();
+        cache.insert(0, plugin(1, "VST3: Amp"), rev);
+
+        assert!(cache.get(0, &PluginKey::new("VST3: Amp", 1)).is_some());
+        assert!(cache.get(0, &PluginKey::new("VST3: Amp", 0)).is_none());
+        assert!(cache.get(0, &PluginKey::new("VST: ReaEQ", 1)).is_none());
+        assert!(cache.get(1, &PluginKey::new("VST3: Amp", 1)).is_none());
+    }
+
+    #[test]
+    fn writes_and_feed_changes_invalidate() {
+        let revisions = live_revisions();
+        let cache = SnapshotCache::new(revisions.clone());
+        let rev = cache.current_revision();
+        cache.insert(0, plugin(0, "Amp"), rev);
+        cache.insert(0, plugin(1, "Delay"), rev);
+
+        cache.invalidate_fx(0, 1);
+        assert!(cache.get(0, &PluginKey::new("Amp", 0)).is_some());
+        assert!(cache.get(0, &PluginKey::new("Delay", 1)).is_none());
+
+        // External change reported by the feed.
+        revisions.lock().unwrap().mark_track(0);
+        assert!(cache.get(0, &PluginKey::new("Amp", 0)).is_none());
+
+        // Refetched after the change: fresh again.
+        let rev = cache.current_revision();
+        cache.insert(0, plugin(0, "Amp"), rev);
+        assert!(cache.get(0, &PluginKey::new("Amp", 0)).is_some());
+    }
+
+    #[test]
+    fn nothing_is_served_while_the_feed_is_down() {
+        let revisions = Arc::new(Mutex::new(SnapshotRevisions::new()));
+        let cache = SnapshotCache::new(revisions.clone());
+        let rev = cache.current_revision();
+        cache.insert(0, plugin(0, "Amp"), rev);
+        assert!(cache.get(0, &PluginKey::new("Amp", 0)).is_none());
+
+        revisions.lock().unwrap().set_feed_live(true);
+        let rev = cache.current_revision();
+        cache.insert(0, plugin(0, "Amp"), rev);
+        assert!(cache.get(0, &PluginKey::new("Amp", 0)).is_some());
+
+        // An outage invalidates what was cached before it, even once the feed is back.
+        revisions.lock().unwrap().set_feed_live(false);
+        assert!(cache.get(0, &PluginKey::new("Amp", 0)).is_none());
+        revisions.lock().unwrap().set_feed_live(true);
+        assert!(cache.get(0, &PluginKey::new("Amp", 0)).is_none());
+    }
+}
@@ -0,0 +1,349 @@
+//! Snapshot Diff
+//!
+//! Compares two `ReaperSnapshot`s of a track (e.g. "last week" vs "now", or a stored
+//! preset vs the live chain) and reports what changed:
+//! - plugins added / removed
+//! - plugins reordered
+//! - enable state changes
+//! - parameter deltas (normalized values + display strings)
+
+use crate::parameter_ai::{ReaperParameter, ReaperPlugin, ReaperSnapshot};
+use crate::rpp_parser::is_raw_slider;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Normalized deltas smaller than this are treated as "unchanged".
+const PARAM_EPSILON: f64 = 1e-4;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PluginRef {
+    pub index: i32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PluginMove {
+    pub name: String,
+    pub from_index: i32,
+    pub to_index: i32,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ParamDelta {
+    pub param_index: i32,
+    pub param_name: String,
+    pub old_value: f64,
+    pub new_value: f64,
+    pub old_display: String,
+    pub new_display: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PluginDiff {
+    pub name: String,
+    pub index_a: i32,
+    pub index_b: i32,
+    pub enabled_a: bool,
+    pub enabled_b: bool,
+    pub param_deltas: Vec<ParamDelta>,
+}
+
+impl PluginDiff {
+    pub fn enable_changed(&self) -> bool {
+        self.enabled_a != self.enabled_b
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Default)]
+pub struct SnapshotDiff {
+    pub added: Vec<PluginRef>,
+    pub removed: Vec<PluginRef>,
+    pub reordered: Vec<PluginMove>,
+    pub changed: Vec<PluginDiff>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.reordered.is_empty()
+            && self.changed.is_empty()
+    }
+
+    /// Human-readable lines (for logs / chat output).
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for p in &self.removed {
+            lines.push(format!("- Removed '{}' (slot {})", p.name, p.index));
+        }
+        for p in &self.added {
+            lines.push(format!("+ Added '{}' (slot {})", p.name, p.index));
+        }
+        for m in &self.reordered {
+            lines.push(format!("~ Moved '{}' {} -> {}", m.name, m.from_index, m.to_index));
+        }
+        for c in &self.changed {
+            if c.enable_changed() {
+                lines.push(format!(
+                    "~ '{}' {}",
+                    c.name,
+                    if c.enabled_b { "enabled" } else { "disabled" }
+                ));
+            }
+            for d in &c.param_deltas {
+                lines.push(format!(
+                    "~ '{}' :: {}: {} -> {}",
+                    c.name,
+                    d.param_name,
+                    display_or_value(&d.old_display, d.old_value),
+                    display_or_value(&d.new_display, d.new_value)
+                ));
+            }
+        }
+        if lines.is_empty() {
+            lines.push("No changes".to_string());
+        }
+        lines
+    }
+}
+
+/// Diff snapshot `a` (before) against snapshot `b` (after).
+///
+/// Plugins are matched by name; duplicates of the same plugin are paired in chain order.
+pub fn snapshot_diff(a: &ReaperSnapshot, b: &ReaperSnapshot) -> SnapshotDiff {
+    let pairs = match_plugins(&a.plugins, &b.plugins);
+
+    let matched_a: Vec<bool> = {
+        let mut v = vec![false; a.plugins.len()];
+        for (ia, _) in &pairs {
+            v[*ia] = true;
+        }
+        v
+    };
+    let matched_b: Vec<bool> = {
+        let mut v = vec![false; b.plugins.len()];
+        for (_, ib) in &pairs {
+            v[*ib] = true;
+        }
+        v
+    };
+
+    let removed = a
+        .plugins
+        .iter()
+        .zip(&matched_a)
+        .filter(|(_, m)| !**m)
+        .map(|(p, _)| PluginRef {
+            index: p.index,
+            name: p.name.clone(),
+        })
+        .collect();
+
+    let added = b
+        .plugins
+        .iter()
+        .zip(&matched_b)
+        .filter(|(_, m)| !**m)
+        .map(|(p, _)| PluginRef {
+            index: p.index,
+            name: p.name.clone(),
+        })
+        .collect();
+
+    // Plugins whose relative order is preserved form the longest increasing run of
+    // b positions; everything outside it was moved. This keeps a single insertion from
+    // reporting every later plugin as "reordered".
+    let stable = longest_increasing_subsequence(&pairs.iter().map(|(_, ib)| *ib).collect::<Vec<_>>());
+    let reordered = pairs
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !stable.contains(i))
+        .map(|(_, (ia, ib))| PluginMove {
+            name: a.plugins[*ia].name.clone(),
+            from_index: a.plugins[*ia].index,
+            to_index: b.plugins[*ib].index,
+        })
+        .collect();
+
+    let changed = pairs
+        .iter()
+        .filter_map(|(ia, ib)| diff_plugin(&a.plugins[*ia], &b.plugins[*ib]))
+        .collect();
+
+    SnapshotDiff {
+        added,
+        removed,
+        reordered,
+        changed,
+    }
+}
+
+/// Pair plugins of two chains by name (duplicates in chain order).
+/// Returns `(index_in_a, index_in_b)` pairs sorted by position in `a`.
+pub fn match_plugins(a: &[ReaperPlugin], b: &[ReaperPlugin]) -> Vec<(usize, usize)> {
+    let mut b_by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (ib, p) in b.iter().enumerate() {
+        b_by_name.entry(p.name.as_str()).or_default().push(ib);
+    }
+    for slots in b_by_name.values_mut() {
+        slots.reverse();
+    }
+
+    let mut pairs = Vec::new();
+    for (ia, p) in a.iter().enumerate() {
+        if let Some(ib) = b_by_name.get_mut(p.name.as_str()).and_then(|slots| slots.pop()) {
+            pairs.push((ia, ib));
+        }
+    }
+    pairs
+}
+
+fn diff_plugin(a: &ReaperPlugin, b: &ReaperPlugin) -> Option<PluginDiff> {
+    let mut param_deltas = Vec::new();
+    for pa in a.parameters.iter().filter(|p| !is_raw_slider(p)) {
+        let Some(pb) = match_param(pa, &b.parameters).filter(|p| !is_raw_slider(p)) else {
+            continue;
+        };
+        if (pa.current_value - pb.current_value).abs() > PARAM_EPSILON {
+            param_deltas.push(ParamDelta {
+                param_index: pb.index,
+                param_name: pb.name.clone(),
+                old_value: pa.current_value,
+                new_value: pb.current_value,
+                old_display: pa.display_value.clone(),
+                new_display: pb.display_value.clone(),
+            });
+        }
+    }
+
+    if a.enabled == b.enabled && param_deltas.is_empty() {
+        return None;
+    }
+
+    Some(PluginDiff {
+        name: b.name.clone(),
+        index_a: a.index,
+        index_b: b.index,
+        enabled_a: a.enabled,
+        enabled_b: b.enabled,
+        param_deltas,
+    })
+}
+
+/// Prefer the same index with the same name; fall back to name lookup (plugin versions
+/// occasionally shift parameter indices).
+pub fn match_param<'a>(pa: &ReaperParameter, params: &'a [ReaperParameter]) -> Option<&'a ReaperParameter> {
+    params
+        .iter()
+        .find(|p| p.index == pa.index && p.name == pa.name)
+        .or_else(|| params.iter().find(|p| p.name == pa.name))
+}
+
+/// Indices (into `seq`) of one longest strictly increasing subsequence.
+fn longest_increasing_subsequence(seq: &[usize]) -> Vec<usize> {
+    if seq.is_empty() {
+        return Vec::new();
+    }
+
+    let mut len = vec![1usize; seq.len()];
+    let mut prev: Vec<Option<usize>> = vec![None; seq.len()];
+    for i in 0..seq.len() {
+        for j in 0..i {
+            if seq[j] < seq[i] && len[j] + 1 > len[i] {
+                len[i] = len[j] + 1;
+                prev[i] = Some(j);
+            }
+        }
+    }
+
+    let mut best = 0;
+    for i in 1..seq.len() {
+        if len[i] > len[best] {
+            best = i;
+        }
+    }
+
+    let mut out = vec![best];
+    let mut cur = best;
+    while let Some(p) = prev[cur] {
+        out.push(p);
+        cur = p;
+    }
+    out.reverse();
+    out
+}
+
+fn display_or_value(display: &str, value: f64) -> String {
+    if display.trim().is_empty() {
+        format!("{:.3}", value)
+    } else {
+        display.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{param, plugin, snapshot};
+
+    #[test]
+    fn identical_snapshots_have_no_diff() {
+        let a = snapshot(vec![plugin(0, "ReaEQ (Cockos)", true, vec![param(0, "Gain", 0.5)])]);
+        let diff = snapshot_diff(&a, &a.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.summary_lines(), vec!["No changes".to_string()]);
+    }
+
+    #[test]
+    fn reports_added_removed_and_param_changes() {
+        let a = snapshot(vec![
+            plugin(0, "ReaGate (Cockos)", true, vec![param(0, "Threshold", 0.3)]),
+            plugin(1, "VST3: Amp", true, vec![param(0, "Gain", 0.5), param(1, "Bass", 0.5)]),
+        ]);
+        let b = snapshot(vec![
+            plugin(0, "VST3: Amp", false, vec![param(0, "Gain", 0.8), param(1, "Bass", 0.5)]),
+            plugin(1, "ReaDelay (Cockos)", true, vec![]),
+        ]);
+
+        let diff = snapshot_diff(&a, &b);
+        assert_eq!(diff.removed, vec![PluginRef { index: 0, name: "ReaGate (Cockos)".to_string() }]);
+        assert_eq!(diff.added, vec![PluginRef { index: 1, name: "ReaDelay (Cockos)".to_string() }]);
+        // Index shift caused by the removal is not a reorder.
+        assert!(diff.reordered.is_empty());
+
+        assert_eq!(diff.changed.len(), 1);
+        let amp = &diff.changed[0];
+        assert!(amp.enable_changed());
+        assert_eq!(amp.param_deltas.len(), 1);
+        assert_eq!(amp.param_deltas[0].param_name, "Gain");
+        assert_eq!(amp.param_deltas[0].old_display, "50%");
+        assert_eq!(amp.param_deltas[0].new_display, "80%");
+    }
+
+    #[test]
+    fn detects_reorder_and_pairs_duplicates_in_order() {
+        let a = snapshot(vec![
+            plugin(0, "ReaEQ (Cockos)", true, vec![param(0, "Gain", 0.1)]),
+            plugin(1, "VST3: Amp", true, vec![]),
+            plugin(2, "ReaEQ (Cockos)", true, vec![param(0, "Gain", 0.9)]),
+        ]);
+        let b = snapshot(vec![
+            plugin(0, "VST3: Amp", true, vec![]),
+            plugin(1, "ReaEQ (Cockos)", true, vec![param(0, "Gain", 0.1)]),
+            plugin(2, "ReaEQ (Cockos)", true, vec![param(0, "Gain", 0.9)]),
+        ]);
+
+        let diff = snapshot_diff(&a, &b);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert!(diff.changed.is_empty());
+        assert_eq!(
+            diff.reordered,
+            vec![PluginMove {
+                name: "VST3: Amp".to_string(),
+                from_index: 1,
+                to_index: 0,
+            }]
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Tempo Sync
+//!
+//! Note divisions for tempo-synced delays. A tone stores its delay time under
+//! `delay.time_note` as a fraction of a whole note (0.25 = 1/4, 0.1875 = dotted 1/8);
+//! tone JSON may also spell it as "1/8.", "1/8d", "1/4T", "dotted eighth" and so on.
+
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+
+/// Delay map key holding the note division (as a whole-note fraction).
+pub const DELAY_NOTE_KEY: &str = "time_note";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteModifier {
+    Straight,
+    Dotted,
+    Triplet,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoteDivision {
+    /// 1 = whole, 4 = quarter, 8 = eighth, ...
+    pub denominator: u32,
+    pub modifier: NoteModifier,
+}
+
+const DENOMINATORS: [u32; 7] = [1, 2, 4, 8, 16, 32, 64];
+
+impl NoteDivision {
+    pub fn new(denominator: u32, modifier: NoteModifier) -> Self {
+        Self {
+            denominator,
+            modifier,
+        }
+    }
+
+    /// Length as a fraction of a whole note.
+    pub fn whole_fraction(&self) -> f64 {
+        let base = 1.0 / self.denominator as f64;
+        match self.modifier {
+            NoteModifier::Straight => base,
+            NoteModifier::Dotted => base * 1.5,
+            NoteModifier::Triplet => base * 2.0 / 3.0,
+        }
+    }
+
+    /// Length in quarter-note beats (what most sync'd plugins display).
+    pub fn beats(&self) -> f64 {
+        self.whole_fraction() * 4.0
+    }
+
+    pub fn to_ms(&self, bpm: f64) -> f64 {
+        self.beats() * 60_000.0 / bpm
+    }
+
+    /// Closest standard division to a whole-note fraction (within 2%).
+    pub fn from_whole_fraction(fraction: f64) -> Option<Self> {
+        if !fraction.is_finite() || fraction <= 0.0 {
+            return None;
+        }
+        DENOMINATORS
+            .iter()
+            .flat_map(|&d| {
+                [
+                    NoteModifier::Straight,
+                    NoteModifier::Dotted,
+                    NoteModifier::Triplet,
+                ]
+                .map(|m| NoteDivision::new(d, m))
+            })
+            .map(|n| (n, (n.whole_fraction() - fraction).abs() / fraction))
+            .filter(|(_, err)| *err < 0.02)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(n, _)| n)
+    }
+
+    /// Parse "1/8", "1/8.", "1/8d", "1/4T", "1/16 triplet", "dotted eighth", "quarter".
+    pub fn parse(text: &str) -> Option<Self> {
+        let s = text.trim().to_lowercase();
+        let mut modifier = NoteModifier::Straight;
+        for (word, m) in [
+            ("dotted", NoteModifier::Dotted),
+            ("triplet", NoteModifier::Triplet),
+        ] {
+            if s.contains(word) {
+                modifier = m;
+            }
+        }
+
+        let denominator = if let Some(pos) = s.find("1/") {
+            let rest = &s[pos + 2..];
+            let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+            // Single-letter suffixes only: "1/8d" is dotted, "1/8 delay" is not.
+            let mut after = rest[digits.len()..].chars();
+            let standalone = |next: Option<char>| next.is_none_or(|c| !c.is_alphabetic());
+            match (after.next(), after.next()) {
+                (Some('.'), _) => modifier = NoteModifier::Dotted,
+                (Some('d'), next) if standalone(next) => modifier = NoteModifier::Dotted,
+                (Some('t'), next) if standalone(next) => modifier = NoteModifier::Triplet,
+                _ => {}
+            }
+            digits.parse().ok()?
+        } else {
+            [
+                ("whole", 1),
+                ("half", 2),
+                ("quarter", 4),
+                ("eighth", 8),
+                ("sixteenth", 16),
+                ("thirty", 32),
+            ]
+            .iter()
+            .find(|(word, _)| s.contains(word))
+            .map(|(_, d)| *d)?
+        };
+
+        DENOMINATORS
+            .contains(&denominator)
+            .then(|| NoteDivision::new(denominator, modifier))
+    }
+
+    pub fn label(&self) -> String {
+        let suffix = match self.modifier {
+            NoteModifier::Straight => "",
+            NoteModifier::Dotted => ".",
+            NoteModifier::Triplet => "T",
+        };
+        format!("1/{}{}", self.denominator, suffix)
+    }
+}
+
+/// Deserialize a delay map, accepting note names for `time_note` ("1/8.") and storing them
+/// as whole-note fractions so the map stays numeric.
+pub fn deserialize_delay_map<'de, D>(deserializer: D) -> Result<HashMap<String, f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(f64),
+        Text(String),
+    }
+
+    let raw = HashMap::<String, Value>::deserialize(deserializer)?;
+    let mut out = HashMap::with_capacity(raw.len());
+    for (key, value) in raw {
+        let number = match value {
+            Value::Number(n) => n,
+            Value::Text(text) if key == DELAY_NOTE_KEY => NoteDivision::parse(&text)
+                .ok_or_else(|| {
+                    serde::de::Error::custom(format!("invalid note division '{}'", text))
+                })?
+                .whole_fraction(),
+            Value::Text(text) => text.trim().parse().map_err(|_| {
+                serde::de::Error::custom(format!("delay.{} is not a number: '{}'", key, text))
+            })?,
+        };
+        out.insert(key, number);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_note_divisions() {
+        let dotted_eighth = NoteDivision::new(8, NoteModifier::Dotted);
+        for text in ["1/8.", "1/8d", "1/8 dotted", "dotted eighth", "Dotted 1/8"] {
+            assert_eq!(NoteDivision::parse(text), Some(dotted_eighth), "{}", text);
+        }
+        assert_eq!(
+            NoteDivision::parse("1/4T"),
+            Some(NoteDivision::new(4, NoteModifier::Triplet))
+        );
+        assert_eq!(
+            NoteDivision::parse("quarter"),
+            Some(NoteDivision::new(4, NoteModifier::Straight))
+        );
+        assert!(NoteDivision::parse("1/5").is_none());
+        assert!(NoteDivision::parse("fast").is_none());
+
+        assert_eq!(
+            NoteDivision::from_whole_fraction(0.1875),
+            Some(dotted_eighth)
+        );
+        assert_eq!(dotted_eighth.label(), "1/8.");
+    }
+
+    #[test]
+    fn delay_maps_accept_note_names() {
+        #[derive(Deserialize)]
+        struct Tone {
+            #[serde(deserialize_with = "deserialize_delay_map")]
+            delay: HashMap<String, f64>,
+        }
+
+        let tone: Tone =
+            serde_json::from_str(r#"{"delay": {"time_note": "1/8.", "mix": 0.2}}"#).unwrap();
+        assert_eq!(tone.delay[DELAY_NOTE_KEY], 0.1875);
+        assert_eq!(tone.delay["mix"], 0.2);
+        assert!(serde_json::from_str::<Tone>(r#"{"delay": {"time_note": "soon"}}"#).is_err());
+    }
+
+    #[test]
+    fn converts_to_milliseconds() {
+        let at = |text: &str, bpm: f64| NoteDivision::parse(text).unwrap().to_ms(bpm);
+        assert!((at("1/4", 120.0) - 500.0).abs() < 1e-9);
+        assert!((at("1/8.", 120.0) - 375.0).abs() < 1e-9);
+        assert!((at("1/8T", 120.0) - 166.666_666).abs() < 1e-3);
+    }
+}
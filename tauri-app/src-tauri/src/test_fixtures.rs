@@ -0,0 +1,70 @@
+//! Snapshot builders shared by the unit tests.
+
+use crate::parameter_ai::{ReaperParameter, ReaperPlugin, ReaperSnapshot};
+
+/// A percentage parameter, displayed the way REAPER shows normalized values ("NN%").
+pub fn param(index: i32, name: &str, value: f64) -> ReaperParameter {
+    ReaperParameter {
+        index,
+        name: name.to_string(),
+        current_value: value,
+        display_value: format!("{:.0}%", value * 100.0),
+        unit: "%".to_string(),
+        format_hint: "percentage".to_string(),
+    }
+}
+
+/// A parameter with the plugin's own display text ("800 Hz", "6.0").
+pub fn param_with_display(index: i32, name: &str, value: f64, display: &str) -> ReaperParameter {
+    ReaperParameter {
+        index,
+        name: name.to_string(),
+        current_value: value,
+        display_value: display.to_string(),
+        unit: String::new(),
+        format_hint: "raw".to_string(),
+    }
+}
+
+/// Parameters named `names` in index order, each at 0.5 without a display.
+pub fn params(names: &[&str]) -> Vec<ReaperParameter> {
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| param_with_display(i as i32, name, 0.5, ""))
+        .collect()
+}
+
+pub fn plugin(
+    index: i32,
+    name: &str,
+    enabled: bool,
+    parameters: Vec<ReaperParameter>,
+) -> ReaperPlugin {
+    ReaperPlugin {
+        index,
+        name: name.to_string(),
+        enabled,
+        parameters,
+    }
+}
+
+/// Track 0 ("Guitar") with `plugins`.
+pub fn snapshot(plugins: Vec<ReaperPlugin>) -> ReaperSnapshot {
+    ReaperSnapshot {
+        track_index: 0,
+        track_name: "Guitar".to_string(),
+        plugins,
+    }
+}
+
+/// Track 0 with enabled, parameterless plugins named `names` in chain order.
+pub fn chain(names: &[&str]) -> ReaperSnapshot {
+    snapshot(
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| plugin(i as i32, name, true, vec![]))
+            .collect(),
+    )
+}
@@ -6,6 +6,8 @@
 
 use crate::ai_client::AIProvider;
 use crate::tone_encyclopedia::{SearchResult, ToneEncyclopedia, ToneEntry, ToneParameters};
+use crate::tone_intent::{ToneIntent, ToneSection};
+use crate::tone_vocabulary::ToneVocabulary;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::error::Error;
@@ -34,6 +36,7 @@ pub enum ToneSource {
 pub struct ToneAI {
     encyclopedia: ToneEncyclopedia,
     ai_provider: Option<AIProvider>,
+    vocabulary: ToneVocabulary,
 }
 
 impl ToneAI {
@@ -42,6 +45,7 @@ impl ToneAI {
         Self {
             encyclopedia,
             ai_provider: None,
+            vocabulary: ToneVocabulary::builtin(),
         }
     }
 
@@ -51,6 +55,13 @@ impl ToneAI {
         self
     }
 
+    /// Descriptive words ("glassy", "brown sound") explained to the model and used to
+    /// bound amp gain; defaults to the built-in vocabulary.
+    pub fn with_vocabulary(mut self, vocabulary: ToneVocabulary) -> Self {
+        self.vocabulary = vocabulary;
+        self
+    }
+
     /// Process user request and return tone recommendation
     pub async fn process_request(&self, user_message: &str) -> Result<ToneAIResult, Box<dyn Error>> {
         println!("[TONE AI] Processing request: {}", user_message);
@@ -83,7 +94,7 @@ impl ToneAI {
                         best_match.tone.song.as_deref().unwrap_or("Unknown Song"),
                         best_match.tone.description
                     ),
-                    parameters: best_match.tone.parameters.clone(),
+                    parameters: best_match.tone.tone_parameters(),
                     matched_entry: Some(best_match.tone.id.clone()),
                     confidence: best_match.score,
                 });
@@ -149,11 +160,21 @@ Example output:
       "presence": 0.7
     },
     "eq": {
-      "80Hz": -2.0,
       "800Hz": -4.0,
       "2kHz": 1.0,
       "5kHz": 2.0
     },
+    "eq_bands": [
+      {"freq_hz": 80, "type": "high_pass", "slope_db_oct": 24},
+      {"freq_hz": 8000, "gain_db": -3.0, "type": "high_shelf"}
+    ],
+    "cab": {
+      "speaker": "4x12 V30",
+      "mic": "SM57",
+      "mic_position": 0.3,
+      "low_cut_hz": 80,
+      "high_cut_hz": 6500
+    },
     "effects": [
       {
         "effect_type": "noise_gate",
@@ -167,6 +188,11 @@ Example output:
     "reverb": {
       "room_size": 0.2,
       "mix": 0.1
+    },
+    "delay": {
+      "time_note": "1/8.",
+      "feedback": 0.3,
+      "mix": 0.15
     }
   }
 }
@@ -175,12 +201,25 @@ Example output:
 IMPORTANT:
 - All amp/effect parameters must be normalized to 0.0-1.0 range
 - EQ values are in dB (-12.0 to +12.0)
+- Use "eq_bands" when the filter shape matters: "type" is peak, low_shelf, high_shelf, high_pass, low_pass, notch or band_pass, with optional "q" and "slope_db_oct"
+- "cab" describes the cabinet: speaker, mic, mic_position (0.0 center .. 1.0 edge), optional low_cut_hz/high_cut_hz and ir_path only if the user names an IR file
+- "routing" only for parallel rigs: {"kind": "container", "paths": [{"name": "A", "chain": ["amp"]}, {"name": "B", "chain": ["amp"]}]} for dual amps, or {"kind": "sends", "dry_db": 0, "paths": [{"name": "Wet L", "chain": ["delay"], "volume_db": -6, "pan": -1}, ...]} for wet/dry/wet
+- Delay time: prefer "time_note" with a note division synced to the project tempo ("1/4", "1/8." dotted, "1/8T" triplet)
 - Be precise and consistent
 - Respect explicit "no / without / keep off / bypass" instructions.
 - Respond ONLY with valid JSON
 "#;
 
-        let user_prompt = format!("{}\n\nUser request: {}", context, user_message);
+        let intent = ToneIntent::parse_with_artists(user_message, &self.encyclopedia.get_all_artists());
+        let mut user_prompt = format!("{}\n\nUser request: {}", context, user_message);
+        let summary = intent.describe();
+        if !summary.is_empty() {
+            user_prompt.push_str(&format!("\nParsed request: {}", summary));
+        }
+        let hints = self.vocabulary.prompt_hints(user_message);
+        if !hints.is_empty() {
+            user_prompt.push_str(&format!("\nDescriptive words:\n{}", hints));
+        }
 
         let response = provider.generate(system_prompt, &user_prompt).await?;
 
@@ -188,8 +227,7 @@ IMPORTANT:
         let mut parsed = self.parse_ai_tone_response(&response)?;
 
         // Validate required sections and attempt a single repair pass if the model omitted requested parts.
-        let req = detect_requested_sections(user_message);
-        let issues = validate_sections(&req, &parsed.parameters);
+        let issues = validate_sections(&intent, &parsed.parameters);
         if !issues.is_empty() {
             println!("[TONE AI] Model output missing requested sections; attempting repair: {:?}", issues);
             let repair_prompt = format!(
@@ -200,7 +238,7 @@ IMPORTANT:
             );
             if let Ok(repair_response) = provider.generate(system_prompt, &repair_prompt).await {
                 if let Ok(repair_parsed) = self.parse_ai_tone_response(&repair_response) {
-                    let issues2 = validate_sections(&req, &repair_parsed.parameters);
+                    let issues2 = validate_sections(&intent, &repair_parsed.parameters);
                     if issues2.is_empty() {
                         parsed = repair_parsed;
                     } else {
@@ -219,8 +257,7 @@ IMPORTANT:
         }
 
         // Respect explicit "no/bypass" instructions (but otherwise allow creative additions).
-        let forbidden = detect_forbidden_sections(user_message);
-        let removed = prune_forbidden(&forbidden, &mut parsed.parameters);
+        let removed = prune_forbidden(&intent, &mut parsed.parameters);
         if !removed.is_empty() {
             println!("[TONE AI] Removed explicitly forbidden sections/effects: {:?}", removed);
         }
@@ -230,6 +267,10 @@ IMPORTANT:
             println!("[TONE AI] Mix heuristics applied: {:?}", mix_adj);
         }
 
+        if let Some(note) = apply_gain_range(&self.vocabulary, user_message, &mut parsed.parameters) {
+            println!("[TONE AI] Vocabulary gain range applied: {}", note);
+        }
+
         Ok(ToneAIResult {
             source: if context.is_empty() {
                 ToneSource::AIGenerated
@@ -279,133 +320,22 @@ IMPORTANT:
     }
 }
 
-#[derive(Debug, Clone, Copy, Default)]
-struct RequestedSections {
-    delay: bool,
-    reverb: bool,
-    gate: bool,
-    eq: bool,
-    overdrive: bool,
-    distortion: bool,
-    compressor: bool,
-    chorus: bool,
-}
-
-fn detect_requested_sections(user_message: &str) -> RequestedSections {
-    let s = user_message.to_lowercase();
-
-    fn has_any(s: &str, needles: &[&str]) -> bool {
-        needles.iter().any(|n| s.contains(n))
-    }
-
-    fn has_negation(s: &str, label: &str) -> bool {
-        // Lightweight negation heuristics for common instructions.
-        let patterns = [
-            format!("no {}", label),
-            format!("without {}", label),
-            format!("{} off", label),
-            format!("{} bypass", label),
-            format!("{} bypassed", label),
-            format!("keep {} off", label),
-            format!("keep {} bypass", label),
-            format!("{} kapalı", label),
-        ];
-        patterns.iter().any(|p| s.contains(p))
-    }
-
-    let delay = has_any(&s, &["delay", "echo", "slapback"]) && !has_negation(&s, "delay");
-    let reverb = has_any(&s, &["reverb", "room", "hall"]) && !has_negation(&s, "reverb");
-    let gate = has_any(&s, &["noise gate", "gate"]) && !has_negation(&s, "gate");
-    let eq = (has_any(&s, &[" eq", "equalizer", "hz"]) || s.starts_with("eq")) && !has_negation(&s, "eq");
-
-    let overdrive = has_any(&s, &["overdrive", "tubescreamer", "tube screamer", "screamer", "od"])
-        && !has_negation(&s, "overdrive")
-        && !has_negation(&s, "tubescreamer");
-    let distortion = has_any(&s, &["distortion", "fuzz", "hm-2", "hm2"])
-        && !has_negation(&s, "distortion")
-        && !has_negation(&s, "fuzz");
-    let compressor =
-        has_any(&s, &["compressor", "compression", "comp"]) && !has_negation(&s, "compressor");
-    let chorus = has_any(&s, &["chorus", "modulation"]) && !has_negation(&s, "chorus");
-
-    RequestedSections {
-        delay,
-        reverb,
-        gate,
-        eq,
-        overdrive,
-        distortion,
-        compressor,
-        chorus,
-    }
-}
-
-#[derive(Debug, Clone, Copy, Default)]
-struct ForbiddenSections {
-    delay: bool,
-    reverb: bool,
-    eq: bool,
-    gate: bool,
-    overdrive: bool,
-    distortion: bool,
-    compressor: bool,
-    chorus: bool,
-    all_effects: bool,
-}
-
-fn detect_forbidden_sections(user_message: &str) -> ForbiddenSections {
-    let s = user_message.to_lowercase();
-
-    fn has_any(s: &str, needles: &[&str]) -> bool {
-        needles.iter().any(|n| s.contains(n))
-    }
-
-    fn has_negation(s: &str, label: &str) -> bool {
-        let patterns = [
-            format!("no {}", label),
-            format!("without {}", label),
-            format!("{} off", label),
-            format!("{} bypass", label),
-            format!("{} bypassed", label),
-            format!("keep {} off", label),
-            format!("keep {} bypass", label),
-            format!("{} kapalı", label),
-        ];
-        patterns.iter().any(|p| s.contains(p))
-    }
-
-    let all_effects = has_any(&s, &["no effects", "without effects", "dry only", "dry signal", "only amp"])
-        || has_any(&s, &["efektsiz", "efekt istemiyorum", "dry"]);
-
-    ForbiddenSections {
-        delay: has_negation(&s, "delay"),
-        reverb: has_negation(&s, "reverb"),
-        eq: has_negation(&s, "eq") || has_negation(&s, "equalizer"),
-        gate: has_negation(&s, "gate") || has_negation(&s, "noise gate"),
-        overdrive: has_negation(&s, "overdrive") || has_negation(&s, "tubescreamer"),
-        distortion: has_negation(&s, "distortion") || has_negation(&s, "fuzz"),
-        compressor: has_negation(&s, "compressor") || has_negation(&s, "compression"),
-        chorus: has_negation(&s, "chorus") || has_negation(&s, "modulation"),
-        all_effects,
-    }
-}
-
-fn validate_sections(req: &RequestedSections, params: &ToneParameters) -> Vec<String> {
+fn validate_sections(intent: &ToneIntent, params: &ToneParameters) -> Vec<String> {
     let mut issues = Vec::new();
-    if req.delay && params.delay.is_empty() {
+    if intent.requests(ToneSection::Delay) && params.delay.is_empty() {
         issues.push("delay".to_string());
     }
-    if req.reverb && params.reverb.is_empty() {
+    if intent.requests(ToneSection::Reverb) && params.reverb.is_empty() {
         issues.push("reverb".to_string());
     }
-    if req.eq && params.eq.is_empty() {
+    if intent.requests(ToneSection::Eq) && params.eq.is_empty() && params.eq_bands.is_empty() {
         issues.push("eq".to_string());
     }
-    if req.gate {
+    if intent.requests(ToneSection::Gate) {
         let has_gate = params
             .effects
             .iter()
-            .any(|e| e.effect_type.to_lowercase().contains("gate"));
+            .any(|e| ToneSection::Gate.matches_effect_type(&e.effect_type));
         if !has_gate {
             issues.push("noise_gate".to_string());
         }
@@ -413,23 +343,24 @@ fn validate_sections(req: &RequestedSections, params: &ToneParameters) -> Vec<St
     issues
 }
 
-fn prune_forbidden(forbidden: &ForbiddenSections, params: &mut ToneParameters) -> Vec<String> {
+fn prune_forbidden(intent: &ToneIntent, params: &mut ToneParameters) -> Vec<String> {
     let mut removed = Vec::new();
 
-    if forbidden.delay && !params.delay.is_empty() {
+    if intent.forbids(ToneSection::Delay) && !params.delay.is_empty() {
         params.delay.clear();
         removed.push("delay".to_string());
     }
-    if forbidden.reverb && !params.reverb.is_empty() {
+    if intent.forbids(ToneSection::Reverb) && !params.reverb.is_empty() {
         params.reverb.clear();
         removed.push("reverb".to_string());
     }
-    if forbidden.eq && !params.eq.is_empty() {
+    if intent.forbids(ToneSection::Eq) && (!params.eq.is_empty() || !params.eq_bands.is_empty()) {
         params.eq.clear();
+        params.eq_bands.clear();
         removed.push("eq".to_string());
     }
 
-    if forbidden.all_effects && !params.effects.is_empty() {
+    if intent.no_effects && !params.effects.is_empty() {
         params.effects.clear();
         removed.push("effects".to_string());
         return removed;
@@ -437,29 +368,10 @@ fn prune_forbidden(forbidden: &ForbiddenSections, params: &mut ToneParameters) -
 
     let before = params.effects.len();
     params.effects.retain(|e| {
-        let t = e.effect_type.to_lowercase();
-        let is_gate = t.contains("gate");
-        let is_overdrive = t.contains("overdrive") || t.contains("tubescreamer") || t.contains("screamer") || t == "od";
-        let is_distortion = t.contains("dist") || t.contains("fuzz") || t.contains("hm-2") || t.contains("hm2");
-        let is_compressor = t.contains("compress");
-        let is_chorus = t.contains("chorus");
-
-        if is_gate && forbidden.gate {
-            return false;
-        }
-        if is_overdrive && forbidden.overdrive {
-            return false;
-        }
-        if is_distortion && forbidden.distortion {
-            return false;
-        }
-        if is_compressor && forbidden.compressor {
-            return false;
-        }
-        if is_chorus && forbidden.chorus {
-            return false;
-        }
-        true
+        !intent
+            .forbidden
+            .iter()
+            .any(|section| section.matches_effect_type(&e.effect_type))
     });
 
     if params.effects.len() != before {
@@ -469,6 +381,15 @@ fn prune_forbidden(forbidden: &ForbiddenSections, params: &mut ToneParameters) -
     removed
 }
 
+/// Keep amp gain inside the range the request's descriptive words imply ("clean", "djent").
+fn apply_gain_range(vocabulary: &ToneVocabulary, user_message: &str, params: &mut ToneParameters) -> Option<String> {
+    let [low, high] = vocabulary.gain_range(user_message)?;
+    let gain = params.amp.get_mut("gain")?;
+    let before = *gain;
+    *gain = gain.clamp(low, high);
+    ((before - *gain).abs() > f64::EPSILON).then(|| format!("amp.gain {:.2} -> {:.2}", before, *gain))
+}
+
 fn apply_mix_heuristics(user_message: &str, params: &mut ToneParameters) -> Vec<String> {
     let s = user_message.to_lowercase();
     let mut notes = Vec::new();
@@ -567,6 +488,9 @@ mod tests {
                     ("treble".to_string(), 0.75),
                 ]),
                 eq: HashMap::new(),
+                eq_bands: Vec::new(),
+                cab: Default::default(),
+                routing: Default::default(),
                 effects: Vec::new(),
                 reverb: HashMap::new(),
                 delay: HashMap::new(),
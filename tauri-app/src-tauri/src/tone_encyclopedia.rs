@@ -1,9 +1,13 @@
 //! Tone Encyclopedia System
 //!
 //! This module manages the tone encyclopedia - a database of thousands of guitar/bass tones
-//! from famous albums and artists. The encyclopedia is stored in JSON format and provides
-//! fuzzy search capabilities to find matching tones.
+//! from famous albums and artists. The encyclopedia is stored in JSON format and is searched
+//! through a ranked, typo-tolerant index (see `encyclopedia_search`).
 
+use crate::cab_model::CabParameters;
+use crate::encyclopedia_search::{SearchField, SearchIndex};
+use crate::eq_model::EqBand;
+use crate::routing::Routing;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -13,7 +17,12 @@ use std::path::Path;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToneEncyclopedia {
     pub version: String,
-    pub tones: Vec<ToneEntry>,
+    /// Private so every change goes through `add_tone`, which keeps `index` in step.
+    tones: Vec<ToneEntry>,
+
+    /// Built on load and kept in step by `add_tone`.
+    #[serde(skip)]
+    index: SearchIndex,
 }
 
 /// A single tone entry in the encyclopedia
@@ -40,6 +49,44 @@ pub struct ToneEntry {
     pub tags: Vec<String>,
 }
 
+impl ToneEntry {
+    /// The entry's parameters, with the cab section filled from `equipment.cabinet` when the
+    /// entry does not set one.
+    pub fn tone_parameters(&self) -> ToneParameters {
+        let mut params = self.parameters.clone();
+        if params.cab.is_empty() {
+            if let Some(cabinet) = &self.equipment.cabinet {
+                params.cab = CabParameters::from_description(cabinet);
+            }
+        }
+        params
+    }
+
+    /// Text fields the search index covers; techniques count as tags.
+    fn search_fields(&self) -> Vec<(SearchField, &str)> {
+        let mut fields = vec![
+            (SearchField::Artist, self.artist.as_str()),
+            (SearchField::Description, self.description.as_str()),
+        ];
+        let optional = [
+            (SearchField::Album, &self.album),
+            (SearchField::Song, &self.song),
+            (SearchField::Genre, &self.genre),
+            (SearchField::Equipment, &self.equipment.guitar),
+            (SearchField::Equipment, &self.equipment.amp),
+            (SearchField::Equipment, &self.equipment.cabinet),
+        ];
+        for (field, text) in optional {
+            if let Some(text) = text {
+                fields.push((field, text.as_str()));
+            }
+        }
+        fields.extend(self.equipment.pedals.iter().map(|p| (SearchField::Equipment, p.as_str())));
+        fields.extend(self.tags.iter().chain(&self.techniques).map(|t| (SearchField::Tags, t.as_str())));
+        fields
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Equipment {
     pub guitar: Option<String>,
@@ -56,14 +103,28 @@ pub struct ToneParameters {
     #[serde(default)]
     pub eq: HashMap<String, f64>, // Frequency -> dB
 
+    /// Typed EQ bands (filter type, Q, slope); see `eq_model`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub eq_bands: Vec<EqBand>,
+
+    /// Cabinet / IR; see `cab_model`.
+    #[serde(default, skip_serializing_if = "CabParameters::is_empty")]
+    pub cab: CabParameters,
+
     #[serde(default)]
     pub effects: Vec<EffectParameters>,
 
     #[serde(default)]
     pub reverb: HashMap<String, f64>,
 
-    #[serde(default)]
+    /// `time_note` may be given as a note division ("1/8.") and is stored as a
+    /// whole-note fraction; see `tempo_sync`.
+    #[serde(default, deserialize_with = "crate::tempo_sync::deserialize_delay_map")]
     pub delay: HashMap<String, f64>,
+
+    /// Parallel paths (dual amps, wet/dry/wet); see `routing`.
+    #[serde(default, skip_serializing_if = "Routing::is_empty")]
+    pub routing: Routing,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,6 +138,8 @@ pub struct EffectParameters {
 pub struct SearchResult {
     pub tone: ToneEntry,
     pub score: f32, // 0.0 - 1.0
+    /// What the score is made of, largest first: "artist: metallica +0.52",
+    /// "song: battery (typed \"batery\") +0.31".
     pub matched_fields: Vec<String>,
 }
 
@@ -86,6 +149,7 @@ impl ToneEncyclopedia {
         Self {
             version: "1.0".to_string(),
             tones: Vec::new(),
+            index: SearchIndex::default(),
         }
     }
 
@@ -93,7 +157,10 @@ impl ToneEncyclopedia {
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
         let content = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
 
-        serde_json::from_str(&content).map_err(|e| format!("Failed to parse JSON: {}", e))
+        let mut encyclopedia: Self =
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        encyclopedia.index = build_index(&encyclopedia.tones);
+        Ok(encyclopedia)
     }
 
     /// Save encyclopedia to JSON file
@@ -106,128 +173,24 @@ impl ToneEncyclopedia {
 
     /// Add a tone entry
     pub fn add_tone(&mut self, tone: ToneEntry) {
+        self.index.add(&tone.search_fields());
         self.tones.push(tone);
     }
 
     /// Search for tones matching a query
     /// Returns results sorted by relevance (highest first)
     pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
-        let query_lower = query.to_lowercase();
-        let mut results: Vec<SearchResult> = Vec::new();
-
-        for tone in &self.tones {
-            let (score, matched_fields) = self.calculate_relevance(tone, &query_lower);
-
-            if score > 0.0 {
-                results.push(SearchResult {
-                    tone: tone.clone(),
-                    score,
-                    matched_fields,
-                });
-            }
-        }
-
-        // Sort by score (descending)
-        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-
-        // Limit results
-        results.truncate(limit);
-
-        results
-    }
-
-    /// Calculate relevance score for a tone against a query
-    fn calculate_relevance(&self, tone: &ToneEntry, query_lower: &str) -> (f32, Vec<String>) {
-        let mut score: f32 = 0.0;
-        let mut matched_fields = Vec::new();
-
-        // Artist match (highest weight)
-        if self.fuzzy_match(&tone.artist.to_lowercase(), query_lower) {
-            score += 10.0;
-            matched_fields.push(format!("artist: {}", tone.artist));
-        }
-
-        // Album match
-        if let Some(ref album) = tone.album {
-            if self.fuzzy_match(&album.to_lowercase(), query_lower) {
-                score += 8.0;
-                matched_fields.push(format!("album: {}", album));
-            }
-        }
-
-        // Song match
-        if let Some(ref song) = tone.song {
-            if self.fuzzy_match(&song.to_lowercase(), query_lower) {
-                score += 7.0;
-                matched_fields.push(format!("song: {}", song));
-            }
-        }
-
-        // Genre match
-        if let Some(ref genre) = tone.genre {
-            if query_lower.contains(&genre.to_lowercase()) || genre.to_lowercase().contains(query_lower) {
-                score += 5.0;
-                matched_fields.push(format!("genre: {}", genre));
-            }
-        }
-
-        // Equipment match
-        if let Some(ref amp) = tone.equipment.amp {
-            if self.fuzzy_match(&amp.to_lowercase(), query_lower) {
-                score += 4.0;
-                matched_fields.push(format!("amp: {}", amp));
-            }
-        }
-
-        if let Some(ref guitar) = tone.equipment.guitar {
-            if self.fuzzy_match(&guitar.to_lowercase(), query_lower) {
-                score += 3.0;
-                matched_fields.push(format!("guitar: {}", guitar));
-            }
-        }
-
-        // Tags match
-        for tag in &tone.tags {
-            if query_lower.contains(&tag.to_lowercase()) || tag.to_lowercase().contains(query_lower) {
-                score += 2.0;
-                matched_fields.push(format!("tag: {}", tag));
-            }
-        }
-
-        // Description match
-        if self.fuzzy_match(&tone.description.to_lowercase(), query_lower) {
-            score += 1.0;
-            matched_fields.push("description".to_string());
-        }
-
-        // Normalize score to 0-1 range
-        let normalized_score = (score / 20.0).min(1.0);
-
-        (normalized_score, matched_fields)
-    }
-
-    /// Simple fuzzy matching - checks if strings contain each other or share significant substrings
-    fn fuzzy_match(&self, text: &str, query: &str) -> bool {
-        // Direct substring match
-        if text.contains(query) || query.contains(text) {
-            return true;
-        }
-
-        // Word-level matching
-        let text_words: Vec<&str> = text.split_whitespace().collect();
-        let query_words: Vec<&str> = query.split_whitespace().collect();
-
-        for query_word in &query_words {
-            for text_word in &text_words {
-                if text_word.contains(query_word) || query_word.contains(text_word) {
-                    if query_word.len() > 3 && text_word.len() > 3 {
-                        return true;
-                    }
-                }
-            }
-        }
-
-        false
+        self.index
+            .search(query, limit)
+            .into_iter()
+            .filter_map(|hit| {
+                Some(SearchResult {
+                    tone: self.tones.get(hit.doc)?.clone(),
+                    score: hit.score,
+                    matched_fields: hit.explanation,
+                })
+            })
+            .collect()
     }
 
     /// Get tone by ID
@@ -263,6 +226,10 @@ impl ToneEncyclopedia {
     }
 }
 
+fn build_index(tones: &[ToneEntry]) -> SearchIndex {
+    SearchIndex::build(tones.iter().map(ToneEntry::search_fields))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,6 +258,9 @@ mod tests {
             parameters: ToneParameters {
                 amp: HashMap::new(),
                 eq: HashMap::new(),
+                eq_bands: Vec::new(),
+                cab: Default::default(),
+                routing: Default::default(),
                 effects: Vec::new(),
                 reverb: HashMap::new(),
                 delay: HashMap::new(),
@@ -312,11 +282,19 @@ mod tests {
     }
 
     #[test]
-    fn test_fuzzy_matching() {
-        let encyclopedia = ToneEncyclopedia::new();
-
-        assert!(encyclopedia.fuzzy_match("metallica", "metal"));
-        assert!(encyclopedia.fuzzy_match("master of puppets", "master"));
-        assert!(encyclopedia.fuzzy_match("gibson explorer", "gibson"));
+    fn test_ranked_search_over_sample_encyclopedia() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../tone_encyclopedia.json");
+        let encyclopedia = ToneEncyclopedia::load_from_file(path).unwrap();
+
+        // Typos still find the entry, and the score says why.
+        let results = encyclopedia.search("metalica batery", 5);
+        assert_eq!(results[0].tone.id, "metallica_master_of_puppets_battery");
+        assert!(results[0].score > 0.8);
+        assert!(results[0].matched_fields.iter().any(|f| f.starts_with("artist: metallica (typed")));
+
+        // "metal" matches the metal genres, not the artist "Metallica" as a substring.
+        let results = encyclopedia.search("metal", 5);
+        assert_eq!(results[0].tone.id, "death_symbolic_chuck_schuldiner");
+        assert!(results.iter().all(|r| r.tone.id != "pink_floyd_comfortably_numb_solo"));
     }
 }
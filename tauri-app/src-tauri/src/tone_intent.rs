@@ -0,0 +1,1185 @@
+//! Tone Intent
+//!
+//! Reads a tone request into a typed `ToneIntent`: which sections are asked for or ruled
+//! out, relative adjustments ("a bit more gain", "less fizz") and reference artists.
+//!
+//! The text is folded to ASCII (Turkish, German and Spanish letters), split into clauses at
+//! punctuation and "but"-words, and tokenized; keywords only match whole tokens or token
+//! runs, so "od" no longer fires inside "good". Negation is scoped: a prefix ("no",
+//! "without", "ohne", "sin") covers the section right after it and any list joined with
+//! "and"/"or"; a suffix ("off", "bypassed", "kapalı", "olmasın") covers the sections just
+//! mentioned; Turkish "-siz/-suz" on the keyword itself negates it too.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToneSection {
+    Amp,
+    Gate,
+    Compressor,
+    Overdrive,
+    Distortion,
+    Eq,
+    Modulation,
+    Delay,
+    Reverb,
+}
+
+impl ToneSection {
+    pub fn label(self) -> &'static str {
+        match self {
+            ToneSection::Amp => "amp",
+            ToneSection::Gate => "gate",
+            ToneSection::Compressor => "compressor",
+            ToneSection::Overdrive => "overdrive",
+            ToneSection::Distortion => "distortion",
+            ToneSection::Eq => "eq",
+            ToneSection::Modulation => "modulation",
+            ToneSection::Delay => "delay",
+            ToneSection::Reverb => "reverb",
+        }
+    }
+
+    /// Whether an `effects[].effect_type` of a tone ("noise_gate", "tube screamer")
+    /// belongs to this section.
+    pub fn matches_effect_type(self, effect_type: &str) -> bool {
+        let t = effect_type.to_lowercase();
+        let any = |needles: &[&str]| needles.iter().any(|n| t.contains(n));
+        match self {
+            ToneSection::Amp => false,
+            ToneSection::Gate => any(&["gate"]),
+            ToneSection::Compressor => any(&["compress"]),
+            ToneSection::Overdrive => any(&["overdrive", "tubescreamer", "screamer"]) || t == "od",
+            ToneSection::Distortion => any(&["dist", "fuzz", "hm-2", "hm2"]),
+            ToneSection::Eq => any(&["eq"]),
+            ToneSection::Modulation => any(&[
+                "chorus",
+                "flanger",
+                "phaser",
+                "tremolo",
+                "vibrato",
+                "modulation",
+            ]),
+            ToneSection::Delay => any(&["delay", "echo"]),
+            ToneSection::Reverb => any(&["reverb"]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    More,
+    Less,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Amount {
+    Slight,
+    #[default]
+    Normal,
+    Strong,
+}
+
+impl Amount {
+    /// Multiplier for the size of a step.
+    pub fn scale(self) -> f64 {
+        match self {
+            Amount::Slight => 0.5,
+            Amount::Normal => 1.0,
+            Amount::Strong => 2.0,
+        }
+    }
+}
+
+/// "a bit more gain": direction, size and what to change.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Adjustment {
+    pub direction: Direction,
+    pub amount: Amount,
+    /// The word (or words) being adjusted, folded: "gain", "fizz", "low end", "bright".
+    pub term: String,
+    /// Set when the term names a section ("reverb", "gain").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<ToneSection>,
+}
+
+impl Adjustment {
+    pub fn describe(&self) -> String {
+        let amount = match self.amount {
+            Amount::Slight => "a bit ",
+            Amount::Normal => "",
+            Amount::Strong => "much ",
+        };
+        let direction = match self.direction {
+            Direction::More => "more",
+            Direction::Less => "less",
+        };
+        format!("{}{} {}", amount, direction, self.term)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ToneIntent {
+    pub requested: BTreeSet<ToneSection>,
+    pub forbidden: BTreeSet<ToneSection>,
+    /// "no effects", "dry only", "just the amp".
+    pub no_effects: bool,
+    pub adjustments: Vec<Adjustment>,
+    /// Reference artists, as written.
+    pub artists: Vec<String>,
+}
+
+impl ToneIntent {
+    pub fn parse(text: &str) -> Self {
+        Self::parse_with_artists(text, &[])
+    }
+
+    /// Like `parse`, also recognizing `known_artists` (e.g. the encyclopedia's) anywhere
+    /// in the text, not only after "like" / "gibi".
+    pub fn parse_with_artists(text: &str, known_artists: &[String]) -> Self {
+        let clauses = tokenize(text);
+        let mut intent = Self::default();
+        for clause in &clauses {
+            intent.read_clause(clause);
+        }
+        let forbidden = intent.forbidden.clone();
+        intent.requested.retain(|s| !forbidden.contains(s));
+        intent.artists = find_artists(&clauses, known_artists);
+        intent
+    }
+
+    pub fn requests(&self, section: ToneSection) -> bool {
+        self.requested.contains(&section)
+    }
+
+    pub fn forbids(&self, section: ToneSection) -> bool {
+        self.forbidden.contains(&section)
+    }
+
+    /// Sections named by adjustments ("more reverb" -> reverb).
+    pub fn target_sections(&self) -> BTreeSet<ToneSection> {
+        self.adjustments.iter().filter_map(|a| a.section).collect()
+    }
+
+    /// One line for prompts and logs; empty if nothing was recognized.
+    pub fn describe(&self) -> String {
+        let labels = |set: &BTreeSet<ToneSection>| {
+            set.iter().map(|s| s.label()).collect::<Vec<_>>().join(", ")
+        };
+        let mut parts = Vec::new();
+        if !self.requested.is_empty() {
+            parts.push(format!("requested: {}", labels(&self.requested)));
+        }
+        if !self.forbidden.is_empty() {
+            parts.push(format!("forbidden: {}", labels(&self.forbidden)));
+        }
+        if self.no_effects {
+            parts.push("no effects".to_string());
+        }
+        if !self.adjustments.is_empty() {
+            let adjustments: Vec<String> = self.adjustments.iter().map(|a| a.describe()).collect();
+            parts.push(format!("adjustments: {}", adjustments.join(", ")));
+        }
+        if !self.artists.is_empty() {
+            parts.push(format!("references: {}", self.artists.join(", ")));
+        }
+        parts.join("; ")
+    }
+
+    fn forbid(&mut self, mention: Mention) {
+        match mention {
+            Mention::Section(section) => {
+                self.forbidden.insert(section);
+            }
+            Mention::Effects => self.no_effects = true,
+        }
+    }
+
+    fn read_clause(&mut self, tokens: &[Token]) {
+        let mut negation = Negation::None;
+        // Positive mentions a trailing "off" would negate, and whether a list is open.
+        let mut recent: Vec<Mention> = Vec::new();
+        let mut list_open = false;
+        let mut amount: Option<Amount> = None;
+        let mut direction: Option<Direction> = None;
+        let mut last_term: Option<(String, Option<ToneSection>)> = None;
+
+        let mut i = 0;
+        while i < tokens.len() {
+            let (word, len) = classify(&tokens[i..], !recent.is_empty());
+            let text = join_norm(&tokens[i..i + len]);
+            i += len;
+
+            // Negated lists: "no delay or reverb", "without any chorus".
+            match (negation, &word) {
+                (Negation::Pending | Negation::AfterConnector, Word::Section(section, _)) => {
+                    self.forbid(Mention::Section(*section));
+                    negation = Negation::AfterSection;
+                    continue;
+                }
+                (Negation::Pending | Negation::AfterConnector, Word::Effects(_)) => {
+                    self.forbid(Mention::Effects);
+                    negation = Negation::AfterSection;
+                    continue;
+                }
+                (Negation::Pending, Word::Filler) => continue,
+                (Negation::AfterConnector, Word::Filler)
+                    if LIST_DETERMINERS.contains(&text.as_str()) =>
+                {
+                    continue
+                }
+                (Negation::AfterSection, Word::Connector) => {
+                    negation = Negation::AfterConnector;
+                    continue;
+                }
+                _ => negation = Negation::None,
+            }
+
+            let mut keep_list = false;
+            match word {
+                Word::NegatePrefix => {
+                    negation = Negation::Pending;
+                    recent.clear();
+                    direction = None;
+                    amount = None;
+                }
+                Word::NegateSuffix => {
+                    for mention in recent.drain(..) {
+                        self.forbid(mention);
+                    }
+                }
+                Word::NoEffects => self.no_effects = true,
+                Word::Section(section, Polarity::Without) => self.forbid(Mention::Section(section)),
+                Word::Section(section, _) => {
+                    let dir = direction.take();
+                    if let Some(dir) = dir {
+                        self.push_adjustment(dir, amount.take(), text.clone(), Some(section));
+                    }
+                    if dir != Some(Direction::Less) {
+                        self.requested.insert(section);
+                        if !list_open {
+                            recent.clear();
+                        }
+                        recent.push(Mention::Section(section));
+                    }
+                    last_term = Some((text, Some(section)));
+                }
+                Word::Effects(Polarity::Without) => self.no_effects = true,
+                Word::Effects(_) => {
+                    if !list_open {
+                        recent.clear();
+                    }
+                    recent.push(Mention::Effects);
+                }
+                Word::Connector => keep_list = !recent.is_empty(),
+                Word::Filler => keep_list = list_open,
+                Word::Amount(a) => amount = Some(a),
+                Word::Direction(d) => direction = Some(d),
+                Word::PostVerb(d) => {
+                    if let Some((term, section)) = last_term.take() {
+                        self.push_adjustment(d, amount.take(), term, section);
+                    }
+                }
+                Word::Comparative(base) => {
+                    self.push_adjustment(Direction::More, amount.take(), base.to_string(), None);
+                }
+                Word::Term => {
+                    let term = tokens[i - len].stem.clone().unwrap_or(text);
+                    if let Some(dir) = direction.take() {
+                        self.push_adjustment(dir, amount.take(), term.clone(), None);
+                    }
+                    last_term = Some((term, None));
+                }
+            }
+            list_open = keep_list;
+        }
+    }
+
+    fn push_adjustment(
+        &mut self,
+        direction: Direction,
+        amount: Option<Amount>,
+        term: String,
+        section: Option<ToneSection>,
+    ) {
+        self.adjustments.push(Adjustment {
+            direction,
+            amount: amount.unwrap_or_default(),
+            term,
+            section,
+        });
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mention {
+    Section(ToneSection),
+    Effects,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Negation {
+    None,
+    /// After "no" / "without", before the section.
+    Pending,
+    /// Right after a negated section; a connector continues the list.
+    AfterSection,
+    AfterConnector,
+}
+
+/// Turkish "-li" (with) / "-siz" (without) on a keyword.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Polarity {
+    Plain,
+    With,
+    Without,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Word {
+    Section(ToneSection, Polarity),
+    Effects(Polarity),
+    NoEffects,
+    NegatePrefix,
+    NegateSuffix,
+    Connector,
+    Filler,
+    Amount(Amount),
+    Direction(Direction),
+    /// Verb after its object (Turkish "mid'i artır", "turn the delay up").
+    PostVerb(Direction),
+    Comparative(&'static str),
+    /// Anything else; a possible adjustment target.
+    Term,
+}
+
+/// Section keywords, ASCII-folded (English, Turkish, German, Spanish).
+const SECTION_WORDS: &[(ToneSection, &[&str])] = &[
+    (
+        ToneSection::Delay,
+        &[
+            "delay",
+            "echo",
+            "slapback",
+            "tape echo",
+            "gecikme",
+            "eko",
+            "verzogerung",
+            "retardo",
+            "eco",
+        ],
+    ),
+    (
+        ToneSection::Reverb,
+        &[
+            "reverb",
+            "reverberation",
+            "hall",
+            "room",
+            "plate",
+            "spring reverb",
+            "yanki",
+            "nachhall",
+            "reverberacion",
+        ],
+    ),
+    (
+        ToneSection::Gate,
+        &["noise gate", "gate", "noisegate", "noise suppressor"],
+    ),
+    (
+        ToneSection::Eq,
+        &[
+            "eq",
+            "equalizer",
+            "equaliser",
+            "ekolayzer",
+            "ekolayzir",
+            "entzerrer",
+            "ecualizador",
+        ],
+    ),
+    (
+        ToneSection::Compressor,
+        &[
+            "compressor",
+            "compression",
+            "comp",
+            "kompresor",
+            "kompressor",
+            "kompression",
+            "compresor",
+            "compresion",
+        ],
+    ),
+    (
+        ToneSection::Overdrive,
+        &[
+            "overdrive",
+            "od",
+            "tubescreamer",
+            "tube screamer",
+            "screamer",
+            "ts9",
+            "ts808",
+            "klon",
+        ],
+    ),
+    (
+        ToneSection::Distortion,
+        &[
+            "distortion",
+            "dist",
+            "fuzz",
+            "hm 2",
+            "hm2",
+            "big muff",
+            "muff",
+            "distorsiyon",
+            "verzerrung",
+            "distorsion",
+        ],
+    ),
+    (
+        ToneSection::Modulation,
+        &[
+            "modulation",
+            "chorus",
+            "flanger",
+            "phaser",
+            "tremolo",
+            "vibrato",
+            "modulasyon",
+        ],
+    ),
+    (
+        ToneSection::Amp,
+        &[
+            "amp",
+            "amps",
+            "amplifier",
+            "amfi",
+            "anfi",
+            "verstarker",
+            "amplificador",
+            "gain",
+            "kazanc",
+        ],
+    ),
+];
+
+const EFFECT_WORDS: &[&str] = &[
+    "effects", "effect", "fx", "efekt", "efektler", "effekte", "efectos",
+];
+
+const NO_EFFECTS_PHRASES: &[&str] = &[
+    "dry only",
+    "only dry",
+    "bone dry",
+    "completely dry",
+    "totally dry",
+    "dry signal",
+    "amp only",
+    "only amp",
+    "only the amp",
+    "just amp",
+    "just the amp",
+    "efektsiz",
+    "sadece amfi",
+];
+
+const NEGATE_PREFIX: &[&str] = &[
+    "no",
+    "without",
+    "not",
+    "dont",
+    "avoid",
+    "remove",
+    "skip",
+    "kill",
+    "drop",
+    "ditch",
+    "disable",
+    "mute",
+    "turn off",
+    "switch off",
+    "ohne",
+    "kein",
+    "keine",
+    "keinen",
+    "nicht",
+    "sin",
+    "sans",
+];
+
+/// Negations after the section. "bypass" also works as a prefix when nothing precedes it.
+const NEGATE_SUFFIX: &[&str] = &[
+    "off",
+    "bypass",
+    "bypassed",
+    "disabled",
+    "kapali",
+    "kapat",
+    "yok",
+    "olmasin",
+    "istemiyorum",
+    "istemem",
+    "aus",
+    "apagado",
+    "apagada",
+];
+
+const CONNECTORS: &[&str] = &[
+    "and", "or", "nor", "ve", "veya", "ya", "und", "oder", "y", "o", "ni", "/", "&", "+",
+];
+
+/// Words skipped while looking for what a negation or "more" applies to.
+const FILLERS: &[&str] = &[
+    "the",
+    "a",
+    "an",
+    "any",
+    "some",
+    "extra",
+    "additional",
+    "my",
+    "that",
+    "this",
+    "of",
+    "keep",
+    "leave",
+    "with",
+    "turn",
+    "please",
+    "lutfen",
+    "bir",
+    "de",
+    "la",
+    "el",
+    "los",
+    "las",
+    "das",
+    "die",
+    "der",
+    "den",
+    "ein",
+    "eine",
+    "einen",
+];
+
+/// After "no delay or", only these may come before the next section.
+const LIST_DETERMINERS: &[&str] = &["any", "a", "an"];
+
+const AMOUNTS: &[(Amount, &[&str])] = &[
+    (
+        Amount::Slight,
+        &[
+            "a bit",
+            "a little",
+            "a touch",
+            "a tad",
+            "a hair",
+            "bit",
+            "little",
+            "touch",
+            "tad",
+            "slightly",
+            "somewhat",
+            "biraz",
+            "azicik",
+            "hafif",
+            "etwas",
+            "ein bisschen",
+            "bisschen",
+            "un poco",
+            "poco",
+        ],
+    ),
+    (
+        Amount::Strong,
+        &[
+            "a lot",
+            "a ton",
+            "lots",
+            "lot",
+            "much",
+            "way",
+            "far",
+            "significantly",
+            "cok",
+            "epey",
+            "viel",
+            "deutlich",
+            "mucho",
+            "muy",
+        ],
+    ),
+];
+
+const DIRECTIONS: &[(Direction, &[&str])] = &[
+    (
+        Direction::Less,
+        &[
+            "less",
+            "fewer",
+            "reduce",
+            "cut",
+            "lower",
+            "decrease",
+            "tame",
+            "turn down",
+            "bring down",
+            "back off",
+            "daha az",
+            "weniger",
+            "menos",
+        ],
+    ),
+    (
+        Direction::More,
+        &[
+            "more",
+            "add",
+            "boost",
+            "increase",
+            "raise",
+            "turn up",
+            "crank",
+            "crank up",
+            "bring up",
+            "bump up",
+            "daha fazla",
+            "daha",
+            "mehr",
+            "mas",
+        ],
+    ),
+];
+
+const POST_VERBS: &[(Direction, &[&str])] = &[
+    (Direction::More, &["up", "artir", "arttir", "yukselt"]),
+    (Direction::Less, &["down", "azalt", "dusur", "kis"]),
+];
+
+/// Comparative adjectives and the descriptor they raise.
+const COMPARATIVES: &[(&str, &str)] = &[
+    ("brighter", "bright"),
+    ("darker", "dark"),
+    ("tighter", "tight"),
+    ("looser", "loose"),
+    ("warmer", "warm"),
+    ("heavier", "heavy"),
+    ("cleaner", "clean"),
+    ("dirtier", "dirty"),
+    ("louder", "loud"),
+    ("quieter", "quiet"),
+    ("thicker", "thick"),
+    ("thinner", "thin"),
+    ("fatter", "fat"),
+    ("fuller", "full"),
+    ("punchier", "punchy"),
+    ("smoother", "smooth"),
+    ("crunchier", "crunchy"),
+    ("softer", "soft"),
+    ("harsher", "harsh"),
+    ("muddier", "muddy"),
+    ("fizzier", "fizzy"),
+    ("boomier", "boomy"),
+    ("wetter", "wet"),
+    ("drier", "dry"),
+    ("dryer", "dry"),
+];
+
+/// Multi-word adjustment targets.
+const TERM_PHRASES: &[&str] = &[
+    "low end",
+    "high end",
+    "top end",
+    "bottom end",
+    "low mids",
+    "high mids",
+    "low mid",
+    "high mid",
+];
+
+/// Suffixes a keyword may carry: English plurals and Turkish case endings (folded).
+const PLAIN_SUFFIXES: &[&str] = &[
+    "s", "es", "i", "u", "a", "e", "yi", "yu", "ya", "ye", "da", "de", "ta", "te", "in", "un",
+    "nin", "nun", "ler", "lar", "leri", "lari",
+];
+const WITH_SUFFIXES: &[&str] = &["li", "lu"];
+const WITHOUT_SUFFIXES: &[&str] = &["siz", "suz"];
+
+/// Artist cues: the artist follows ("like X") or precedes ("X gibi").
+const ARTIST_CUES_BEFORE: &[&str] = &["like", "a la", "ala", "similar to", "wie", "como"];
+const ARTIST_CUES_AFTER: &[&str] = &["gibi", "tarzi", "tarzinda"];
+
+/// Clause breaks besides punctuation.
+const CLAUSE_WORDS: &[&str] = &[
+    "but", "however", "although", "ama", "fakat", "ancak", "aber", "sondern", "pero",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    /// As written (apostrophes dropped), for artist names.
+    raw: String,
+    /// Lowercased and folded to ASCII.
+    norm: String,
+    /// Folded part before an apostrophe ("mid'i" -> "mid").
+    stem: Option<String>,
+}
+
+fn fold_char(c: char, out: &mut String) {
+    match c {
+        'İ' | 'ı' | 'í' | 'Í' | 'î' => out.push('i'),
+        'ş' | 'Ş' => out.push('s'),
+        'ğ' | 'Ğ' => out.push('g'),
+        'ç' | 'Ç' => out.push('c'),
+        'ö' | 'Ö' | 'ó' | 'Ó' | 'ô' => out.push('o'),
+        'ü' | 'Ü' | 'ú' | 'Ú' | 'û' => out.push('u'),
+        'ä' | 'Ä' | 'á' | 'Á' | 'à' | 'â' => out.push('a'),
+        'é' | 'É' | 'è' | 'ê' => out.push('e'),
+        'ñ' | 'Ñ' => out.push('n'),
+        'ß' => out.push_str("ss"),
+        c => out.extend(c.to_lowercase()),
+    }
+}
+
+/// Clauses of tokens. Apostrophes join ("don't" -> "dont"); "-" splits ("hm-2").
+fn tokenize(text: &str) -> Vec<Vec<Token>> {
+    let mut clauses = vec![Vec::new()];
+    let mut raw = String::new();
+    let mut norm = String::new();
+    let mut stem: Option<String> = None;
+
+    fn flush(
+        clauses: &mut [Vec<Token>],
+        raw: &mut String,
+        norm: &mut String,
+        stem: &mut Option<String>,
+    ) {
+        if !norm.is_empty() {
+            let clause = clauses.last_mut().expect("at least one clause");
+            clause.push(Token {
+                raw: std::mem::take(raw),
+                norm: std::mem::take(norm),
+                stem: stem.take(),
+            });
+        }
+        raw.clear();
+        *stem = None;
+    }
+
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            raw.push(c);
+            fold_char(c, &mut norm);
+        } else if matches!(c, '\'' | '’') {
+            if !norm.is_empty() && stem.is_none() {
+                stem = Some(norm.clone());
+            }
+        } else {
+            flush(&mut clauses, &mut raw, &mut norm, &mut stem);
+            if matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | '(' | ')' | '\n') {
+                clauses.push(Vec::new());
+            } else if matches!(c, '/' | '&' | '+') {
+                let symbol = c.to_string();
+                clauses
+                    .last_mut()
+                    .expect("at least one clause")
+                    .push(Token {
+                        raw: symbol.clone(),
+                        norm: symbol,
+                        stem: None,
+                    });
+            }
+        }
+    }
+    flush(&mut clauses, &mut raw, &mut norm, &mut stem);
+
+    // "but" and friends also end a clause.
+    let mut split = Vec::new();
+    for clause in clauses {
+        let mut current = Vec::new();
+        for token in clause {
+            if CLAUSE_WORDS.contains(&token.norm.as_str()) {
+                split.push(std::mem::take(&mut current));
+            } else {
+                current.push(token);
+            }
+        }
+        split.push(current);
+    }
+    split.retain(|c| !c.is_empty());
+    split
+}
+
+/// Folded words of a text ("Lo-Fi, glassy" -> ["lo", "fi", "glassy"]), for matching
+/// keyword tables kept in the same form.
+pub fn words(text: &str) -> Vec<String> {
+    tokenize(text).into_iter().flatten().map(|t| t.norm).collect()
+}
+
+fn join_norm(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|t| t.norm.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Token count of `phrase` if the tokens start with it.
+fn phrase_len(tokens: &[Token], phrase: &str) -> Option<usize> {
+    let words: Vec<&str> = phrase.split(' ').collect();
+    (tokens.len() >= words.len() && words.iter().zip(tokens).all(|(w, t)| t.norm == *w))
+        .then_some(words.len())
+}
+
+/// Longest phrase of `table` the tokens start with.
+fn longest(tokens: &[Token], table: &[&str]) -> Option<usize> {
+    table.iter().filter_map(|p| phrase_len(tokens, p)).max()
+}
+
+/// A single-word keyword, possibly with a suffix ("reverbsuz", "delays", "gaini").
+fn keyword_polarity(token: &Token, keywords: &[&str]) -> Option<Polarity> {
+    if token.stem.as_deref().is_some_and(|s| keywords.contains(&s)) {
+        return Some(Polarity::Plain);
+    }
+    keywords
+        .iter()
+        .filter(|k| !k.contains(' ') && k.len() >= 4)
+        .find_map(|k| {
+            let suffix = token.norm.strip_prefix(k)?;
+            if PLAIN_SUFFIXES.contains(&suffix) {
+                Some(Polarity::Plain)
+            } else if WITH_SUFFIXES.contains(&suffix) {
+                Some(Polarity::With)
+            } else if WITHOUT_SUFFIXES.contains(&suffix) {
+                Some(Polarity::Without)
+            } else {
+                None
+            }
+        })
+}
+
+/// "400hz", "2.5khz" (split at the dot) or "400 hz".
+fn frequency_len(tokens: &[Token]) -> Option<usize> {
+    let first = &tokens.first()?.norm;
+    let starts_numeric = first.starts_with(|c: char| c.is_ascii_digit());
+    if starts_numeric && (first.ends_with("hz")) {
+        return Some(1);
+    }
+    let unit = tokens.get(1).map(|t| t.norm.as_str());
+    (starts_numeric && matches!(unit, Some("hz" | "khz"))).then_some(2)
+}
+
+fn classify(tokens: &[Token], has_recent: bool) -> (Word, usize) {
+    if let Some(len) = longest(tokens, NO_EFFECTS_PHRASES) {
+        return (Word::NoEffects, len);
+    }
+
+    // Longest keyword phrase wins ("noise gate" over "gate", "tube screamer").
+    let section = SECTION_WORDS
+        .iter()
+        .filter_map(|(section, words)| longest(tokens, words).map(|len| (*section, len)))
+        .max_by_key(|(_, len)| *len);
+    if let Some((section, len)) = section {
+        return (Word::Section(section, Polarity::Plain), len);
+    }
+    if let Some(len) = frequency_len(tokens) {
+        return (Word::Section(ToneSection::Eq, Polarity::Plain), len);
+    }
+    if longest(tokens, EFFECT_WORDS).is_some() {
+        return (Word::Effects(Polarity::Plain), 1);
+    }
+    if let Some((section, polarity)) = SECTION_WORDS
+        .iter()
+        .find_map(|(section, words)| keyword_polarity(&tokens[0], words).map(|p| (*section, p)))
+    {
+        return (Word::Section(section, polarity), 1);
+    }
+    if let Some(polarity) = keyword_polarity(&tokens[0], EFFECT_WORDS) {
+        return (Word::Effects(polarity), 1);
+    }
+
+    if tokens[0].norm == "bypass" && !has_recent {
+        return (Word::NegatePrefix, 1);
+    }
+    if let Some(len) = longest(tokens, NEGATE_PREFIX) {
+        return (Word::NegatePrefix, len);
+    }
+    if let Some(len) = longest(tokens, NEGATE_SUFFIX) {
+        return (Word::NegateSuffix, len);
+    }
+
+    // Amounts before fillers ("a bit" is not the filler "a").
+    if let Some((amount, len)) = AMOUNTS
+        .iter()
+        .filter_map(|(amount, words)| longest(tokens, words).map(|len| (*amount, len)))
+        .max_by_key(|(_, len)| *len)
+    {
+        return (Word::Amount(amount), len);
+    }
+    if let Some((direction, len)) = DIRECTIONS
+        .iter()
+        .filter_map(|(direction, words)| longest(tokens, words).map(|len| (*direction, len)))
+        .max_by_key(|(_, len)| *len)
+    {
+        return (Word::Direction(direction), len);
+    }
+    if let Some((direction, _)) = POST_VERBS
+        .iter()
+        .find(|(_, words)| words.contains(&tokens[0].norm.as_str()))
+    {
+        return (Word::PostVerb(*direction), 1);
+    }
+    if let Some((_, base)) = COMPARATIVES.iter().find(|(c, _)| *c == tokens[0].norm) {
+        return (Word::Comparative(base), 1);
+    }
+    if longest(tokens, CONNECTORS).is_some() {
+        return (Word::Connector, 1);
+    }
+    if longest(tokens, FILLERS).is_some() {
+        return (Word::Filler, 1);
+    }
+    (Word::Term, longest(tokens, TERM_PHRASES).unwrap_or(1))
+}
+
+/// Known artists anywhere (single-word names only when capitalized), plus capitalized
+/// names after "like" / before "gibi".
+fn find_artists(clauses: &[Vec<Token>], known_artists: &[String]) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    let add = |name: String, found: &mut Vec<String>| {
+        if !found.iter().any(|f| f.eq_ignore_ascii_case(&name)) {
+            found.push(name);
+        }
+    };
+    let capitalized = |t: &Token| t.raw.starts_with(|c: char| c.is_uppercase());
+
+    for clause in clauses {
+        let mut i = 0;
+        while i < clause.len() {
+            let rest = &clause[i..];
+            let known = known_artists.iter().find_map(|artist| {
+                let folded: Vec<Token> = tokenize(artist).into_iter().flatten().collect();
+                let len = folded.len();
+                let matches = len > 0
+                    && rest.len() >= len
+                    && folded.iter().zip(rest).all(|(a, t)| a.norm == t.norm)
+                    && (len > 1 || capitalized(&rest[0]));
+                matches.then_some((artist.clone(), len))
+            });
+            if let Some((artist, len)) = known {
+                add(artist, &mut found);
+                i += len;
+                continue;
+            }
+
+            if let Some(len) = longest(rest, ARTIST_CUES_BEFORE) {
+                // "like Metallica or Guns N' Roses": names joined by connectors.
+                i += len;
+                loop {
+                    let name: Vec<&str> = clause[i..]
+                        .iter()
+                        .take_while(|t| capitalized(t))
+                        .map(|t| t.raw.as_str())
+                        .collect();
+                    if name.is_empty() {
+                        break;
+                    }
+                    add(name.join(" "), &mut found);
+                    i += name.len();
+                    let joined = clause
+                        .get(i)
+                        .is_some_and(|t| CONNECTORS.contains(&t.norm.as_str()))
+                        && clause.get(i + 1).is_some_and(capitalized);
+                    if !joined {
+                        break;
+                    }
+                    i += 1;
+                }
+                continue;
+            }
+            if longest(rest, ARTIST_CUES_AFTER).is_some() {
+                let name: Vec<&str> = clause[..i]
+                    .iter()
+                    .rev()
+                    .take_while(|t| capitalized(t))
+                    .map(|t| t.raw.as_str())
+                    .collect();
+                if !name.is_empty() {
+                    add(
+                        name.into_iter().rev().collect::<Vec<_>>().join(" "),
+                        &mut found,
+                    );
+                }
+            }
+            i += 1;
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ToneSection::*;
+
+    /// (request, requested, forbidden, no effects)
+    const CORPUS: &[(&str, &[ToneSection], &[ToneSection], bool)] = &[
+        ("Good crunchy rhythm tone", &[], &[], false),
+        (
+            "Battery tone with delay and reverb",
+            &[Delay, Reverb],
+            &[],
+            false,
+        ),
+        ("no delay, lots of reverb", &[Reverb], &[Delay], false),
+        (
+            "keep the delay and reverb off",
+            &[],
+            &[Delay, Reverb],
+            false,
+        ),
+        (
+            "without any chorus or flanger, add a tube screamer",
+            &[Overdrive],
+            &[Modulation],
+            false,
+        ),
+        ("the noise gate should be bypassed", &[], &[Gate], false),
+        (
+            "bypass the compressor and boost the mids",
+            &[],
+            &[Compressor],
+            false,
+        ),
+        ("no effects please", &[], &[], true),
+        ("dry only, just the amp", &[], &[], true),
+        ("EQ: cut 400 Hz a bit and boost 3kHz", &[Eq], &[], false),
+        ("slapback echo on a clean amp", &[Delay, Amp], &[], false),
+        ("hm-2 chainsaw, no gate", &[Distortion], &[Gate], false),
+        (
+            "Reverb kapalı, biraz daha delay",
+            &[Delay],
+            &[Reverb],
+            false,
+        ),
+        (
+            "delaysiz ve reverbsüz temiz bir ton",
+            &[],
+            &[Delay, Reverb],
+            false,
+        ),
+        (
+            "Gate olmasın ama kompresör olsun",
+            &[Compressor],
+            &[Gate],
+            false,
+        ),
+        ("efektsiz, sadece amfi", &[], &[], true),
+        (
+            "Ohne Hall, aber mit Kompressor",
+            &[Compressor],
+            &[Reverb],
+            false,
+        ),
+        (
+            "Sin reverb, con un poco de delay",
+            &[Delay],
+            &[Reverb],
+            false,
+        ),
+    ];
+
+    #[test]
+    fn corpus_sections() {
+        for (text, requested, forbidden, no_effects) in CORPUS {
+            let intent = ToneIntent::parse(text);
+            let want = |s: &[ToneSection]| s.iter().copied().collect::<BTreeSet<_>>();
+            assert_eq!(intent.requested, want(requested), "requested in {:?}", text);
+            assert_eq!(intent.forbidden, want(forbidden), "forbidden in {:?}", text);
+            assert_eq!(intent.no_effects, *no_effects, "no_effects in {:?}", text);
+        }
+    }
+
+    #[test]
+    fn reads_adjustments() {
+        let adj = |direction, amount, term: &str, section| Adjustment {
+            direction,
+            amount,
+            term: term.to_string(),
+            section,
+        };
+        let cases: &[(&str, Vec<Adjustment>)] = &[
+            (
+                "a bit more gain and much less fizz",
+                vec![
+                    adj(Direction::More, Amount::Slight, "gain", Some(Amp)),
+                    adj(Direction::Less, Amount::Strong, "fizz", None),
+                ],
+            ),
+            (
+                "tighter low end, daha az reverb",
+                vec![
+                    adj(Direction::More, Amount::Normal, "tight", None),
+                    adj(Direction::Less, Amount::Normal, "reverb", Some(Reverb)),
+                ],
+            ),
+            (
+                "more low end",
+                vec![adj(Direction::More, Amount::Normal, "low end", None)],
+            ),
+            (
+                "mid'i biraz artır",
+                vec![adj(Direction::More, Amount::Slight, "mid", None)],
+            ),
+            (
+                "turn the delay up",
+                vec![adj(Direction::More, Amount::Normal, "delay", Some(Delay))],
+            ),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(&ToneIntent::parse(text).adjustments, expected, "{:?}", text);
+        }
+
+        let intent = ToneIntent::parse("a bit more gain, less reverb");
+        assert_eq!(
+            intent.target_sections(),
+            [Amp, Reverb].into_iter().collect()
+        );
+        assert!(!intent.requests(Reverb));
+        assert_eq!(
+            intent.describe(),
+            "requested: amp; adjustments: a bit more gain, less reverb"
+        );
+    }
+
+    #[test]
+    fn finds_reference_artists() {
+        let known = vec![
+            "Metallica".to_string(),
+            "Van Halen".to_string(),
+            "Tool".to_string(),
+        ];
+        let intent = ToneIntent::parse_with_artists(
+            "van halen brown sound but tighter, like Metallica or Guns N' Roses",
+            &known,
+        );
+        assert_eq!(
+            intent.artists,
+            vec!["Van Halen", "Metallica", "Guns N Roses"]
+        );
+        assert!(ToneIntent::parse_with_artists("use a tool for it", &known)
+            .artists
+            .is_empty());
+        assert_eq!(
+            ToneIntent::parse("John Mayer gibi temiz bir ton").artists,
+            vec!["John Mayer"]
+        );
+    }
+}
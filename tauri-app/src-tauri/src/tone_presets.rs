@@ -0,0 +1,458 @@
+//! ToneForge Presets
+//!
+//! A REAPER-project-independent preset format: one track's FX chain (plugin names,
+//! order, enable state, every normalized parameter plus its display string), stored
+//! as JSON files in a local library with tags.
+//!
+//! This module only describes and plans; `preset_recall` talks to REAPER.
+
+use crate::parameter_ai::{ReaperParameter, ReaperPlugin, ReaperSnapshot};
+use crate::rpp_parser::is_raw_slider;
+use crate::snapshot_diff::{match_param, match_plugins};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PRESET_FORMAT_VERSION: u32 = 1;
+
+/// Normalized deltas smaller than this are not rewritten on recall.
+const RECALL_EPSILON: f64 = 1e-4;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TonePreset {
+    pub format_version: u32,
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: u64,
+    #[serde(default)]
+    pub source_track_name: String,
+    pub plugins: Vec<PresetPlugin>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetPlugin {
+    pub name: String,
+    pub enabled: bool,
+    pub params: Vec<PresetParam>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetParam {
+    pub index: i32,
+    pub name: String,
+    pub value: f64,
+    #[serde(default)]
+    pub display: String,
+}
+
+/// Lightweight listing entry (no parameter payload).
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetSummary {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub plugin_count: usize,
+    pub created_at: u64,
+}
+
+impl TonePreset {
+    /// Capture a preset from a full track snapshot (see `act_mode::collect_reaper_snapshot`).
+    pub fn from_snapshot(name: &str, description: &str, tags: &[String], snapshot: &ReaperSnapshot) -> Self {
+        let mut tags: Vec<String> = tags
+            .iter()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+        tags.sort();
+        tags.dedup();
+
+        Self {
+            format_version: PRESET_FORMAT_VERSION,
+            id: preset_id(name),
+            name: name.trim().to_string(),
+            description: description.trim().to_string(),
+            tags,
+            created_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            source_track_name: snapshot.track_name.clone(),
+            plugins: snapshot
+                .plugins
+                .iter()
+                .map(|p| PresetPlugin {
+                    name: p.name.clone(),
+                    enabled: p.enabled,
+                    params: p
+                        .parameters
+                        .iter()
+                        .filter(|param| !is_raw_slider(param))
+                        .map(|param| PresetParam {
+                            index: param.index,
+                            name: param.name.clone(),
+                            value: param.current_value,
+                            display: param.display_value.clone(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// View the preset as a snapshot of `track_index` (for diffing against a live track).
+    pub fn to_snapshot(&self, track_index: i32) -> ReaperSnapshot {
+        ReaperSnapshot {
+            track_index,
+            track_name: self.source_track_name.clone(),
+            plugins: self
+                .plugins
+                .iter()
+                .enumerate()
+                .map(|(i, p)| ReaperPlugin {
+                    index: i as i32,
+                    name: p.name.clone(),
+                    enabled: p.enabled,
+                    parameters: p
+                        .params
+                        .iter()
+                        .map(|param| ReaperParameter {
+                            index: param.index,
+                            name: param.name.clone(),
+                            current_value: param.value,
+                            display_value: param.display.clone(),
+                            unit: String::new(),
+                            format_hint: String::new(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim().to_lowercase();
+        self.tags.contains(&tag)
+    }
+
+    pub fn summary(&self) -> PresetSummary {
+        PresetSummary {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            tags: self.tags.clone(),
+            plugin_count: self.plugins.len(),
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// File-safe id derived from the preset name ("Master of Puppets!" -> "master-of-puppets").
+pub fn preset_id(name: &str) -> String {
+    let mut id = String::new();
+    for c in name.trim().to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            id.push(c);
+        } else if !id.is_empty() && !id.ends_with('-') {
+            id.push('-');
+        }
+    }
+    let id = id.trim_end_matches('-').to_string();
+    if id.is_empty() {
+        "preset".to_string()
+    } else {
+        id
+    }
+}
+
+/// Directory-backed preset library (one `<id>.json` per preset).
+#[derive(Debug, Clone)]
+pub struct PresetLibrary {
+    dir: PathBuf,
+}
+
+impl PresetLibrary {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path_for(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", preset_id(id)))
+    }
+
+    /// Save (or overwrite) a preset. Returns the file path.
+    pub fn save(&self, preset: &TonePreset) -> Result<PathBuf, String> {
+        fs::create_dir_all(&self.dir).map_err(|e| format!("Failed to create preset dir: {}", e))?;
+
+        let path = self.path_for(&preset.id);
+        let content =
+            serde_json::to_string_pretty(preset).map_err(|e| format!("Failed to serialize preset: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write preset: {}", e))?;
+        Ok(path)
+    }
+
+    pub fn load(&self, id: &str) -> Result<TonePreset, String> {
+        let path = self.path_for(id);
+        let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read preset '{}': {}", id, e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse preset '{}': {}", id, e))
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        let path = self.path_for(id);
+        if !path.exists() {
+            return Err(format!("Preset '{}' not found", id));
+        }
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete preset: {}", e))
+    }
+
+    /// List presets sorted by name, optionally filtered by tag. Unreadable files are skipped.
+    pub fn list(&self, tag: Option<&str>) -> Result<Vec<PresetSummary>, String> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let entries = fs::read_dir(&self.dir).map_err(|e| format!("Failed to read preset dir: {}", e))?;
+        let mut out = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let Ok(preset) = serde_json::from_str::<TonePreset>(&content) else {
+                continue;
+            };
+            if tag.map(|t| preset.has_tag(t)).unwrap_or(true) {
+                out.push(preset.summary());
+            }
+        }
+
+        out.sort_by_key(|a| a.name.to_lowercase());
+        Ok(out)
+    }
+}
+
+// ==================== RECALL PLANNING ====================
+
+/// Plugin names from the preset that have no counterpart on the live track.
+pub fn missing_plugins(preset: &TonePreset, live: &ReaperSnapshot) -> Vec<String> {
+    let target = preset.to_snapshot(live.track_index);
+    let pairs = match_plugins(&target.plugins, &live.plugins);
+
+    target
+        .plugins
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !pairs.iter().any(|(ia, _)| ia == i))
+        .map(|(_, p)| p.name.clone())
+        .collect()
+}
+
+/// Sequential `(from, to)` moves that bring the preset's plugins to the front of the chain,
+/// in preset order. Each move is relative to the chain after the previous moves
+/// (REAPER move semantics: remove at `from`, insert at `to`). Plugins not in the preset
+/// end up after the preset chain.
+pub fn plan_reorder(preset: &TonePreset, live: &ReaperSnapshot) -> Vec<(i32, i32)> {
+    let target = preset.to_snapshot(live.track_index);
+    let pairs = match_plugins(&target.plugins, &live.plugins);
+
+    // Current order as live-slot ids.
+    let mut order: Vec<usize> = (0..live.plugins.len()).collect();
+    let mut moves = Vec::new();
+
+    for (to, (_, live_slot)) in pairs.iter().enumerate() {
+        let Some(from) = order.iter().position(|id| id == live_slot) else {
+            continue;
+        };
+        if from != to {
+            let id = order.remove(from);
+            order.insert(to, id);
+            moves.push((from as i32, to as i32));
+        }
+    }
+
+    moves
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecallToggle {
+    pub fx_index: i32,
+    pub fx_name: String,
+    pub was_enabled: bool,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecallParamWrite {
+    pub fx_index: i32,
+    pub fx_name: String,
+    pub param_index: i32,
+    pub param_name: String,
+    pub old_value: f64,
+    pub new_value: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecallSettings {
+    pub toggles: Vec<RecallToggle>,
+    pub params: Vec<RecallParamWrite>,
+    /// Preset parameters that could not be found on the live plugin.
+    pub unmatched_params: Vec<String>,
+}
+
+/// Enable-state and parameter writes needed to make the live chain match the preset.
+/// Live plugins that are not part of the preset are bypassed so the recalled chain is
+/// what you hear.
+pub fn plan_settings(preset: &TonePreset, live: &ReaperSnapshot) -> RecallSettings {
+    let target = preset.to_snapshot(live.track_index);
+    let pairs = match_plugins(&target.plugins, &live.plugins);
+    let mut settings = RecallSettings::default();
+
+    for (ia, ib) in &pairs {
+        let want = &target.plugins[*ia];
+        let have = &live.plugins[*ib];
+
+        if want.enabled != have.enabled {
+            settings.toggles.push(RecallToggle {
+                fx_index: have.index,
+                fx_name: have.name.clone(),
+                was_enabled: have.enabled,
+                enabled: want.enabled,
+            });
+        }
+
+        for param in &want.parameters {
+            let Some(live_param) = match_param(param, &have.parameters) else {
+                settings
+                    .unmatched_params
+                    .push(format!("{} :: {}", want.name, param.name));
+                continue;
+            };
+            if (live_param.current_value - param.current_value).abs() > RECALL_EPSILON {
+                settings.params.push(RecallParamWrite {
+                    fx_index: have.index,
+                    fx_name: have.name.clone(),
+                    param_index: live_param.index,
+                    param_name: live_param.name.clone(),
+                    old_value: live_param.current_value,
+                    new_value: param.current_value,
+                });
+            }
+        }
+    }
+
+    for (ib, have) in live.plugins.iter().enumerate() {
+        if have.enabled && !pairs.iter().any(|(_, b)| *b == ib) {
+            settings.toggles.push(RecallToggle {
+                fx_index: have.index,
+                fx_name: have.name.clone(),
+                was_enabled: true,
+                enabled: false,
+            });
+        }
+    }
+
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{param, plugin, snapshot};
+
+    fn preset_chain() -> TonePreset {
+        let source = snapshot(vec![
+            plugin(0, "ReaGate (Cockos)", true, vec![param(0, "Threshold", 0.3)]),
+            plugin(1, "VST3: Amp", true, vec![param(0, "Gain", 0.8)]),
+            plugin(2, "ReaEQ (Cockos)", false, vec![param(0, "Band 1 Gain", 0.4)]),
+        ]);
+        TonePreset::from_snapshot("Thrash Rhythm", "", &["Metal".to_string(), " metal ".to_string()], &source)
+    }
+
+    #[test]
+    fn captures_snapshot_and_normalizes_tags() {
+        let preset = preset_chain();
+        assert_eq!(preset.id, "thrash-rhythm");
+        assert_eq!(preset.tags, vec!["metal".to_string()]);
+        assert!(preset.has_tag("METAL"));
+        assert_eq!(preset.plugins.len(), 3);
+        assert_eq!(preset.plugins[1].params[0].display, "80%");
+        assert_eq!(preset_id("  Master of Puppets!  "), "master-of-puppets");
+    }
+
+    #[test]
+    fn library_roundtrip_and_tag_filter() {
+        let dir = std::env::temp_dir().join(format!("toneforge-presets-test-{}", std::process::id()));
+        let library = PresetLibrary::new(&dir);
+
+        let preset = preset_chain();
+        library.save(&preset).unwrap();
+
+        let loaded = library.load("Thrash Rhythm").unwrap();
+        assert_eq!(loaded.plugins.len(), 3);
+        assert_eq!(library.list(None).unwrap().len(), 1);
+        assert_eq!(library.list(Some("metal")).unwrap().len(), 1);
+        assert!(library.list(Some("ambient")).unwrap().is_empty());
+
+        library.delete(&preset.id).unwrap();
+        assert!(library.list(None).unwrap().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn plans_loads_reorder_and_settings() {
+        let preset = preset_chain();
+
+        // Live track: EQ and amp swapped, gate missing, extra delay enabled.
+        let live = snapshot(vec![
+            plugin(0, "ReaEQ (Cockos)", true, vec![param(0, "Band 1 Gain", 0.4)]),
+            plugin(1, "VST3: Amp", true, vec![param(0, "Gain", 0.5)]),
+            plugin(2, "ReaDelay (Cockos)", true, vec![]),
+        ]);
+        assert_eq!(missing_plugins(&preset, &live), vec!["ReaGate (Cockos)".to_string()]);
+
+        // After loading, the gate sits at the end.
+        let mut loaded = live.clone();
+        loaded
+            .plugins
+            .push(plugin(3, "ReaGate (Cockos)", true, vec![param(0, "Threshold", 0.5)]));
+        let moves = plan_reorder(&preset, &loaded);
+
+        // Apply moves to a name list and check the resulting order.
+        let mut names: Vec<String> = loaded.plugins.iter().map(|p| p.name.clone()).collect();
+        for (from, to) in &moves {
+            let n = names.remove(*from as usize);
+            names.insert(*to as usize, n);
+        }
+        assert_eq!(
+            names,
+            vec!["ReaGate (Cockos)", "VST3: Amp", "ReaEQ (Cockos)", "ReaDelay (Cockos)"]
+        );
+
+        let reordered = snapshot(vec![
+            plugin(0, "ReaGate (Cockos)", true, vec![param(0, "Threshold", 0.5)]),
+            plugin(1, "VST3: Amp", true, vec![param(0, "Gain", 0.5)]),
+            plugin(2, "ReaEQ (Cockos)", true, vec![param(0, "Band 1 Gain", 0.4)]),
+            plugin(3, "ReaDelay (Cockos)", true, vec![]),
+        ]);
+        let settings = plan_settings(&preset, &reordered);
+        assert_eq!(settings.params.len(), 2);
+        assert!(settings.params.iter().any(|w| w.fx_index == 1 && (w.new_value - 0.8).abs() < 1e-9));
+        // EQ disabled per preset, extra delay bypassed.
+        assert_eq!(settings.toggles.len(), 2);
+        assert!(settings.toggles.iter().all(|t| !t.enabled));
+        assert!(settings.unmatched_params.is_empty());
+    }
+}
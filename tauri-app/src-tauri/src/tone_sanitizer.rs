@@ -6,6 +6,10 @@
 //! - Canonicalize common key/effect synonyms
 //! - Cap list sizes so downstream mapping stays deterministic
 
+use crate::cab_model::CabParameters;
+use crate::eq_model::EqBand;
+use crate::param_curve::split_unit_key;
+use crate::routing::{Routing, MAX_PARALLEL_PATHS};
 use crate::tone_encyclopedia::{EffectParameters, ToneParameters};
 use std::collections::HashMap;
 
@@ -23,6 +27,9 @@ pub fn sanitize(mut parameters: ToneParameters) -> SanitizedTone {
     parameters.delay = sanitize_unit_map(parameters.delay, "delay", &mut warnings, 64);
 
     parameters.eq = sanitize_eq_map(parameters.eq, &mut warnings, 32);
+    parameters.eq_bands = sanitize_eq_bands(parameters.eq_bands, &mut warnings, 16);
+    parameters.cab = sanitize_cab(parameters.cab, &mut warnings);
+    parameters.routing = sanitize_routing(parameters.routing, &mut warnings);
     parameters.effects = sanitize_effects(parameters.effects, &mut warnings, 12, 48);
 
     SanitizedTone { parameters, warnings }
@@ -86,6 +93,16 @@ fn sanitize_unit_map(
             continue;
         }
 
+        // Unit targets ("threshold_db") carry physical values; only the key is canonicalized.
+        if let Some((base, _, _)) = split_unit_key(&k) {
+            let suffix = &k.trim()[base.len() + 1..];
+            let canonical_base = canonical_param_key(group, base).unwrap_or_else(|| base.to_string());
+            if out.len() < max_keys {
+                out.insert(format!("{}_{}", canonical_base, suffix.to_lowercase()), v);
+            }
+            continue;
+        }
+
         let canonical_key = canonical_param_key(group, &k).unwrap_or_else(|| k.trim().to_string());
         let clamped = v.clamp(0.0, 1.0);
         if (clamped - v).abs() > f64::EPSILON {
@@ -140,6 +157,122 @@ fn sanitize_eq_map(mut map: HashMap<String, f64>, warnings: &mut Vec<String>, ma
     pairs.into_iter().collect()
 }
 
+fn sanitize_eq_bands(mut bands: Vec<EqBand>, warnings: &mut Vec<String>, max_bands: usize) -> Vec<EqBand> {
+    bands.retain(|b| {
+        let finite = [Some(b.freq_hz), Some(b.gain_db), b.q, b.slope_db_oct]
+            .into_iter()
+            .flatten()
+            .all(f64::is_finite);
+        if !finite {
+            warnings.push(format!("eq_bands: dropped band with non-finite values at {} Hz", b.freq_hz));
+        }
+        finite
+    });
+
+    for band in &mut bands {
+        let freq = band.freq_hz.clamp(20.0, 20_000.0);
+        let gain = band.gain_db.clamp(-12.0, 12.0);
+        if (freq - band.freq_hz).abs() > f64::EPSILON || (gain - band.gain_db).abs() > f64::EPSILON {
+            warnings.push(format!(
+                "eq_bands: clamped {:.0} Hz {:+.1} dB to {:.0} Hz {:+.1} dB",
+                band.freq_hz, band.gain_db, freq, gain
+            ));
+        }
+        band.freq_hz = freq;
+        band.gain_db = gain;
+        band.q = band.q.map(|q| q.clamp(0.1, 40.0));
+        band.slope_db_oct = band.slope_db_oct.map(|slope| slope.clamp(6.0, 96.0));
+    }
+
+    if bands.len() > max_bands {
+        warnings.push(format!("eq_bands: too many bands; keeping first {}", max_bands));
+        bands.truncate(max_bands);
+    }
+    bands
+}
+
+fn sanitize_cab(mut cab: CabParameters, warnings: &mut Vec<String>) -> CabParameters {
+    for (field, text) in [
+        ("speaker", &mut cab.speaker),
+        ("mic", &mut cab.mic),
+        ("ir_path", &mut cab.ir_path),
+    ] {
+        if let Some(value) = text.take() {
+            let trimmed = value.trim();
+            if trimmed.is_empty() {
+                warnings.push(format!("cab: dropped empty {}", field));
+            } else {
+                *text = Some(trimmed.to_string());
+            }
+        }
+    }
+
+    if let Some(position) = cab.mic_position {
+        if !position.is_finite() {
+            warnings.push("cab: dropped non-finite mic_position".to_string());
+            cab.mic_position = None;
+        } else if !(0.0..=1.0).contains(&position) {
+            warnings.push(format!("cab: clamped mic_position from {:.2}", position));
+            cab.mic_position = Some(position.clamp(0.0, 1.0));
+        }
+    }
+
+    for (field, hz) in [("low_cut_hz", &mut cab.low_cut_hz), ("high_cut_hz", &mut cab.high_cut_hz)] {
+        match *hz {
+            Some(value) if !value.is_finite() => {
+                warnings.push(format!("cab: dropped non-finite {}", field));
+                *hz = None;
+            }
+            Some(value) => *hz = Some(value.clamp(20.0, 20_000.0)),
+            None => {}
+        }
+    }
+    if let (Some(low), Some(high)) = (cab.low_cut_hz, cab.high_cut_hz) {
+        if low >= high {
+            warnings.push(format!(
+                "cab: low cut {:.0} Hz is not below high cut {:.0} Hz; dropped both",
+                low, high
+            ));
+            cab.low_cut_hz = None;
+            cab.high_cut_hz = None;
+        }
+    }
+
+    cab
+}
+
+fn sanitize_routing(mut routing: Routing, warnings: &mut Vec<String>) -> Routing {
+    let level = |db: f64| if db.is_finite() { db.clamp(-60.0, 12.0) } else { 0.0 };
+    let pan = |pan: f64| if pan.is_finite() { pan.clamp(-1.0, 1.0) } else { 0.0 };
+
+    routing.paths.retain(|p| {
+        let keep = p.chain.iter().any(|entry| !entry.trim().is_empty());
+        if !keep {
+            warnings.push(format!("routing: dropped path '{}' without plugins", p.name));
+        }
+        keep
+    });
+    if routing.paths.len() > MAX_PARALLEL_PATHS {
+        warnings.push(format!("routing: too many paths; keeping first {}", MAX_PARALLEL_PATHS));
+        routing.paths.truncate(MAX_PARALLEL_PATHS);
+    }
+    for (i, path) in routing.paths.iter_mut().enumerate() {
+        if path.name.trim().is_empty() {
+            path.name = format!("Path {}", i + 1);
+        }
+        path.chain.retain(|entry| !entry.trim().is_empty());
+        let (volume_db, pan_value) = (level(path.volume_db), pan(path.pan));
+        if volume_db != path.volume_db || pan_value != path.pan {
+            warnings.push(format!("routing: clamped level/pan of path '{}'", path.name));
+        }
+        path.volume_db = volume_db;
+        path.pan = pan_value;
+    }
+    routing.dry_db = routing.dry_db.map(level);
+    routing.dry_pan = routing.dry_pan.map(pan);
+    routing
+}
+
 fn canonical_effect_type(effect_type: &str) -> String {
     let t = normalize_token(effect_type);
     match t.as_str() {
@@ -0,0 +1,306 @@
+//! Tone Vocabulary
+//!
+//! What descriptive words mean ("fizzy", "glassy", "brown sound"): the frequency region
+//! they live in, EQ and knob moves that add more of them, the amp gain range they imply
+//! and effects that usually come with them. The curated list lives in the versioned
+//! `tone_vocabulary.json` next to `tone_encyclopedia.json`; the copy built into the app is
+//! the fallback. Tone AI puts the matched entries into its prompt and keeps amp gain inside
+//! their ranges, and relative edits take their moves from here.
+
+use crate::eq_model::parse_frequency_hz;
+use crate::tone_intent::words;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+const BUILTIN_VOCABULARY: &str = include_str!("../../../tone_vocabulary.json");
+
+/// Major version of the file format this build reads.
+pub const SUPPORTED_VERSION: u32 = 1;
+
+/// Endings tried when a word is not listed as written ("tizi", "chugs").
+const WORD_SUFFIXES: &[&str] = &["yi", "yu", "i", "u", "s", "es"];
+
+/// Boost (positive steps) or cut around a frequency.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EqMove {
+    pub freq_hz: f64,
+    pub steps: f64,
+}
+
+/// Turn the control for a tone key ("presence", "mix") on the best plugin of a role.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KnobMove {
+    pub role: String,
+    pub key: String,
+    pub steps: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VocabularyTerm {
+    /// Spellings, folded like `tone_intent::words` ("brown sound", "tiz").
+    pub words: Vec<String>,
+    pub description: String,
+    #[serde(default)]
+    pub region_hz: Option<[f64; 2]>,
+    /// Moves that add more of the quality; "less" reverses them.
+    #[serde(default)]
+    pub eq: Vec<EqMove>,
+    #[serde(default)]
+    pub knobs: Vec<KnobMove>,
+    /// Normalized amp gain range the word implies.
+    #[serde(default)]
+    pub gain: Option<[f64; 2]>,
+    /// Effect types that usually come with it.
+    #[serde(default)]
+    pub effects: Vec<String>,
+}
+
+impl VocabularyTerm {
+    /// Whether a relative edit can realize the word.
+    pub fn has_moves(&self) -> bool {
+        !self.eq.is_empty() || !self.knobs.is_empty()
+    }
+
+    /// One prompt line: "glassy: hi-fi clean ...; 3 kHz-8 kHz; amp gain 0.00-0.30; ...".
+    pub fn describe(&self) -> String {
+        let mut line = format!("{}: {}", self.words[0], self.description);
+        if let Some([low, high]) = self.region_hz {
+            line.push_str(&format!("; {}-{}", format_hz(low), format_hz(high)));
+        }
+        if let Some([low, high]) = self.gain {
+            line.push_str(&format!("; amp gain {:.2}-{:.2}", low, high));
+        }
+        if !self.effects.is_empty() {
+            line.push_str(&format!("; often with {}", self.effects.join(", ")));
+        }
+        line
+    }
+}
+
+fn format_hz(hz: f64) -> String {
+    if hz >= 1000.0 {
+        format!("{} kHz", hz / 1000.0)
+    } else {
+        format!("{} Hz", hz)
+    }
+}
+
+/// Geometric center of a frequency region.
+fn region_center([low, high]: [f64; 2]) -> f64 {
+    (low * high).sqrt()
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToneVocabulary {
+    pub version: String,
+    pub terms: Vec<VocabularyTerm>,
+}
+
+impl Default for ToneVocabulary {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl ToneVocabulary {
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN_VOCABULARY).expect("built-in tone_vocabulary.json is valid")
+    }
+
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("Failed to read {}: {}", path.as_ref().display(), e))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut vocabulary: Self =
+            serde_json::from_str(text).map_err(|e| format!("Invalid tone vocabulary: {}", e))?;
+        let major = vocabulary
+            .version
+            .split('.')
+            .next()
+            .and_then(|v| v.trim().parse::<u32>().ok());
+        if major != Some(SUPPORTED_VERSION) {
+            return Err(format!(
+                "Unsupported tone vocabulary version '{}' (expected {}.x)",
+                vocabulary.version, SUPPORTED_VERSION
+            ));
+        }
+        for term in &mut vocabulary.terms {
+            term.words = term.words.iter().map(|w| words(w).join(" ")).collect();
+            term.words.retain(|w| !w.is_empty());
+            let name = term
+                .words
+                .first()
+                .cloned()
+                .ok_or("Vocabulary term without words")?;
+            if term
+                .region_hz
+                .is_some_and(|[low, high]| !(0.0 < low && low < high))
+            {
+                return Err(format!("'{}': region_hz must be [low, high] in Hz", name));
+            }
+            if term
+                .gain
+                .is_some_and(|[low, high]| !(0.0 <= low && low <= high && high <= 1.0))
+            {
+                return Err(format!("'{}': gain must be [low, high] within 0..1", name));
+            }
+        }
+        Ok(vocabulary)
+    }
+
+    /// Entry for a single (folded) word or phrase, also trying common endings.
+    pub fn lookup(&self, word: &str) -> Option<&VocabularyTerm> {
+        let exact = |w: &str| self.terms.iter().find(|t| t.words.iter().any(|tw| tw == w));
+        exact(word).or_else(|| {
+            WORD_SUFFIXES
+                .iter()
+                .filter_map(|s| word.strip_suffix(s))
+                .find_map(exact)
+        })
+    }
+
+    /// Entries whose words appear in `text`, in order of first appearance.
+    pub fn find_in(&self, text: &str) -> Vec<&VocabularyTerm> {
+        let tokens = words(text);
+        let mut found: Vec<&VocabularyTerm> = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            // Longest phrase first ("brown sound" over "brown"), then the single word.
+            let phrase = self
+                .terms
+                .iter()
+                .flat_map(|t| t.words.iter().map(move |w| (t, w)))
+                .filter_map(|(t, w)| {
+                    let parts: Vec<&str> = w.split(' ').collect();
+                    let matches = tokens.len() >= i + parts.len()
+                        && parts.iter().zip(&tokens[i..]).all(|(p, t)| p == t);
+                    matches.then_some((t, parts.len()))
+                })
+                .max_by_key(|(_, len)| *len);
+            let (term, len) = match phrase {
+                Some(hit) => hit,
+                None => match self.lookup(&tokens[i]) {
+                    Some(term) => (term, 1),
+                    None => {
+                        i += 1;
+                        continue;
+                    }
+                },
+            };
+            if !found.iter().any(|f| std::ptr::eq(*f, term)) {
+                found.push(term);
+            }
+            i += len;
+        }
+        found
+    }
+
+    /// Prompt section explaining the descriptive words in a request; empty if none.
+    pub fn prompt_hints(&self, text: &str) -> String {
+        self.find_in(text)
+            .iter()
+            .map(|t| format!("- {}", t.describe()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// `eq` map with descriptor keys ("mud": -3.0) moved to the center of their region
+    /// ("300Hz"); frequency keys and unknown words are kept as they are.
+    pub fn resolve_eq_keys(&self, eq: &HashMap<String, f64>) -> HashMap<String, f64> {
+        let mut resolved = HashMap::new();
+        for (key, db) in eq {
+            let region = match parse_frequency_hz(key) {
+                Some(_) => None,
+                None => self.lookup(&words(key).join(" ")).and_then(|t| t.region_hz),
+            };
+            let key = match region {
+                Some(region) => format!("{:.0}Hz", region_center(region)),
+                None => key.clone(),
+            };
+            *resolved.entry(key).or_insert(0.0) += db;
+        }
+        resolved
+    }
+
+    /// Amp gain range implied by the request: the overlap of every matched range, `None`
+    /// if nothing implies one or the ranges contradict each other.
+    pub fn gain_range(&self, text: &str) -> Option<[f64; 2]> {
+        let mut ranges = self.find_in(text).into_iter().filter_map(|t| t.gain);
+        let first = ranges.next()?;
+        ranges.try_fold(first, |[low, high], [l, h]| {
+            let overlap = [low.max(l), high.min(h)];
+            (overlap[0] <= overlap[1]).then_some(overlap)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_vocabulary_matches_words_and_phrases() {
+        let vocabulary = ToneVocabulary::builtin();
+        assert_eq!(vocabulary.version, "1.0");
+
+        let found: Vec<&str> = vocabulary
+            .find_in("Brown sound, a bit chuggy, lo-fi and Glassy cleans")
+            .iter()
+            .map(|t| t.words[0].as_str())
+            .collect();
+        assert_eq!(
+            found,
+            vec!["brown sound", "chug", "lofi", "glassy", "clean"]
+        );
+        assert_eq!(vocabulary.lookup("tizi"), vocabulary.lookup("treble"));
+        assert!(vocabulary.lookup("fizz").is_some_and(|t| t.has_moves()));
+
+        let hints = vocabulary.prompt_hints("woman tone");
+        assert!(hints.starts_with("- woman tone: Clapton-style"));
+        assert!(hints.contains("500 Hz-1.5 kHz; amp gain 0.60-0.85; often with reverb"));
+        assert!(vocabulary.prompt_hints("Metallica Battery").is_empty());
+    }
+
+    #[test]
+    fn gain_ranges_intersect() {
+        let vocabulary = ToneVocabulary::builtin();
+        assert_eq!(vocabulary.gain_range("heavy modern chug"), Some([0.7, 0.9]));
+        assert_eq!(vocabulary.gain_range("glassy"), Some([0.0, 0.3]));
+        // Contradicting words imply nothing.
+        assert_eq!(vocabulary.gain_range("glassy djent"), None);
+        assert_eq!(vocabulary.gain_range("more fizz"), None);
+    }
+
+    #[test]
+    fn descriptor_eq_keys_land_in_their_region() {
+        let vocabulary = ToneVocabulary::builtin();
+        let eq = HashMap::from([
+            ("Mud".to_string(), -3.0),
+            ("300Hz".to_string(), -1.0),
+            ("high mids".to_string(), 2.0),
+            ("warmth".to_string(), 1.0),
+        ]);
+        let resolved = vocabulary.resolve_eq_keys(&eq);
+        assert_eq!(resolved.get("283Hz"), Some(&-3.0));
+        assert_eq!(resolved.get("300Hz"), Some(&-1.0));
+        assert_eq!(resolved.get("2121Hz"), Some(&2.0));
+        assert_eq!(resolved.get("warmth"), Some(&1.0));
+    }
+
+    #[test]
+    fn rejects_unknown_versions_and_bad_ranges() {
+        let term = r#"{"words": ["x"], "description": "", "gain": [0.8, 0.2]}"#;
+        let v2 = format!(r#"{{"version": "2.0", "terms": [{}]}}"#, term);
+        assert!(ToneVocabulary::parse(&v2).unwrap_err().contains("version"));
+        let bad = format!(r#"{{"version": "1.1", "terms": [{}]}}"#, term);
+        assert!(ToneVocabulary::parse(&bad).unwrap_err().contains("gain"));
+    }
+}
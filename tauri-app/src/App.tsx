@@ -14,7 +14,7 @@ import { Tooltip } from "./components/Tooltip";
 import { SkeletonChannels, SkeletonFxList } from "./components/Skeleton";
 import { LiveRunMonitor, UiLogEvent } from "./components/LiveRunMonitor";
 import { useNotificationSound } from "./hooks/useNotificationSound";
-import { ChatResponse, ChangeEntry, SecureConfig } from "./types";
+import { ChatResponse, ChangeEntry, ReaperChangedEvent, SecureConfig } from "./types";
 import "./App.css";
 
 const PROVIDERS = [
@@ -72,6 +72,7 @@ function App() {
   const [apiKey, setApiKey] = useState("");
   const [apiKeySet, setApiKeySet] = useState(false);
   const [customInstructions, setCustomInstructions] = useState("");
+  const [gainStaging, setGainStaging] = useState(false);
   const [reaperConnected, setReaperConnected] = useState(false);
   const [loading, setLoading] = useState(false);
   const [tracksLoading, setTracksLoading] = useState(true);
@@ -89,6 +90,7 @@ function App() {
   });
   const [toasts, setToasts] = useState<ToastMessage[]>([]);
   const [tracks, setTracks] = useState<TrackInfo[]>([]);
+  const [reaperChangeTick, setReaperChangeTick] = useState(0);
   const [selectedTrack, setSelectedTrack] = useState(0);
   const [provider, setProvider] = useState<ProviderKey>(DEFAULT_PROVIDER);
   const [model, setModel] = useState(DEFAULT_MODEL);
@@ -208,6 +210,34 @@ function App() {
     };
   }, []);
 
+  // Live REAPER change feed: refresh the FX overview when a chain changes outside ToneForge
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    listen<ReaperChangedEvent>("toneforge:reaper_changed", (event) => {
+      const { all_tracks, structural_tracks, changes } = event.payload;
+      const bypassToggled = changes.some((change) => change.kind === "enabled");
+      if (all_tracks || structural_tracks.length > 0 || bypassToggled) {
+        setReaperChangeTick((tick) => tick + 1);
+      }
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((e) => {
+        console.error("Failed to listen for REAPER changes:", e);
+      });
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
+  useEffect(() => {
+    if (reaperChangeTick > 0 && reaperConnected) {
+      loadTrackOverview();
+    }
+  }, [reaperChangeTick]);
+
   useEffect(() => {
     if (messages.length) {
       localStorage.setItem(HISTORY_STORAGE_KEY, JSON.stringify(messages));
@@ -404,6 +434,7 @@ function App() {
         message: payload,
         track: selectedTrack,
         customInstructions,
+        gainStaging,
       });
 
       const response: ChatResponse = JSON.parse(responseString);
@@ -595,6 +626,15 @@ function App() {
                       />
                       <small>Applied to every AI request and saved securely.</small>
                     </div>
+                    <label className="custom-instructions" htmlFor="gain-staging">
+                      <input
+                        id="gain-staging"
+                        type="checkbox"
+                        checked={gainStaging}
+                        onChange={(e) => setGainStaging(e.target.checked)}
+                      />{" "}
+                      Gain staging (plays the item under the edit cursor)
+                    </label>
                     <RecentTones onSelectTone={handleSelectRecentTone} />
                   </div>
 
@@ -15,8 +15,17 @@ export function UndoRedo({ onAction }: UndoRedoProps) {
     redo_description: null,
     undo_count: 0,
     redo_count: 0,
+    project_path: null,
+    stale_count: 0,
+    undo_stale_reason: null,
+    redo_stale_reason: null,
+    current_id: null,
+    nodes: [],
+    checkpoints: [],
+    persist_error: null,
   });
   const [loading, setLoading] = useState(false);
+  const [abSide, setAbSide] = useState<"before" | "after" | null>(null);
 
   const fetchState = useCallback(async () => {
     try {
@@ -34,7 +43,7 @@ export function UndoRedo({ onAction }: UndoRedoProps) {
   }, [fetchState]);
 
   const handleUndo = async () => {
-    if (!state.can_undo || loading) return;
+    if (!state.can_undo || loading || abSide) return;
     setLoading(true);
     try {
       const result = await invoke<string>("perform_undo");
@@ -48,7 +57,7 @@ export function UndoRedo({ onAction }: UndoRedoProps) {
   };
 
   const handleRedo = async () => {
-    if (!state.can_redo || loading) return;
+    if (!state.can_redo || loading || abSide) return;
     setLoading(true);
     try {
       const result = await invoke<string>("perform_redo");
@@ -61,6 +70,63 @@ export function UndoRedo({ onAction }: UndoRedoProps) {
     }
   };
 
+  const handleDiscardStale = async () => {
+    if (loading) return;
+    try {
+      await invoke<string>("discard_stale_undo");
+      await fetchState();
+    } catch (error) {
+      console.error("Discard stale history failed:", error);
+    }
+  };
+
+  const handleSaveCheckpoint = async () => {
+    const name = window.prompt("Checkpoint name", "pre-mix");
+    if (!name) return;
+    try {
+      await invoke<string>("set_undo_checkpoint", { name });
+      await fetchState();
+    } catch (error) {
+      console.error("Save checkpoint failed:", error);
+    }
+  };
+
+  const handleJumpToCheckpoint = async (name: string) => {
+    if (!name || loading) return;
+    setLoading(true);
+    try {
+      const result = await invoke<string>("jump_to_undo_checkpoint", { name });
+      onAction?.("undo", result);
+      await fetchState();
+    } catch (error) {
+      console.error("Jump to checkpoint failed:", error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const runAb = async (command: string, args: Record<string, unknown>, next: typeof abSide) => {
+    if (loading) return;
+    setLoading(true);
+    try {
+      const result = await invoke<string>(command, args);
+      setAbSide(next);
+      onAction?.(next === "before" ? "undo" : "redo", result);
+      await fetchState();
+    } catch (error) {
+      console.error("A/B compare failed:", error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleAbToggle = () =>
+    abSide === null
+      ? runAb("start_ab_compare", {}, "after")
+      : runAb("switch_ab_compare", {}, abSide === "after" ? "before" : "after");
+
+  const handleAbKeep = (keep: "before" | "after") => runAb("end_ab_compare", { keep }, null);
+
   // Keyboard shortcuts
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
@@ -81,15 +147,21 @@ export function UndoRedo({ onAction }: UndoRedoProps) {
 
     window.addEventListener("keydown", handleKeyDown);
     return () => window.removeEventListener("keydown", handleKeyDown);
-  }, [state.can_undo, state.can_redo, loading]);
+  }, [state.can_undo, state.can_redo, loading, abSide]);
 
   return (
     <div className="undo-redo-container">
       <button
         className={`undo-btn ${!state.can_undo || loading ? "disabled" : ""}`}
         onClick={handleUndo}
-        disabled={!state.can_undo || loading}
-        title={state.undo_description ? `Undo: ${state.undo_description}` : "Nothing to undo (Ctrl+Z)"}
+        disabled={!state.can_undo || loading || !!abSide}
+        title={
+          state.undo_stale_reason
+            ? `Stale: ${state.undo_stale_reason}`
+            : state.undo_description
+              ? `Undo: ${state.undo_description}`
+              : "Nothing to undo (Ctrl+Z)"
+        }
       >
         <svg viewBox="0 0 24 24" width="16" height="16" fill="currentColor">
           <path d="M12.5 8c-2.65 0-5.05.99-6.9 2.6L2 7v9h9l-3.62-3.62c1.39-1.16 3.16-1.88 5.12-1.88 3.54 0 6.55 2.31 7.6 5.5l2.37-.78C21.08 11.03 17.15 8 12.5 8z" />
@@ -99,14 +171,82 @@ export function UndoRedo({ onAction }: UndoRedoProps) {
       <button
         className={`redo-btn ${!state.can_redo || loading ? "disabled" : ""}`}
         onClick={handleRedo}
-        disabled={!state.can_redo || loading}
-        title={state.redo_description ? `Redo: ${state.redo_description}` : "Nothing to redo (Ctrl+Shift+Z)"}
+        disabled={!state.can_redo || loading || !!abSide}
+        title={
+          state.redo_stale_reason
+            ? `Stale: ${state.redo_stale_reason}`
+            : state.redo_description
+              ? `Redo: ${state.redo_description}`
+              : "Nothing to redo (Ctrl+Shift+Z)"
+        }
       >
         <svg viewBox="0 0 24 24" width="16" height="16" fill="currentColor">
           <path d="M18.4 10.6C16.55 8.99 14.15 8 11.5 8c-4.65 0-8.58 3.03-9.96 7.22L3.9 16c1.05-3.19 4.05-5.5 7.6-5.5 1.95 0 3.73.72 5.12 1.88L13 16h9V7l-3.6 3.6z" />
         </svg>
         {state.redo_count > 0 && <span className="count">{state.redo_count}</span>}
       </button>
+      <button
+        className={`checkpoint-btn ${abSide ? "active" : ""}`}
+        onClick={handleAbToggle}
+        disabled={loading || (!abSide && !state.can_undo)}
+        title={
+          abSide
+            ? `Playing ${abSide === "after" ? "B (after)" : "A (before)"}, loudness-matched. Click to switch.`
+            : "Loudness-matched A/B of the last change (plays the item under the edit cursor)"
+        }
+      >
+        {abSide === "before" ? "A" : abSide === "after" ? "B" : "A/B"}
+      </button>
+      {abSide && (
+        <>
+          <button className="checkpoint-btn" onClick={() => handleAbKeep("before")} disabled={loading} title="Keep the chain from before the change">
+            Keep A
+          </button>
+          <button className="checkpoint-btn" onClick={() => handleAbKeep("after")} disabled={loading} title="Keep the changed chain">
+            Keep B
+          </button>
+        </>
+      )}
+      <button
+        className="checkpoint-btn"
+        onClick={handleSaveCheckpoint}
+        title="Name the current position so you can come back to it"
+      >
+        +
+      </button>
+      {state.checkpoints.length > 0 && (
+        <select
+          className="checkpoint-select"
+          value=""
+          disabled={loading}
+          onChange={(e) => handleJumpToCheckpoint(e.target.value)}
+          title="Jump to a checkpoint (other branches are kept)"
+        >
+          <option value="">Checkpoints</option>
+          {state.checkpoints.map((checkpoint) => (
+            <option key={checkpoint.name} value={checkpoint.name}>
+              {checkpoint.node_id === state.current_id ? `• ${checkpoint.name}` : checkpoint.name}
+            </option>
+          ))}
+        </select>
+      )}
+      {state.persist_error && (
+        <span
+          className="persist-error"
+          title={`Undo history could not be saved: ${state.persist_error}`}
+        >
+          History not saved
+        </span>
+      )}
+      {state.stale_count > 0 && (
+        <button
+          className="stale-btn"
+          onClick={handleDiscardStale}
+          title={`${state.stale_count} history entries no longer match the REAPER project. Click to discard them.`}
+        >
+          {state.stale_count} stale
+        </button>
+      )}
     </div>
   );
 }
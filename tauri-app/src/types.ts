@@ -22,6 +22,30 @@ export interface UndoState {
   redo_description: string | null;
   undo_count: number;
   redo_count: number;
+  project_path: string | null;
+  stale_count: number;
+  undo_stale_reason: string | null;
+  redo_stale_reason: string | null;
+  current_id: string | null;
+  nodes: UndoNodeSummary[];
+  checkpoints: UndoCheckpoint[];
+  persist_error: string | null;
+}
+
+export interface UndoNodeSummary {
+  id: string;
+  parent_id: string | null;
+  description: string;
+  change_count: number;
+  timestamp: number;
+  applied: boolean;
+  stale_reason: string | null;
+}
+
+export interface UndoCheckpoint {
+  name: string;
+  node_id: string | null;
+  timestamp: number;
 }
 
 export interface UndoActionSummary {
@@ -69,3 +93,21 @@ export interface KeyboardShortcut {
   description: string;
   action: () => void;
 }
+
+export interface ReaperChange {
+  seq: number;
+  kind: "param" | "enabled" | "chain" | "tracks";
+  track: number | null;
+  fx: number | null;
+  param: number | null;
+  value: number | null;
+  enabled: boolean | null;
+}
+
+export interface ReaperChangedEvent {
+  seq: number;
+  tracks: number[];
+  structural_tracks: number[];
+  all_tracks: boolean;
+  changes: ReaperChange[];
+}
@@ -1,7 +1,14 @@
+pub mod undo_executor;
+pub mod undo_history;
 pub mod undo_redo;
+pub mod undo_tree;
 
+pub use undo_executor::{
+    UndoBackend, UndoDirection, UndoExecution, UndoExecutor, UndoOp, UndoOpFailure,
+};
+pub use undo_history::{history_file_name, validate_jump, LiveChains, UndoHistory};
 pub use undo_redo::{
-    FxMoveChange, FxToggleChange, ParameterChange, PluginChange, UndoAction, UndoActionSummary,
-    UndoManager, UndoState,
+    FxMoveChange, FxToggleChange, ParameterChange, PluginChange, PluginParamState, PluginState,
+    UndoAction, UndoActionSummary, UndoManager, UndoNodeSummary, UndoState,
 };
-
+pub use undo_tree::{UndoCheckpoint, UndoJump, UndoNode, UndoTree};
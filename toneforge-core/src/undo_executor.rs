@@ -0,0 +1,772 @@
+//! Undo/Redo Execution
+//!
+//! Turns an `UndoAction` into an ordered list of operations and replays them against a
+//! small backend trait (REAPER in the app, a fake chain in tests).
+//!
+//! Ordering:
+//! - undo: params, toggles (post-action indices) -> moves reversed -> loads/removals reversed
+//! - redo: loads/removals -> moves -> toggles, params
+//!
+//! Recorded FX indices describe the chain as it was planned. If an operation fails, or
+//! REAPER puts a re-added plugin somewhere other than its recorded slot, later indices
+//! would point at the wrong FX; the executor tracks planned vs actual chain positions
+//! per track and translates every index before use.
+
+use crate::undo_redo::{PluginChange, PluginState, UndoAction};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+
+/// Operations the executor needs from the DAW. Indices are live FX indices.
+pub trait UndoBackend {
+    fn set_param(
+        &self,
+        track: i32,
+        fx_index: i32,
+        param_index: i32,
+        value: f64,
+    ) -> impl Future<Output = Result<(), String>> + Send;
+
+    fn set_fx_enabled(
+        &self,
+        track: i32,
+        fx_index: i32,
+        enabled: bool,
+    ) -> impl Future<Output = Result<(), String>> + Send;
+
+    fn move_fx(
+        &self,
+        track: i32,
+        from: i32,
+        to: i32,
+    ) -> impl Future<Output = Result<(), String>> + Send;
+
+    /// Load a plugin; returns the slot it landed in.
+    fn add_plugin(
+        &self,
+        track: i32,
+        plugin_name: &str,
+    ) -> impl Future<Output = Result<i32, String>> + Send;
+
+    fn remove_plugin(
+        &self,
+        track: i32,
+        fx_index: i32,
+    ) -> impl Future<Output = Result<(), String>> + Send;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UndoDirection {
+    Undo,
+    Redo,
+}
+
+/// One step of an undo/redo replay. FX indices are as recorded (planned).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UndoOp {
+    SetParam {
+        track: i32,
+        fx_index: i32,
+        param_index: i32,
+        param_name: String,
+        value: f64,
+    },
+    SetEnabled {
+        track: i32,
+        fx_index: i32,
+        enabled: bool,
+    },
+    MoveFx {
+        track: i32,
+        from: i32,
+        to: i32,
+    },
+    AddPlugin {
+        track: i32,
+        fx_index: i32,
+        plugin_name: String,
+        /// Restored after the plugin is placed (undo of a removal).
+        state: Option<PluginState>,
+    },
+    RemovePlugin {
+        track: i32,
+        fx_index: i32,
+        plugin_name: String,
+    },
+}
+
+impl UndoOp {
+    pub fn describe(&self) -> String {
+        match self {
+            UndoOp::SetParam {
+                fx_index,
+                param_name,
+                value,
+                ..
+            } => format!("Set FX {} '{}' to {:.3}", fx_index, param_name, value),
+            UndoOp::SetEnabled {
+                fx_index, enabled, ..
+            } => format!(
+                "{} FX {}",
+                if *enabled { "Enable" } else { "Bypass" },
+                fx_index
+            ),
+            UndoOp::MoveFx { from, to, .. } => format!("Move FX {} -> {}", from, to),
+            UndoOp::AddPlugin {
+                fx_index,
+                plugin_name,
+                ..
+            } => format!("Add '{}' at FX {}", plugin_name, fx_index),
+            UndoOp::RemovePlugin {
+                fx_index,
+                plugin_name,
+                ..
+            } => format!("Remove '{}' at FX {}", plugin_name, fx_index),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UndoOpFailure {
+    pub op: UndoOp,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UndoExecution {
+    pub direction: UndoDirection,
+    pub description: String,
+    pub applied: usize,
+    pub failures: Vec<UndoOpFailure>,
+}
+
+impl UndoExecution {
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+pub struct UndoExecutor<'a, B: UndoBackend> {
+    backend: &'a B,
+}
+
+impl<'a, B: UndoBackend + Sync> UndoExecutor<'a, B> {
+    pub fn new(backend: &'a B) -> Self {
+        Self { backend }
+    }
+
+    /// Ordered operations that revert (`Undo`) or re-apply (`Redo`) an action.
+    pub fn plan(action: &UndoAction, direction: UndoDirection) -> Vec<UndoOp> {
+        let mut ops = Vec::new();
+        match direction {
+            UndoDirection::Undo => {
+                push_settings(&mut ops, action, direction);
+                for mv in action.fx_moves.iter().rev() {
+                    ops.push(UndoOp::MoveFx {
+                        track: mv.track,
+                        from: mv.to_fx_index,
+                        to: mv.from_fx_index,
+                    });
+                }
+                for change in action.plugin_changes.iter().rev() {
+                    ops.push(plugin_op(change, !change.was_loaded));
+                }
+            }
+            UndoDirection::Redo => {
+                for change in &action.plugin_changes {
+                    ops.push(plugin_op(change, change.was_loaded));
+                }
+                for mv in &action.fx_moves {
+                    ops.push(UndoOp::MoveFx {
+                        track: mv.track,
+                        from: mv.from_fx_index,
+                        to: mv.to_fx_index,
+                    });
+                }
+                push_settings(&mut ops, action, direction);
+            }
+        }
+        ops
+    }
+
+    pub async fn undo(&self, action: &UndoAction) -> UndoExecution {
+        self.execute(action, UndoDirection::Undo).await
+    }
+
+    pub async fn redo(&self, action: &UndoAction) -> UndoExecution {
+        self.execute(action, UndoDirection::Redo).await
+    }
+
+    async fn execute(&self, action: &UndoAction, direction: UndoDirection) -> UndoExecution {
+        let mut execution = UndoExecution {
+            direction,
+            description: action.description.clone(),
+            applied: 0,
+            failures: Vec::new(),
+        };
+        let mut tracks: HashMap<i32, ChainTracker> = HashMap::new();
+
+        for op in Self::plan(action, direction) {
+            let tracker = tracks.entry(op_track(&op)).or_default();
+            match self.run_op(&op, tracker).await {
+                Ok(()) => execution.applied += 1,
+                Err(error) => execution.failures.push(UndoOpFailure { op, error }),
+            }
+        }
+
+        execution
+    }
+
+    async fn run_op(&self, op: &UndoOp, tracker: &mut ChainTracker) -> Result<(), String> {
+        match op {
+            UndoOp::SetParam {
+                track,
+                fx_index,
+                param_index,
+                value,
+                ..
+            } => {
+                let fx = tracker.actual_index(*fx_index)?;
+                self.backend
+                    .set_param(*track, fx, *param_index, *value)
+                    .await
+            }
+            UndoOp::SetEnabled {
+                track,
+                fx_index,
+                enabled,
+            } => {
+                let fx = tracker.actual_index(*fx_index)?;
+                self.backend.set_fx_enabled(*track, fx, *enabled).await
+            }
+            UndoOp::MoveFx { track, from, to } => {
+                let (actual_from, actual_to) = tracker.plan_move(*from, *to)?;
+                let result = if actual_from == actual_to {
+                    Ok(())
+                } else {
+                    self.backend.move_fx(*track, actual_from, actual_to).await
+                };
+                if result.is_ok() {
+                    tracker.actual_move(actual_from, actual_to);
+                }
+                result
+            }
+            UndoOp::RemovePlugin {
+                track, fx_index, ..
+            } => {
+                let actual = tracker.actual_index(*fx_index);
+                let token = tracker.planned_remove(*fx_index);
+                let actual = actual?;
+                self.backend.remove_plugin(*track, actual).await?;
+                tracker.actual_remove(token);
+                Ok(())
+            }
+            UndoOp::AddPlugin {
+                track,
+                fx_index,
+                plugin_name,
+                state,
+            } => {
+                let token = tracker.planned_insert(*fx_index);
+                let slot = self.backend.add_plugin(*track, plugin_name).await?;
+                tracker.actual_insert(slot, token);
+
+                // REAPER appends; move the plugin to where the plan expects it.
+                let mut fx = slot;
+                let target = tracker.placement_for(token);
+                if target != slot {
+                    match self.backend.move_fx(*track, slot, target).await {
+                        Ok(()) => {
+                            tracker.actual_move(slot, target);
+                            fx = target;
+                        }
+                        Err(e) => {
+                            return Err(format!(
+                                "Loaded '{}' but could not place it: {}",
+                                plugin_name, e
+                            ))
+                        }
+                    }
+                }
+
+                match state {
+                    Some(state) => self.restore_state(*track, fx, plugin_name, state).await,
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl<B: UndoBackend + Sync> UndoExecutor<'_, B> {
+    async fn restore_state(
+        &self,
+        track: i32,
+        fx: i32,
+        plugin_name: &str,
+        state: &PluginState,
+    ) -> Result<(), String> {
+        let mut errors = Vec::new();
+        for param in &state.params {
+            if let Err(e) = self
+                .backend
+                .set_param(track, fx, param.param_index, param.value)
+                .await
+            {
+                errors.push(format!("{}: {}", param.param_name, e));
+            }
+        }
+        if !state.enabled {
+            if let Err(e) = self.backend.set_fx_enabled(track, fx, false).await {
+                errors.push(format!("bypass: {}", e));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Re-added '{}' but {} of {} setting(s) failed to restore: {}",
+                plugin_name,
+                errors.len(),
+                state.params.len() + usize::from(!state.enabled),
+                errors.join("; ")
+            ))
+        }
+    }
+}
+
+fn push_settings(ops: &mut Vec<UndoOp>, action: &UndoAction, direction: UndoDirection) {
+    for change in &action.parameter_changes {
+        ops.push(UndoOp::SetParam {
+            track: change.track,
+            fx_index: change.fx_index,
+            param_index: change.param_index,
+            param_name: change.param_name.clone(),
+            value: match direction {
+                UndoDirection::Undo => change.old_value,
+                UndoDirection::Redo => change.new_value,
+            },
+        });
+    }
+    for toggle in &action.fx_toggles {
+        ops.push(UndoOp::SetEnabled {
+            track: toggle.track,
+            fx_index: toggle.fx_index,
+            enabled: match direction {
+                UndoDirection::Undo => toggle.was_enabled,
+                UndoDirection::Redo => !toggle.was_enabled,
+            },
+        });
+    }
+}
+
+fn plugin_op(change: &PluginChange, add: bool) -> UndoOp {
+    if add {
+        UndoOp::AddPlugin {
+            track: change.track,
+            fx_index: change.fx_index,
+            plugin_name: change.plugin_name.clone(),
+            state: change.state.clone(),
+        }
+    } else {
+        UndoOp::RemovePlugin {
+            track: change.track,
+            fx_index: change.fx_index,
+            plugin_name: change.plugin_name.clone(),
+        }
+    }
+}
+
+fn op_track(op: &UndoOp) -> i32 {
+    match op {
+        UndoOp::SetParam { track, .. }
+        | UndoOp::SetEnabled { track, .. }
+        | UndoOp::MoveFx { track, .. }
+        | UndoOp::AddPlugin { track, .. }
+        | UndoOp::RemovePlugin { track, .. } => *track,
+    }
+}
+
+/// Planned vs actual FX order for one track, as identity tokens.
+///
+/// Both chains start identical; they diverge only when an operation fails or REAPER
+/// places something differently. FX beyond the known region are untouched and map 1:1
+/// onto the tail of the actual chain.
+#[derive(Debug, Default)]
+struct ChainTracker {
+    planned: Vec<u32>,
+    actual: Vec<u32>,
+    next_token: u32,
+}
+
+impl ChainTracker {
+    fn fresh(&mut self) -> u32 {
+        self.next_token += 1;
+        self.next_token
+    }
+
+    /// Grow both chains so planned index `index` is known.
+    fn ensure_planned(&mut self, index: usize) {
+        while self.planned.len() <= index {
+            let token = self.fresh();
+            self.planned.push(token);
+            self.actual.push(token);
+        }
+    }
+
+    fn ensure_actual(&mut self, index: usize) {
+        while self.actual.len() <= index {
+            let token = self.fresh();
+            self.planned.push(token);
+            self.actual.push(token);
+        }
+    }
+
+    fn position(&self, token: u32) -> Option<i32> {
+        self.actual
+            .iter()
+            .position(|t| *t == token)
+            .map(|p| p as i32)
+    }
+
+    fn actual_index(&mut self, planned: i32) -> Result<i32, String> {
+        if planned < 0 {
+            return Err(format!("Invalid FX index {}", planned));
+        }
+        self.ensure_planned(planned as usize);
+        let token = self.planned[planned as usize];
+        self.position(token)
+            .ok_or_else(|| format!("FX {} is missing after an earlier failed step", planned))
+    }
+
+    /// Where `token` belongs in the actual chain: right after its planned predecessor.
+    fn placement_for(&self, token: u32) -> i32 {
+        let planned_pos = self.planned.iter().position(|t| *t == token).unwrap_or(0);
+        let without: Vec<u32> = self
+            .actual
+            .iter()
+            .copied()
+            .filter(|t| *t != token)
+            .collect();
+        self.planned[..planned_pos]
+            .iter()
+            .rev()
+            .find_map(|prev| without.iter().position(|t| t == prev))
+            .map(|p| p as i32 + 1)
+            .unwrap_or(0)
+    }
+
+    fn plan_move(&mut self, from: i32, to: i32) -> Result<(i32, i32), String> {
+        if from < 0 || to < 0 {
+            return Err(format!("Invalid move {} -> {}", from, to));
+        }
+        let actual_from = self.actual_index(from)?;
+        self.ensure_planned(to as usize);
+
+        let token = self.planned.remove(from as usize);
+        self.planned.insert(to as usize, token);
+        Ok((actual_from, self.placement_for(token)))
+    }
+
+    fn actual_move(&mut self, from: i32, to: i32) {
+        self.ensure_actual(from.max(to) as usize);
+        let token = self.actual.remove(from as usize);
+        self.actual.insert(to as usize, token);
+    }
+
+    fn planned_remove(&mut self, index: i32) -> Option<u32> {
+        if index < 0 {
+            return None;
+        }
+        self.ensure_planned(index as usize);
+        Some(self.planned.remove(index as usize))
+    }
+
+    fn actual_remove(&mut self, token: Option<u32>) {
+        if let Some(token) = token {
+            self.actual.retain(|t| *t != token);
+        }
+    }
+
+    fn planned_insert(&mut self, index: i32) -> u32 {
+        let index = index.max(0) as usize;
+        if index > 0 {
+            self.ensure_planned(index - 1);
+        }
+        let token = self.fresh();
+        self.planned.insert(index.min(self.planned.len()), token);
+        token
+    }
+
+    fn actual_insert(&mut self, slot: i32, token: u32) {
+        let slot = slot.max(0) as usize;
+        if slot > 0 {
+            self.ensure_actual(slot - 1);
+        }
+        self.actual.insert(slot.min(self.actual.len()), token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::undo_redo::{FxMoveChange, FxToggleChange, ParameterChange, PluginParamState};
+    use std::collections::HashSet;
+    use std::pin::pin;
+    use std::sync::Mutex;
+    use std::task::{Context, Poll, Waker};
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(out) = future.as_mut().poll(&mut cx) {
+                return out;
+            }
+        }
+    }
+
+    /// (name, enabled, params)
+    type FakeFx = (String, bool, HashMap<i32, f64>);
+
+    /// In-memory single-track chain.
+    #[derive(Default)]
+    struct FakeBackend {
+        chain: Mutex<Vec<FakeFx>>,
+        fail_names: HashSet<String>,
+    }
+
+    impl FakeBackend {
+        fn with(names: &[&str]) -> Self {
+            Self {
+                chain: Mutex::new(
+                    names
+                        .iter()
+                        .map(|n| (n.to_string(), true, HashMap::new()))
+                        .collect(),
+                ),
+                fail_names: HashSet::new(),
+            }
+        }
+
+        fn names(&self) -> Vec<String> {
+            self.chain
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|f| f.0.clone())
+                .collect()
+        }
+
+        fn check(&self, fx: i32) -> Result<(), String> {
+            let chain = self.chain.lock().unwrap();
+            let entry = chain.get(fx as usize).ok_or("no such FX")?;
+            if self.fail_names.contains(&entry.0) {
+                return Err(format!("'{}' refused", entry.0));
+            }
+            Ok(())
+        }
+    }
+
+    impl UndoBackend for FakeBackend {
+        async fn set_param(
+            &self,
+            _track: i32,
+            fx: i32,
+            param: i32,
+            value: f64,
+        ) -> Result<(), String> {
+            self.check(fx)?;
+            self.chain.lock().unwrap()[fx as usize]
+                .2
+                .insert(param, value);
+            Ok(())
+        }
+
+        async fn set_fx_enabled(&self, _track: i32, fx: i32, enabled: bool) -> Result<(), String> {
+            self.check(fx)?;
+            self.chain.lock().unwrap()[fx as usize].1 = enabled;
+            Ok(())
+        }
+
+        async fn move_fx(&self, _track: i32, from: i32, to: i32) -> Result<(), String> {
+            self.check(from)?;
+            let mut chain = self.chain.lock().unwrap();
+            if to as usize >= chain.len() {
+                return Err("bad target".to_string());
+            }
+            let fx = chain.remove(from as usize);
+            chain.insert(to as usize, fx);
+            Ok(())
+        }
+
+        async fn add_plugin(&self, _track: i32, name: &str) -> Result<i32, String> {
+            let mut chain = self.chain.lock().unwrap();
+            chain.push((name.to_string(), true, HashMap::new()));
+            Ok(chain.len() as i32 - 1)
+        }
+
+        async fn remove_plugin(&self, _track: i32, fx: i32) -> Result<(), String> {
+            self.check(fx)?;
+            self.chain.lock().unwrap().remove(fx as usize);
+            Ok(())
+        }
+    }
+
+    fn plugin_change(fx_index: i32, name: &str, was_loaded: bool) -> PluginChange {
+        PluginChange {
+            track: 0,
+            fx_index,
+            plugin_name: name.to_string(),
+            was_loaded,
+            state: None,
+        }
+    }
+
+    #[test]
+    fn plan_orders_undo_and_redo() {
+        let mut action = UndoAction::new("Mixed");
+        action.add_plugin_change(plugin_change(2, "EQ", true));
+        action.add_fx_move(FxMoveChange {
+            track: 0,
+            from_fx_index: 2,
+            to_fx_index: 0,
+        });
+        action.add_fx_toggle(FxToggleChange {
+            track: 0,
+            fx_index: 1,
+            fx_name: "Amp".to_string(),
+            was_enabled: false,
+        });
+
+        let undo = UndoExecutor::<FakeBackend>::plan(&action, UndoDirection::Undo);
+        assert!(matches!(undo[0], UndoOp::SetEnabled { enabled: false, .. }));
+        assert_eq!(
+            undo[1],
+            UndoOp::MoveFx {
+                track: 0,
+                from: 0,
+                to: 2
+            }
+        );
+        assert!(matches!(undo[2], UndoOp::RemovePlugin { fx_index: 2, .. }));
+
+        let redo = UndoExecutor::<FakeBackend>::plan(&action, UndoDirection::Redo);
+        assert!(matches!(redo[0], UndoOp::AddPlugin { fx_index: 2, .. }));
+        assert_eq!(
+            redo[1],
+            UndoOp::MoveFx {
+                track: 0,
+                from: 2,
+                to: 0
+            }
+        );
+        assert!(matches!(redo[2], UndoOp::SetEnabled { enabled: true, .. }));
+    }
+
+    #[test]
+    fn undo_of_removal_places_plugin_back_in_its_slot() {
+        // Gate (slot 0) was removed from [Gate, Amp, Delay].
+        let backend = FakeBackend::with(&["Amp", "Delay"]);
+        let mut action = UndoAction::new("Remove gate");
+        action.add_plugin_change(plugin_change(0, "Gate", false));
+
+        let execution = block_on(UndoExecutor::new(&backend).undo(&action));
+        assert!(execution.is_complete());
+        assert_eq!(backend.names(), vec!["Gate", "Amp", "Delay"]);
+
+        let execution = block_on(UndoExecutor::new(&backend).redo(&action));
+        assert!(execution.is_complete());
+        assert_eq!(backend.names(), vec!["Amp", "Delay"]);
+    }
+
+    #[test]
+    fn failed_removal_shifts_later_indices() {
+        // Redo removes slot 0 then sets a param on what was slot 2 (now slot 1).
+        let mut backend = FakeBackend::with(&["Gate", "Amp", "Delay"]);
+        backend.fail_names.insert("Gate".to_string());
+
+        let mut action = UndoAction::new("Remove gate, tweak delay");
+        action.add_plugin_change(plugin_change(0, "Gate", false));
+        action.add_param_change(ParameterChange {
+            track: 0,
+            fx_index: 1,
+            fx_name: "Delay".to_string(),
+            param_index: 3,
+            param_name: "Feedback".to_string(),
+            old_value: 0.2,
+            new_value: 0.6,
+        });
+
+        let execution = block_on(UndoExecutor::new(&backend).redo(&action));
+        assert_eq!(execution.applied, 1);
+        assert_eq!(execution.failures.len(), 1);
+        assert!(matches!(
+            execution.failures[0].op,
+            UndoOp::RemovePlugin { .. }
+        ));
+
+        // The gate is still there, so the param write must land on slot 2 (Delay), not Amp.
+        let chain = backend.chain.lock().unwrap();
+        assert_eq!(chain[2].2.get(&3), Some(&0.6));
+        assert!(chain[1].2.is_empty());
+    }
+
+    #[test]
+    fn undo_of_removal_restores_parameters_and_bypass() {
+        let backend = FakeBackend::with(&["Amp", "Delay"]);
+        let mut action = UndoAction::new("Remove gate");
+        action.add_plugin_change(PluginChange {
+            state: Some(PluginState {
+                enabled: false,
+                params: vec![PluginParamState {
+                    param_index: 0,
+                    param_name: "Threshold".to_string(),
+                    value: 0.3,
+                }],
+            }),
+            ..plugin_change(1, "Gate", false)
+        });
+
+        let execution = block_on(UndoExecutor::new(&backend).undo(&action));
+        assert!(execution.is_complete());
+
+        let chain = backend.chain.lock().unwrap();
+        assert_eq!(chain[1].0, "Gate");
+        assert!(!chain[1].1);
+        assert_eq!(chain[1].2.get(&0), Some(&0.3));
+    }
+
+    #[test]
+    fn redo_of_load_reapplies_params_from_same_action() {
+        // Action: load EQ at slot 0 (REAPER appended it, then it was moved), set its gain.
+        let backend = FakeBackend::with(&["Amp"]);
+        let mut action = UndoAction::new("Load EQ");
+        action.add_plugin_change(plugin_change(1, "EQ", true));
+        action.add_fx_move(FxMoveChange {
+            track: 0,
+            from_fx_index: 1,
+            to_fx_index: 0,
+        });
+        action.add_param_change(ParameterChange {
+            track: 0,
+            fx_index: 0,
+            fx_name: "EQ".to_string(),
+            param_index: 2,
+            param_name: "Gain".to_string(),
+            old_value: 0.5,
+            new_value: 0.7,
+        });
+
+        let execution = block_on(UndoExecutor::new(&backend).redo(&action));
+        assert!(execution.is_complete());
+
+        let chain = backend.chain.lock().unwrap();
+        assert_eq!(chain[0].0, "EQ");
+        assert_eq!(chain[0].2.get(&2), Some(&0.7));
+        assert!(chain[1].2.is_empty());
+    }
+}
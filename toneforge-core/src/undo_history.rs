@@ -0,0 +1,371 @@
+//! Persistent Undo History
+//!
+//! Undo/redo stacks are stored per REAPER project (keyed by project path) so they survive
+//! restarts. Because the project can change while ToneForge is closed, restored entries are
+//! validated against the live FX chains before use: any entry whose recorded plugin
+//! names/indices no longer line up is marked stale instead of being replayed.
+
+use crate::undo_redo::UndoAction;
+use crate::undo_tree::UndoTree;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// 1: linear undo/redo stacks. 2: branching tree (stacks left empty).
+pub const UNDO_HISTORY_VERSION: u32 = 2;
+
+/// Live FX chain names per track index (as reported by REAPER).
+pub type LiveChains = HashMap<i32, Vec<String>>;
+
+/// On-disk undo history for one project. Version 1 files only have the linear stacks
+/// (bottom -> top); they are loaded as a single-branch tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoHistory {
+    pub version: u32,
+    pub project_path: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub undo: Vec<UndoAction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redo: Vec<UndoAction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree: Option<UndoTree>,
+}
+
+impl UndoHistory {
+    pub fn from_tree(project_path: &str, tree: &UndoTree) -> Self {
+        Self {
+            version: UNDO_HISTORY_VERSION,
+            project_path: project_path.to_string(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            tree: Some(tree.clone()),
+        }
+    }
+
+    pub fn into_tree(self) -> UndoTree {
+        match self.tree {
+            Some(tree) => tree,
+            None => UndoTree::from_stacks(self.undo, self.redo),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read undo history: {}", e))?;
+        let history: UndoHistory = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse undo history: {}", e))?;
+        if history.version > UNDO_HISTORY_VERSION {
+            return Err(format!(
+                "Undo history version {} is newer than supported ({})",
+                history.version, UNDO_HISTORY_VERSION
+            ));
+        }
+        Ok(history)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create undo history dir: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize undo history: {}", e))?;
+        fs::write(path, json).map_err(|e| format!("Failed to write undo history: {}", e))
+    }
+}
+
+/// Stable file name for a project's history (FNV-1a of the path; std hashers are not
+/// stable across Rust versions).
+pub fn history_file_name(project_path: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in project_path.to_lowercase().bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}.json", hash)
+}
+
+/// Validate an undo stack (bottom -> top) against the live chains.
+///
+/// Walks from the top down, reverting each action on a simulated chain. Returns a stale
+/// reason per entry (same order as `actions`); once an entry is stale, everything below
+/// it is stale too since it can only be reached by undoing the stale one first.
+pub fn validate_undo_stack(actions: &[UndoAction], live: &LiveChains) -> Vec<Option<String>> {
+    let mut chains = live.clone();
+    let mut reasons = vec![None; actions.len()];
+    let mut broken = false;
+
+    for (i, action) in actions.iter().enumerate().rev() {
+        if broken {
+            reasons[i] = Some("Depends on a newer stale entry".to_string());
+            continue;
+        }
+        if let Err(reason) =
+            check_settings(action, &chains).and_then(|_| revert(action, &mut chains))
+        {
+            reasons[i] = Some(reason);
+            broken = true;
+        }
+    }
+
+    reasons
+}
+
+/// Check that a planned jump (revert these, newest first; then apply these, in order) can
+/// be replayed against the live chains.
+pub fn validate_jump(
+    revert: &[UndoAction],
+    apply: &[UndoAction],
+    live: &LiveChains,
+) -> Result<(), String> {
+    let mut chains = live.clone();
+    for action in revert {
+        check_settings(action, &chains)
+            .and_then(|_| self::revert(action, &mut chains))
+            .map_err(|e| format!("'{}': {}", action.description, e))?;
+    }
+    for action in apply {
+        reapply(action, &mut chains)
+            .and_then(|_| check_settings(action, &chains))
+            .map_err(|e| format!("'{}': {}", action.description, e))?;
+    }
+    Ok(())
+}
+
+/// Validate a redo stack (bottom -> top) against the live chains.
+///
+/// Walks from the top down, re-applying each action on a simulated chain.
+pub fn validate_redo_stack(actions: &[UndoAction], live: &LiveChains) -> Vec<Option<String>> {
+    let mut chains = live.clone();
+    let mut reasons = vec![None; actions.len()];
+    let mut broken = false;
+
+    for (i, action) in actions.iter().enumerate().rev() {
+        if broken {
+            reasons[i] = Some("Depends on a newer stale entry".to_string());
+            continue;
+        }
+        if let Err(reason) =
+            reapply(action, &mut chains).and_then(|_| check_settings(action, &chains))
+        {
+            reasons[i] = Some(reason);
+            broken = true;
+        }
+    }
+
+    reasons
+}
+
+/// Parameter and toggle changes refer to the chain as it is after the action.
+fn check_settings(action: &UndoAction, chains: &LiveChains) -> Result<(), String> {
+    for change in &action.parameter_changes {
+        expect_fx(chains, change.track, change.fx_index, &change.fx_name)?;
+    }
+    for toggle in &action.fx_toggles {
+        expect_fx(chains, toggle.track, toggle.fx_index, &toggle.fx_name)?;
+    }
+    Ok(())
+}
+
+fn revert(action: &UndoAction, chains: &mut LiveChains) -> Result<(), String> {
+    for mv in action.fx_moves.iter().rev() {
+        move_fx(chains, mv.track, mv.to_fx_index, mv.from_fx_index)?;
+    }
+    for change in action.plugin_changes.iter().rev() {
+        if change.was_loaded {
+            remove_fx(chains, change.track, change.fx_index, &change.plugin_name)?;
+        } else {
+            insert_fx(chains, change.track, change.fx_index, &change.plugin_name)?;
+        }
+    }
+    Ok(())
+}
+
+fn reapply(action: &UndoAction, chains: &mut LiveChains) -> Result<(), String> {
+    for change in &action.plugin_changes {
+        if change.was_loaded {
+            insert_fx(chains, change.track, change.fx_index, &change.plugin_name)?;
+        } else {
+            remove_fx(chains, change.track, change.fx_index, &change.plugin_name)?;
+        }
+    }
+    for mv in &action.fx_moves {
+        move_fx(chains, mv.track, mv.from_fx_index, mv.to_fx_index)?;
+    }
+    Ok(())
+}
+
+fn chain(chains: &LiveChains, track: i32) -> Result<&Vec<String>, String> {
+    chains
+        .get(&track)
+        .ok_or_else(|| format!("Track {} no longer exists", track))
+}
+
+fn chain_mut(chains: &mut LiveChains, track: i32) -> Result<&mut Vec<String>, String> {
+    chains
+        .get_mut(&track)
+        .ok_or_else(|| format!("Track {} no longer exists", track))
+}
+
+fn expect_fx(chains: &LiveChains, track: i32, fx_index: i32, name: &str) -> Result<(), String> {
+    let chain = chain(chains, track)?;
+    match chain.get(fx_index as usize) {
+        Some(live) if names_match(name, live) => Ok(()),
+        Some(live) => Err(format!(
+            "Track {} FX {} is '{}' (expected '{}')",
+            track, fx_index, live, name
+        )),
+        None => Err(format!(
+            "Track {} has no FX {} (expected '{}')",
+            track, fx_index, name
+        )),
+    }
+}
+
+fn remove_fx(chains: &mut LiveChains, track: i32, fx_index: i32, name: &str) -> Result<(), String> {
+    expect_fx(chains, track, fx_index, name)?;
+    chain_mut(chains, track)?.remove(fx_index as usize);
+    Ok(())
+}
+
+fn insert_fx(chains: &mut LiveChains, track: i32, fx_index: i32, name: &str) -> Result<(), String> {
+    let chain = chain_mut(chains, track)?;
+    if fx_index < 0 || fx_index as usize > chain.len() {
+        return Err(format!(
+            "Cannot restore '{}' at FX {} on track {} ({} FX present)",
+            name,
+            fx_index,
+            track,
+            chain.len()
+        ));
+    }
+    chain.insert(fx_index as usize, name.to_string());
+    Ok(())
+}
+
+fn move_fx(chains: &mut LiveChains, track: i32, from: i32, to: i32) -> Result<(), String> {
+    let chain = chain_mut(chains, track)?;
+    let len = chain.len() as i32;
+    if from < 0 || from >= len || to < 0 || to >= len {
+        return Err(format!(
+            "Cannot move FX {} -> {} on track {} ({} FX present)",
+            from, to, track, len
+        ));
+    }
+    let fx = chain.remove(from as usize);
+    chain.insert(to as usize, fx);
+    Ok(())
+}
+
+/// Recorded names are often the requested name ("ReaEQ (Cockos)") while REAPER reports the
+/// full one ("VST: ReaEQ (Cockos)"), so compare normalized and allow containment.
+fn names_match(recorded: &str, live: &str) -> bool {
+    let recorded = normalize(recorded);
+    if recorded.is_empty() {
+        return true;
+    }
+    let live = normalize(live);
+    live.contains(&recorded) || recorded.contains(&live)
+}
+
+fn normalize(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::undo_redo::{FxMoveChange, ParameterChange, PluginChange};
+
+    fn live(names: &[&str]) -> LiveChains {
+        let mut chains = LiveChains::new();
+        chains.insert(0, names.iter().map(|n| n.to_string()).collect());
+        chains
+    }
+
+    fn param_action(fx_index: i32, fx_name: &str) -> UndoAction {
+        let mut action = UndoAction::new("Set param");
+        action.add_param_change(ParameterChange {
+            track: 0,
+            fx_index,
+            fx_name: fx_name.to_string(),
+            param_index: 0,
+            param_name: "Gain".to_string(),
+            old_value: 0.2,
+            new_value: 0.8,
+        });
+        action
+    }
+
+    #[test]
+    fn undo_stack_is_simulated_through_loads_and_moves() {
+        // Oldest: set amp gain while the chain was [Amp].
+        let older = param_action(0, "VST3: Amp");
+        // Newest: load ReaEQ (slot 1) and move it to the front.
+        let mut newer = UndoAction::new("Load EQ");
+        newer.add_plugin_change(PluginChange {
+            track: 0,
+            fx_index: 1,
+            plugin_name: "ReaEQ (Cockos)".to_string(),
+            was_loaded: true,
+            state: None,
+        });
+        newer.add_fx_move(FxMoveChange {
+            track: 0,
+            from_fx_index: 1,
+            to_fx_index: 0,
+        });
+
+        let stack = vec![older, newer];
+        let reasons = validate_undo_stack(&stack, &live(&["VST: ReaEQ (Cockos)", "VST3: Amp"]));
+        assert_eq!(reasons, vec![None, None]);
+
+        // Someone swapped the amp for a different plugin while ToneForge was closed.
+        let reasons = validate_undo_stack(&stack, &live(&["VST: ReaEQ (Cockos)", "VST3: Other"]));
+        assert!(reasons[0].is_some());
+        assert!(reasons[1].is_none());
+    }
+
+    #[test]
+    fn stale_top_marks_everything_below() {
+        let stack = vec![param_action(0, "VST3: Amp"), param_action(1, "ReaDelay")];
+        let reasons = validate_undo_stack(&stack, &live(&["VST3: Amp"]));
+        assert!(reasons[1].as_deref().unwrap().contains("no FX 1"));
+        assert_eq!(
+            reasons[0].as_deref(),
+            Some("Depends on a newer stale entry")
+        );
+    }
+
+    #[test]
+    fn history_roundtrip_and_stable_file_name() {
+        let dir = std::env::temp_dir().join(format!("toneforge-undo-{}", uuid::Uuid::new_v4()));
+        let file = dir.join(history_file_name("C:\\Projects\\Song.RPP"));
+        assert_eq!(
+            history_file_name("C:\\Projects\\Song.RPP"),
+            history_file_name("c:\\projects\\song.rpp")
+        );
+
+        // Version 1 files (linear stacks) still load.
+        let history = UndoHistory {
+            version: 1,
+            project_path: "C:\\Projects\\Song.RPP".to_string(),
+            undo: vec![param_action(0, "VST3: Amp")],
+            redo: Vec::new(),
+            tree: None,
+        };
+        history.save(&file).unwrap();
+        let tree = UndoHistory::load(&file).unwrap().into_tree();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(
+            tree.current_action().unwrap().parameter_changes[0].fx_name,
+            "VST3: Amp"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
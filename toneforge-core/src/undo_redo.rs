@@ -2,10 +2,18 @@
 //!
 //! Transaction-based undo/redo history for plugin parameter changes.
 
+use crate::undo_history::{
+    history_file_name, validate_redo_stack, validate_undo_stack, LiveChains, UndoHistory,
+};
+use crate::undo_tree::{UndoCheckpoint, UndoJump, UndoTree};
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 
-const MAX_UNDO_HISTORY: usize = 50;
+/// Total nodes kept across all branches.
+const MAX_UNDO_NODES: usize = 200;
+/// Parameter-only actions committed this soon after the previous one on the same
+/// parameters are merged into it (slider drags, repeated nudges).
+const DEFAULT_MERGE_WINDOW_MS: u64 = 750;
 
 /// Represents a single parameter change
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +44,23 @@ pub struct PluginChange {
     pub fx_index: i32,
     pub plugin_name: String,
     pub was_loaded: bool, // true = was loaded (undo = remove), false = was removed (undo = add)
+    /// State captured before a removal, restored when the plugin is re-added.
+    #[serde(default)]
+    pub state: Option<PluginState>,
+}
+
+/// Full state of a plugin instance (enable state + every parameter)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PluginState {
+    pub enabled: bool,
+    pub params: Vec<PluginParamState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PluginParamState {
+    pub param_index: i32,
+    pub param_name: String,
+    pub value: f64,
 }
 
 /// Represents a single FX move (reorder) change
@@ -56,24 +81,39 @@ pub struct UndoAction {
     pub fx_toggles: Vec<FxToggleChange>,
     pub plugin_changes: Vec<PluginChange>,
     pub fx_moves: Vec<FxMoveChange>,
+    /// Set when the live project no longer matches what this action recorded.
+    #[serde(default)]
+    pub stale_reason: Option<String>,
+    /// Last time (ms since epoch) changes were merged into this action.
+    #[serde(default)]
+    pub last_edit_ms: u64,
+    /// Writes undo cannot replay (containers, send tracks, ...). An action with any of
+    /// these is never committed, so it is not persisted either.
+    #[serde(skip)]
+    pub untracked_changes: Vec<String>,
 }
 
 impl UndoAction {
     pub fn new(description: &str) -> Self {
+        let now_ms = now_ms();
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             description: description.to_string(),
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map(|d| d.as_secs())
-                .unwrap_or(0),
+            timestamp: now_ms / 1000,
             parameter_changes: Vec::new(),
             fx_toggles: Vec::new(),
             plugin_changes: Vec::new(),
             fx_moves: Vec::new(),
+            stale_reason: None,
+            last_edit_ms: now_ms,
+            untracked_changes: Vec::new(),
         }
     }
 
+    pub fn is_stale(&self) -> bool {
+        self.stale_reason.is_some()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.parameter_changes.is_empty()
             && self.fx_toggles.is_empty()
@@ -85,6 +125,38 @@ impl UndoAction {
         self.parameter_changes.push(change);
     }
 
+    /// True if the action only contains parameter changes.
+    pub fn is_param_only(&self) -> bool {
+        !self.parameter_changes.is_empty()
+            && self.fx_toggles.is_empty()
+            && self.plugin_changes.is_empty()
+            && self.fx_moves.is_empty()
+    }
+
+    fn find_param_change(&self, track: i32, fx_index: i32, param_index: i32) -> Option<usize> {
+        self.parameter_changes.iter().position(|c| {
+            c.track == track && c.fx_index == fx_index && c.param_index == param_index
+        })
+    }
+
+    /// Fold a later param-only action into this one: values it set win, original
+    /// `old_value`s are kept. Fails (leaving `self` untouched) if it touches a parameter
+    /// this action does not.
+    fn absorb_params(&mut self, later: &UndoAction) -> bool {
+        let targets: Option<Vec<usize>> = later
+            .parameter_changes
+            .iter()
+            .map(|c| self.find_param_change(c.track, c.fx_index, c.param_index))
+            .collect();
+        let Some(targets) = targets else {
+            return false;
+        };
+        for (target, change) in targets.into_iter().zip(&later.parameter_changes) {
+            self.parameter_changes[target].new_value = change.new_value;
+        }
+        true
+    }
+
     pub fn add_fx_toggle(&mut self, toggle: FxToggleChange) {
         self.fx_toggles.push(toggle);
     }
@@ -106,29 +178,260 @@ impl UndoAction {
     }
 }
 
+/// Change counts of the open action when a group level was opened, so cancelling an inner
+/// level only drops what was recorded inside it.
+#[derive(Debug, Clone, Copy)]
+struct GroupMark {
+    params: usize,
+    toggles: usize,
+    plugins: usize,
+    moves: usize,
+    untracked: usize,
+}
+
+impl GroupMark {
+    fn of(action: &UndoAction) -> Self {
+        Self {
+            params: action.parameter_changes.len(),
+            toggles: action.fx_toggles.len(),
+            plugins: action.plugin_changes.len(),
+            moves: action.fx_moves.len(),
+            untracked: action.untracked_changes.len(),
+        }
+    }
+
+    fn truncate(&self, action: &mut UndoAction) {
+        action.parameter_changes.truncate(self.params);
+        action.fx_toggles.truncate(self.toggles);
+        action.plugin_changes.truncate(self.plugins);
+        action.fx_moves.truncate(self.moves);
+        action.untracked_changes.truncate(self.untracked);
+    }
+}
+
 /// The main undo/redo manager
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct UndoManager {
-    undo_stack: VecDeque<UndoAction>,
-    redo_stack: VecDeque<UndoAction>,
+    tree: UndoTree,
     current_action: Option<UndoAction>,
+    /// Open `begin_group` levels (and actions begun inside them), outermost first.
+    groups: Vec<GroupMark>,
+    merge_window_ms: u64,
+    /// Project the history belongs to, and where it is persisted.
+    project_path: Option<String>,
+    history_file: Option<PathBuf>,
+    /// Why the last history save failed; cleared by the next successful save.
+    persist_error: Option<String>,
 }
 
 impl UndoManager {
     pub fn new() -> Self {
         Self {
-            undo_stack: VecDeque::with_capacity(MAX_UNDO_HISTORY),
-            redo_stack: VecDeque::with_capacity(MAX_UNDO_HISTORY),
+            tree: UndoTree::new(),
             current_action: None,
+            groups: Vec::new(),
+            merge_window_ms: DEFAULT_MERGE_WINDOW_MS,
+            project_path: None,
+            history_file: None,
+            persist_error: None,
+        }
+    }
+
+    /// Switch to a project's persistent history: the tree is replaced with the stored
+    /// history for `project_path` (empty if none) and saved to `history_dir` on every change.
+    pub fn attach_project(&mut self, project_path: &str, history_dir: &Path) -> Result<(), String> {
+        let file = history_dir.join(history_file_name(project_path));
+
+        self.tree = UndoTree::new();
+        self.current_action = None;
+        self.groups.clear();
+        self.project_path = Some(project_path.to_string());
+        self.history_file = Some(file.clone());
+
+        if file.exists() {
+            self.tree = UndoHistory::load(&file)?.into_tree();
+            self.tree.prune(MAX_UNDO_NODES);
+        }
+        Ok(())
+    }
+
+    /// Stop persisting (e.g. the project is unsaved and has no path yet).
+    pub fn detach_project(&mut self) {
+        self.project_path = None;
+        self.history_file = None;
+        self.persist_error = None;
+    }
+
+    pub fn project_path(&self) -> Option<&str> {
+        self.project_path.as_deref()
+    }
+
+    pub fn tree(&self) -> &UndoTree {
+        &self.tree
+    }
+
+    /// How long after a parameter-only action a follow-up edit of the same parameters is
+    /// merged into it. 0 disables merging.
+    pub fn set_merge_window(&mut self, window_ms: u64) {
+        self.merge_window_ms = window_ms;
+    }
+
+    /// Mark entries on the current undo path and redo chain that no longer match the live
+    /// FX chains as stale. Returns the number of stale entries.
+    pub fn validate_against(&mut self, live: &LiveChains) -> usize {
+        let undo: Vec<UndoAction> = self.tree.undo_path().into_iter().cloned().collect();
+        let mut redo: Vec<UndoAction> = self.tree.redo_chain().into_iter().cloned().collect();
+        redo.reverse();
+
+        let reasons = validate_undo_stack(&undo, live)
+            .into_iter()
+            .zip(&undo)
+            .chain(validate_redo_stack(&redo, live).into_iter().zip(&redo));
+        for (reason, action) in reasons {
+            if let Some(node) = self.tree.action_mut(&action.id) {
+                node.stale_reason = reason;
+            }
+        }
+
+        self.persist_or_defer();
+        self.stale_count()
+    }
+
+    pub fn stale_count(&self) -> usize {
+        self.tree
+            .undo_path()
+            .into_iter()
+            .chain(self.tree.redo_chain())
+            .filter(|a| a.is_stale())
+            .count()
+    }
+
+    /// Drop stale entries (and everything that depends on them). Returns how many were removed.
+    pub fn discard_stale(&mut self) -> usize {
+        let removed = self.tree.discard_stale();
+        if removed > 0 {
+            self.persist_or_defer();
         }
+        removed
     }
 
-    /// Start a new action group (for batching multiple changes)
+    pub fn peek_undo(&self) -> Option<&UndoAction> {
+        self.tree.current_action()
+    }
+
+    pub fn peek_redo(&self) -> Option<&UndoAction> {
+        self.tree.next_action()
+    }
+
+    /// Name the current position in the history.
+    pub fn set_checkpoint(&mut self, name: &str) -> Result<(), String> {
+        self.tree.set_checkpoint(name)?;
+        self.persist()
+    }
+
+    pub fn remove_checkpoint(&mut self, name: &str) -> bool {
+        let removed = self.tree.remove_checkpoint(name);
+        if removed {
+            self.persist_or_defer();
+        }
+        removed
+    }
+
+    /// Plan a jump to a node (`None` = the state before any recorded action).
+    pub fn plan_jump(&self, target: Option<&str>) -> Result<UndoJump, String> {
+        self.tree.plan_jump(target)
+    }
+
+    /// Plan a jump to a named checkpoint.
+    pub fn plan_checkpoint_jump(&self, name: &str) -> Result<UndoJump, String> {
+        let checkpoint = self
+            .tree
+            .checkpoint(name)
+            .ok_or_else(|| format!("No checkpoint named '{}'", name))?;
+        self.tree.plan_jump(checkpoint.node_id.as_deref())
+    }
+
+    /// Actions of a planned jump, resolved (revert list, apply list).
+    pub fn jump_actions(&self, jump: &UndoJump) -> (Vec<UndoAction>, Vec<UndoAction>) {
+        let resolve = |ids: &[String]| {
+            ids.iter()
+                .filter_map(|id| self.tree.node(id))
+                .map(|n| n.action.clone())
+                .collect()
+        };
+        (resolve(&jump.revert), resolve(&jump.apply))
+    }
+
+    /// Move the cursor one step along a jump after its action was executed.
+    pub fn step_back(&mut self) -> Option<UndoAction> {
+        let action = self.tree.step_back();
+        self.persist_or_defer();
+        action
+    }
+
+    pub fn step_into(&mut self, id: &str) -> Result<UndoAction, String> {
+        let action = self.tree.step_into(id)?;
+        self.persist()?;
+        Ok(action)
+    }
+
+    /// Save the history of the attached project (no-op when detached). The outcome is
+    /// also kept for `persist_error`, so failures of methods without a `Result` are visible.
+    fn persist(&mut self) -> Result<(), String> {
+        let (Some(project_path), Some(file)) = (&self.project_path, &self.history_file) else {
+            return Ok(());
+        };
+        let result = UndoHistory::from_tree(project_path, &self.tree).save(file);
+        self.persist_error = result.as_ref().err().cloned();
+        result
+    }
+
+    /// `persist` for methods that cannot return an error; see `persist_error`.
+    fn persist_or_defer(&mut self) {
+        let _ = self.persist();
+    }
+
+    /// Why the history could not be saved, until a later save succeeds.
+    pub fn persist_error(&self) -> Option<&str> {
+        self.persist_error.as_deref()
+    }
+
+    /// Start a new action (for batching multiple changes). Inside an open group this
+    /// opens a nested level of the group instead.
     pub fn begin_action(&mut self, description: &str) {
-        self.current_action = Some(UndoAction::new(description));
+        if self.groups.is_empty() {
+            self.current_action = Some(UndoAction::new(description));
+        } else {
+            self.begin_group(description);
+        }
+    }
+
+    /// Open a group: everything recorded until the matching `end_group` (including nested
+    /// groups and `begin_action`/`commit_action` pairs) becomes one undo entry, described
+    /// by the outermost group.
+    pub fn begin_group(&mut self, description: &str) {
+        let action = self
+            .current_action
+            .get_or_insert_with(|| UndoAction::new(description));
+        self.groups.push(GroupMark::of(action));
+    }
+
+    /// Close the innermost group. Closing the outermost one commits the whole group.
+    pub fn end_group(&mut self) -> Option<String> {
+        self.groups.pop()?;
+        if self.groups.is_empty() {
+            self.commit_at(now_ms())
+        } else {
+            None
+        }
+    }
+
+    pub fn group_depth(&self) -> usize {
+        self.groups.len()
     }
 
     /// Record a parameter change in the current action
+    #[allow(clippy::too_many_arguments)]
     pub fn record_param_change(
         &mut self,
         track: i32,
@@ -139,7 +442,16 @@ impl UndoManager {
         old_value: f64,
         new_value: f64,
     ) {
+        let inner_start = self.groups.last().map_or(0, |mark| mark.params);
         if let Some(ref mut action) = self.current_action {
+            // Editing the same parameter again in this level keeps the first old value.
+            if let Some(existing) = action
+                .find_param_change(track, fx_index, param_index)
+                .filter(|&i| i >= inner_start)
+            {
+                action.parameter_changes[existing].new_value = new_value;
+                return;
+            }
             action.add_param_change(ParameterChange {
                 track,
                 fx_index,
@@ -178,6 +490,26 @@ impl UndoManager {
                 fx_index,
                 plugin_name: plugin_name.to_string(),
                 was_loaded,
+                state: None,
+            });
+        }
+    }
+
+    /// Record a plugin removal with its state so undo can restore it in place
+    pub fn record_plugin_removal(
+        &mut self,
+        track: i32,
+        fx_index: i32,
+        plugin_name: &str,
+        state: PluginState,
+    ) {
+        if let Some(ref mut action) = self.current_action {
+            action.add_plugin_change(PluginChange {
+                track,
+                fx_index,
+                plugin_name: plugin_name.to_string(),
+                was_loaded: false,
+                state: Some(state),
             });
         }
     }
@@ -192,76 +524,157 @@ impl UndoManager {
         }
     }
 
-    /// Commit the current action to the undo stack
+    /// Note a write that undo cannot replay. The open action will not be committed: its
+    /// recorded FX indices may no longer match the chain after such a write.
+    pub fn record_untracked(&mut self, description: &str) {
+        if let Some(ref mut action) = self.current_action {
+            action.untracked_changes.push(description.to_string());
+        }
+    }
+
+    /// Untracked writes in the open action; non-empty means `commit_action` will drop it.
+    pub fn untracked_changes(&self) -> &[String] {
+        self.current_action
+            .as_ref()
+            .map_or(&[], |action| action.untracked_changes.as_slice())
+    }
+
+    /// Commit the current action as a new child of the current history node. Inside a
+    /// group this only closes the innermost level.
     pub fn commit_action(&mut self) -> Option<String> {
-        if let Some(action) = self.current_action.take() {
-            if !action.is_empty() {
-                let id = action.id.clone();
-
-                self.redo_stack.clear();
-                self.undo_stack.push_back(action);
-                while self.undo_stack.len() > MAX_UNDO_HISTORY {
-                    self.undo_stack.pop_front();
-                }
+        if !self.groups.is_empty() {
+            return self.end_group();
+        }
+        self.commit_at(now_ms())
+    }
 
-                return Some(id);
-            }
+    fn commit_at(&mut self, now_ms: u64) -> Option<String> {
+        let action = self.current_action.take()?;
+        if action.is_empty() || !action.untracked_changes.is_empty() {
+            return None;
         }
-        None
+
+        if let Some(id) = self.merge_into_current(&action, now_ms) {
+            self.persist_or_defer();
+            return Some(id);
+        }
+
+        let id = self.tree.insert(action);
+        self.tree.prune(MAX_UNDO_NODES);
+        self.persist_or_defer();
+        Some(id)
     }
 
-    /// Cancel the current action without committing
+    /// Merge a parameter-only action into the current node if that node is a recent
+    /// parameter-only edit of the same parameters and nothing was built on top of it.
+    fn merge_into_current(&mut self, action: &UndoAction, now_ms: u64) -> Option<String> {
+        if self.merge_window_ms == 0 || !action.is_param_only() {
+            return None;
+        }
+        let id = self.tree.current_id()?.to_string();
+        if self.tree.children(Some(&id)).next().is_some() {
+            return None;
+        }
+
+        let window = self.merge_window_ms;
+        let top = self.tree.action_mut(&id)?;
+        if !top.is_param_only()
+            || top.is_stale()
+            || now_ms.saturating_sub(top.last_edit_ms) > window
+            || !top.absorb_params(action)
+        {
+            return None;
+        }
+        top.last_edit_ms = now_ms;
+        Some(id)
+    }
+
+    /// Cancel the current action without committing. Inside a group this drops only what
+    /// was recorded since the innermost level was opened.
     pub fn cancel_action(&mut self) {
-        self.current_action = None;
+        match self.groups.pop() {
+            Some(mark) => {
+                if let Some(action) = self.current_action.as_mut() {
+                    mark.truncate(action);
+                }
+                if self.groups.is_empty() {
+                    self.current_action = None;
+                }
+            }
+            None => self.current_action = None,
+        }
     }
 
+    /// Step back to the parent node, returning the action to revert.
     pub fn pop_undo(&mut self) -> Option<UndoAction> {
-        self.undo_stack.pop_back()
+        self.step_back()
     }
 
+    /// Make `action` the next redo. Actions already in the tree (just undone) are kept in
+    /// place; anything else is attached as a new branch.
     pub fn push_redo(&mut self, action: UndoAction) {
-        self.redo_stack.push_back(action);
-        while self.redo_stack.len() > MAX_UNDO_HISTORY {
-            self.redo_stack.pop_front();
+        if !self.tree.contains(&action.id) {
+            self.tree.attach_child(action);
+            self.tree.prune(MAX_UNDO_NODES);
         }
+        self.persist_or_defer();
     }
 
+    /// Step forward along the active branch, returning the action to apply.
     pub fn pop_redo(&mut self) -> Option<UndoAction> {
-        self.redo_stack.pop_back()
+        let action = self.tree.step_forward();
+        self.persist_or_defer();
+        action
     }
 
+    /// Make `action` the current node. Actions already in the tree (just redone) are kept in
+    /// place; anything else is inserted as a new branch.
     pub fn push_undo(&mut self, action: UndoAction) {
-        self.undo_stack.push_back(action);
-        while self.undo_stack.len() > MAX_UNDO_HISTORY {
-            self.undo_stack.pop_front();
+        if !self.tree.contains(&action.id) {
+            self.tree.insert(action);
+            self.tree.prune(MAX_UNDO_NODES);
         }
+        self.persist_or_defer();
     }
 
     pub fn can_undo(&self) -> bool {
-        !self.undo_stack.is_empty()
+        self.tree.current_id().is_some()
     }
 
     pub fn can_redo(&self) -> bool {
-        !self.redo_stack.is_empty()
+        self.tree.next_id().is_some()
     }
 
     pub fn undo_description(&self) -> Option<&str> {
-        self.undo_stack.back().map(|a| a.description.as_str())
+        self.peek_undo().map(|a| a.description.as_str())
     }
 
     pub fn redo_description(&self) -> Option<&str> {
-        self.redo_stack.back().map(|a| a.description.as_str())
+        self.peek_redo().map(|a| a.description.as_str())
     }
 
     pub fn undo_count(&self) -> usize {
-        self.undo_stack.len()
+        self.tree.undo_path().len()
     }
 
     pub fn redo_count(&self) -> usize {
-        self.redo_stack.len()
+        self.tree.redo_chain().len()
+    }
+}
+
+impl Default for UndoManager {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 /// Summary of an undo action (for UI display)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UndoActionSummary {
@@ -280,10 +693,37 @@ pub struct UndoState {
     pub redo_description: Option<String>,
     pub undo_count: usize,
     pub redo_count: usize,
+    pub project_path: Option<String>,
+    pub stale_count: usize,
+    /// Reason the next undo/redo entry is stale, if it is.
+    pub undo_stale_reason: Option<String>,
+    pub redo_stale_reason: Option<String>,
+    /// Current history node (`None` = before any recorded action).
+    pub current_id: Option<String>,
+    /// Every node of the history tree, oldest first.
+    pub nodes: Vec<UndoNodeSummary>,
+    pub checkpoints: Vec<UndoCheckpoint>,
+    /// Why the history could not be saved to disk, while that is the case.
+    pub persist_error: Option<String>,
+}
+
+/// One node of the history tree (for UI display)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoNodeSummary {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub description: String,
+    pub change_count: usize,
+    pub timestamp: u64,
+    /// On the path from the root to the current node.
+    pub applied: bool,
+    pub stale_reason: Option<String>,
 }
 
 impl From<&UndoManager> for UndoState {
     fn from(manager: &UndoManager) -> Self {
+        let tree = manager.tree();
+        let applied = tree.ancestors(tree.current_id());
         Self {
             can_undo: manager.can_undo(),
             can_redo: manager.can_redo(),
@@ -291,6 +731,26 @@ impl From<&UndoManager> for UndoState {
             redo_description: manager.redo_description().map(String::from),
             undo_count: manager.undo_count(),
             redo_count: manager.redo_count(),
+            project_path: manager.project_path().map(String::from),
+            stale_count: manager.stale_count(),
+            undo_stale_reason: manager.peek_undo().and_then(|a| a.stale_reason.clone()),
+            redo_stale_reason: manager.peek_redo().and_then(|a| a.stale_reason.clone()),
+            current_id: tree.current_id().map(String::from),
+            nodes: tree
+                .nodes()
+                .iter()
+                .map(|node| UndoNodeSummary {
+                    id: node.action.id.clone(),
+                    parent_id: node.parent.clone(),
+                    description: node.action.description.clone(),
+                    change_count: node.action.change_count(),
+                    timestamp: node.action.timestamp,
+                    applied: applied.contains(&node.action.id),
+                    stale_reason: node.action.stale_reason.clone(),
+                })
+                .collect(),
+            checkpoints: tree.checkpoints().to_vec(),
+            persist_error: manager.persist_error().map(String::from),
         }
     }
 }
@@ -313,5 +773,172 @@ mod tests {
         assert_eq!(action.plugin_changes.len(), 1);
         assert_eq!(action.change_count(), 2);
     }
-}
 
+    #[test]
+    fn history_persists_per_project() {
+        let dir = std::env::temp_dir().join(format!("toneforge-undo-{}", uuid::Uuid::new_v4()));
+
+        let mut manager = UndoManager::new();
+        manager.attach_project("/projects/a.rpp", &dir).unwrap();
+        manager.begin_action("Toggle amp");
+        manager.record_fx_toggle(0, 0, "VST3: Amp", true);
+        manager.commit_action();
+
+        // Another project starts empty...
+        manager.attach_project("/projects/b.rpp", &dir).unwrap();
+        assert_eq!(manager.undo_count(), 0);
+
+        // ...and a fresh manager restores the first one.
+        let mut restored = UndoManager::new();
+        restored.attach_project("/projects/a.rpp", &dir).unwrap();
+        assert_eq!(restored.undo_description(), Some("Toggle amp"));
+
+        let mut live = LiveChains::new();
+        live.insert(0, vec!["VST3: Other".to_string()]);
+        assert_eq!(restored.validate_against(&live), 1);
+        assert!(UndoState::from(&restored).undo_stale_reason.is_some());
+        assert_eq!(restored.discard_stale(), 1);
+        assert!(!restored.can_undo());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn failed_history_save_is_reported() {
+        // A plain file where the history directory should be makes every save fail.
+        let file = std::env::temp_dir().join(format!("toneforge-undo-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&file, "").unwrap();
+
+        let mut manager = UndoManager::new();
+        manager.attach_project("/projects/a.rpp", &file).unwrap();
+        manager.begin_action("Toggle amp");
+        manager.record_fx_toggle(0, 0, "VST3: Amp", true);
+        manager.commit_action();
+
+        assert!(manager.persist_error().is_some());
+        assert!(UndoState::from(&manager).persist_error.is_some());
+        assert!(manager.set_checkpoint("pre-mix").is_err());
+
+        // A save that goes through again clears it.
+        std::fs::remove_file(&file).unwrap();
+        manager.set_checkpoint("post-mix").unwrap();
+        assert!(manager.persist_error().is_none());
+
+        let _ = std::fs::remove_dir_all(&file);
+    }
+
+    #[test]
+    fn new_action_after_undo_branches_instead_of_clearing_redo() {
+        let mut manager = UndoManager::new();
+        manager.begin_action("Tone A");
+        manager.record_fx_toggle(0, 0, "Amp", true);
+        manager.commit_action();
+        let tone_a = manager.pop_undo().unwrap();
+        manager.push_redo(tone_a.clone());
+        manager.set_checkpoint("pre-mix").unwrap();
+
+        manager.begin_action("Tone B");
+        manager.record_fx_toggle(0, 1, "Drive", true);
+        manager.commit_action();
+        assert!(!manager.can_redo());
+
+        let jump = manager.plan_jump(Some(&tone_a.id)).unwrap();
+        let (revert, apply) = manager.jump_actions(&jump);
+        assert_eq!(revert[0].description, "Tone B");
+        assert_eq!(apply[0].description, "Tone A");
+
+        let state = UndoState::from(&manager);
+        assert_eq!(state.nodes.len(), 2);
+        assert_eq!(state.checkpoints[0].name, "pre-mix");
+        assert_eq!(state.checkpoints[0].node_id, None);
+        let back = manager.plan_checkpoint_jump("pre-mix").unwrap();
+        assert!(back.apply.is_empty());
+    }
+
+    fn slider_edit(manager: &mut UndoManager, old_value: f64, new_value: f64, now_ms: u64) {
+        manager.begin_action("Set Gain");
+        manager.record_param_change(0, 0, "Amp", 2, "Gain", old_value, new_value);
+        manager.commit_at(now_ms);
+    }
+
+    #[test]
+    fn edits_within_merge_window_collapse() {
+        let mut manager = UndoManager::new();
+        manager.set_merge_window(500);
+        let start = now_ms();
+
+        slider_edit(&mut manager, 0.2, 0.3, start);
+        slider_edit(&mut manager, 0.3, 0.4, start + 200);
+        slider_edit(&mut manager, 0.4, 0.5, start + 600);
+        assert_eq!(manager.undo_count(), 1);
+        let change = &manager.peek_undo().unwrap().parameter_changes[0];
+        assert_eq!((change.old_value, change.new_value), (0.2, 0.5));
+
+        // Outside the window a new entry starts.
+        slider_edit(&mut manager, 0.5, 0.6, start + 1200);
+        assert_eq!(manager.undo_count(), 2);
+
+        // A different parameter never merges.
+        manager.begin_action("Set Bass");
+        manager.record_param_change(0, 0, "Amp", 3, "Bass", 0.5, 0.7);
+        manager.commit_at(start + 1300);
+        assert_eq!(manager.undo_count(), 3);
+    }
+
+    #[test]
+    fn repeated_param_in_one_action_keeps_first_old_value() {
+        let mut manager = UndoManager::new();
+        manager.begin_action("Nudge");
+        manager.record_param_change(0, 0, "Amp", 2, "Gain", 0.2, 0.3);
+        manager.record_param_change(0, 0, "Amp", 2, "Gain", 0.3, 0.35);
+        manager.commit_action();
+
+        let action = manager.peek_undo().unwrap();
+        assert_eq!(action.parameter_changes.len(), 1);
+        assert_eq!(action.parameter_changes[0].old_value, 0.2);
+        assert_eq!(action.parameter_changes[0].new_value, 0.35);
+    }
+
+    #[test]
+    fn nested_groups_commit_once() {
+        let mut manager = UndoManager::new();
+        manager.begin_group("Apply tone");
+        manager.record_fx_toggle(0, 0, "Amp", false);
+
+        manager.begin_action("Inner step");
+        manager.record_fx_move(0, 2, 1);
+        assert_eq!(manager.commit_action(), None);
+
+        manager.begin_group("Cancelled step");
+        manager.record_plugin_change(0, 3, "ReaEQ", true);
+        manager.cancel_action();
+        assert_eq!(manager.group_depth(), 1);
+
+        let id = manager.end_group();
+        assert!(id.is_some());
+        assert_eq!(manager.undo_count(), 1);
+        let action = manager.peek_undo().unwrap();
+        assert_eq!(action.description, "Apply tone");
+        assert_eq!(action.change_count(), 2);
+        assert!(action.plugin_changes.is_empty());
+        assert_eq!(manager.end_group(), None);
+    }
+
+    #[test]
+    fn action_with_untracked_writes_is_not_committed() {
+        let mut manager = UndoManager::new();
+        manager.begin_group("Apply tone");
+        manager.record_param_change(0, 0, "Amp", 2, "Gain", 0.2, 0.3);
+
+        manager.begin_group("Cancelled step");
+        manager.record_untracked("Container with FX [0, 1]");
+        manager.cancel_action();
+        assert!(manager.untracked_changes().is_empty());
+
+        manager.record_untracked("Send track 'Wet'");
+        assert_eq!(manager.untracked_changes(), ["Send track 'Wet'"]);
+        assert_eq!(manager.end_group(), None);
+        assert_eq!(manager.undo_count(), 0);
+        assert!(manager.untracked_changes().is_empty());
+    }
+}
@@ -0,0 +1,534 @@
+//! Branching Undo Tree
+//!
+//! Every committed action is a node whose parent is the action that was current when it was
+//! committed, so undoing and then doing something new starts a branch instead of discarding
+//! the redo history. The linear undo/redo stacks are views of the tree: the path from the
+//! root to the current node, and the chain of most recently visited children below it.
+//!
+//! `None` as a node id means the root state (before any recorded action).
+
+use crate::undo_redo::UndoAction;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoNode {
+    pub action: UndoAction,
+    pub parent: Option<String>,
+    /// Child that redo follows (the most recently visited one).
+    #[serde(default)]
+    pub active_child: Option<String>,
+    /// Insertion order, used to prune the oldest branches first.
+    pub seq: u64,
+}
+
+/// A named position in the tree (e.g. "pre-mix").
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UndoCheckpoint {
+    pub name: String,
+    pub node_id: Option<String>,
+    pub timestamp: u64,
+}
+
+/// Steps needed to move from the current node to another one: revert `revert` (in order,
+/// current first), then apply `apply` (in order, ending at the target).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UndoJump {
+    pub revert: Vec<String>,
+    pub apply: Vec<String>,
+}
+
+impl UndoJump {
+    pub fn is_empty(&self) -> bool {
+        self.revert.is_empty() && self.apply.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.revert.len() + self.apply.len()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UndoTree {
+    nodes: Vec<UndoNode>,
+    current: Option<String>,
+    /// Root-level child that redo follows from the root state.
+    #[serde(default)]
+    root_active: Option<String>,
+    #[serde(default)]
+    checkpoints: Vec<UndoCheckpoint>,
+    #[serde(default)]
+    next_seq: u64,
+}
+
+impl UndoTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a single-branch tree from linear stacks (bottom -> top each).
+    pub fn from_stacks(undo: Vec<UndoAction>, redo: Vec<UndoAction>) -> Self {
+        let mut tree = Self::new();
+        for action in undo {
+            tree.insert(action);
+        }
+        let current = tree.current.clone();
+        for action in redo.into_iter().rev() {
+            tree.insert(action);
+        }
+        while tree.current != current {
+            tree.step_back();
+        }
+        tree
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn nodes(&self) -> &[UndoNode] {
+        &self.nodes
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.node(id).is_some()
+    }
+
+    pub fn node(&self, id: &str) -> Option<&UndoNode> {
+        self.nodes.iter().find(|n| n.action.id == id)
+    }
+
+    fn node_mut(&mut self, id: &str) -> Option<&mut UndoNode> {
+        self.nodes.iter_mut().find(|n| n.action.id == id)
+    }
+
+    pub fn action_mut(&mut self, id: &str) -> Option<&mut UndoAction> {
+        self.node_mut(id).map(|n| &mut n.action)
+    }
+
+    pub fn current_id(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    pub fn current_action(&self) -> Option<&UndoAction> {
+        self.current
+            .as_deref()
+            .and_then(|id| self.node(id))
+            .map(|n| &n.action)
+    }
+
+    /// The node redo would apply next.
+    pub fn next_id(&self) -> Option<&str> {
+        let active = match self.current.as_deref() {
+            Some(id) => self.node(id)?.active_child.as_deref(),
+            None => self.root_active.as_deref(),
+        }?;
+        self.contains(active).then_some(active)
+    }
+
+    pub fn next_action(&self) -> Option<&UndoAction> {
+        self.next_id()
+            .and_then(|id| self.node(id))
+            .map(|n| &n.action)
+    }
+
+    pub fn children<'a>(&'a self, parent: Option<&'a str>) -> impl Iterator<Item = &'a UndoNode> {
+        self.nodes
+            .iter()
+            .filter(move |n| n.parent.as_deref() == parent)
+    }
+
+    /// Ids from `id` up to its root (inclusive).
+    pub fn ancestors(&self, id: Option<&str>) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut cursor = id.map(String::from);
+        while let Some(id) = cursor {
+            cursor = self.node(&id).and_then(|n| n.parent.clone());
+            path.push(id);
+        }
+        path
+    }
+
+    /// Actions from the root to the current node (the undo stack, bottom -> top).
+    pub fn undo_path(&self) -> Vec<&UndoAction> {
+        let mut path: Vec<&UndoAction> = self
+            .ancestors(self.current.as_deref())
+            .iter()
+            .filter_map(|id| self.node(id))
+            .map(|n| &n.action)
+            .collect();
+        path.reverse();
+        path
+    }
+
+    /// Actions redo would apply, nearest first.
+    pub fn redo_chain(&self) -> Vec<&UndoAction> {
+        let mut chain = Vec::new();
+        let mut seen = HashSet::new();
+        let mut cursor = self.next_id().map(String::from);
+        while let Some(id) = cursor {
+            let Some(node) = self.node(&id) else { break };
+            if !seen.insert(id.clone()) {
+                break;
+            }
+            chain.push(&node.action);
+            cursor = node
+                .active_child
+                .clone()
+                .filter(|child| self.contains(child));
+        }
+        chain
+    }
+
+    fn set_active_child(&mut self, parent: Option<&str>, child: Option<String>) {
+        match parent {
+            Some(parent) => {
+                if let Some(node) = self.node_mut(parent) {
+                    node.active_child = child;
+                }
+            }
+            None => self.root_active = child,
+        }
+    }
+
+    /// Add an action as a child of the current node and make it current.
+    pub fn insert(&mut self, action: UndoAction) -> String {
+        let id = self.attach_child(action);
+        self.current = Some(id.clone());
+        id
+    }
+
+    /// Add an action as a child of the current node (the next redo) without moving to it.
+    pub fn attach_child(&mut self, action: UndoAction) -> String {
+        let id = action.id.clone();
+        let parent = self.current.clone();
+        self.set_active_child(parent.as_deref(), Some(id.clone()));
+        self.nodes.push(UndoNode {
+            action,
+            parent,
+            active_child: None,
+            seq: self.next_seq,
+        });
+        self.next_seq += 1;
+        id
+    }
+
+    /// Move to the parent of the current node. Returns the action to revert.
+    pub fn step_back(&mut self) -> Option<UndoAction> {
+        let id = self.current.clone()?;
+        let node = self.node(&id)?;
+        let (action, parent) = (node.action.clone(), node.parent.clone());
+        self.set_active_child(parent.as_deref(), Some(id));
+        self.current = parent;
+        Some(action)
+    }
+
+    /// Move to the active child of the current node. Returns the action to apply.
+    pub fn step_forward(&mut self) -> Option<UndoAction> {
+        let next = self.next_id()?.to_string();
+        self.step_into(&next).ok()
+    }
+
+    /// Move to a specific child of the current node. Returns the action to apply.
+    pub fn step_into(&mut self, child: &str) -> Result<UndoAction, String> {
+        let node = self
+            .node(child)
+            .ok_or_else(|| format!("Unknown undo entry '{}'", child))?;
+        if node.parent != self.current {
+            return Err(format!(
+                "'{}' is not a child of the current entry",
+                node.action.description
+            ));
+        }
+        let action = node.action.clone();
+        let current = self.current.clone();
+        self.set_active_child(current.as_deref(), Some(child.to_string()));
+        self.current = Some(child.to_string());
+        Ok(action)
+    }
+
+    /// Minimal path from the current node to `target` through their common ancestor.
+    pub fn plan_jump(&self, target: Option<&str>) -> Result<UndoJump, String> {
+        if let Some(target) = target {
+            if !self.contains(target) {
+                return Err(format!("Unknown undo entry '{}'", target));
+            }
+        }
+
+        let from = self.ancestors(self.current.as_deref());
+        let to = self.ancestors(target);
+        let to_set: HashSet<&String> = to.iter().collect();
+
+        let revert: Vec<String> = from
+            .iter()
+            .take_while(|id| !to_set.contains(id))
+            .cloned()
+            .collect();
+        let common = from.get(revert.len());
+        let mut apply: Vec<String> = to
+            .iter()
+            .take_while(|id| Some(*id) != common)
+            .cloned()
+            .collect();
+        apply.reverse();
+
+        Ok(UndoJump { revert, apply })
+    }
+
+    pub fn checkpoints(&self) -> &[UndoCheckpoint] {
+        &self.checkpoints
+    }
+
+    pub fn checkpoint(&self, name: &str) -> Option<&UndoCheckpoint> {
+        self.checkpoints
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Name the current node. An existing checkpoint with the same name is moved here.
+    pub fn set_checkpoint(&mut self, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Checkpoint name cannot be empty".to_string());
+        }
+        self.remove_checkpoint(name);
+        self.checkpoints.push(UndoCheckpoint {
+            name: name.to_string(),
+            node_id: self.current.clone(),
+            timestamp: now_secs(),
+        });
+        Ok(())
+    }
+
+    pub fn remove_checkpoint(&mut self, name: &str) -> bool {
+        let before = self.checkpoints.len();
+        self.checkpoints
+            .retain(|c| !c.name.eq_ignore_ascii_case(name));
+        self.checkpoints.len() != before
+    }
+
+    /// Drop nodes until at most `max` remain: the oldest branch tips off the current path
+    /// go first, then the oldest entries of the current path itself.
+    pub fn prune(&mut self, max: usize) {
+        while self.nodes.len() > max.max(1) {
+            let on_path: HashSet<String> = self
+                .ancestors(self.current.as_deref())
+                .into_iter()
+                .collect();
+            let parents: HashSet<&str> = self
+                .nodes
+                .iter()
+                .filter_map(|n| n.parent.as_deref())
+                .collect();
+            let leaf = self
+                .nodes
+                .iter()
+                .filter(|n| !parents.contains(n.action.id.as_str()))
+                .filter(|n| !on_path.contains(&n.action.id))
+                .min_by_key(|n| n.seq)
+                .map(|n| n.action.id.clone());
+
+            match leaf {
+                Some(leaf) => self.remove_leaf(&leaf),
+                None => {
+                    // Only the current path is left; it is a single line.
+                    let Some(root) = self.ancestors(self.current.as_deref()).pop() else {
+                        break;
+                    };
+                    self.rebase_onto(&root);
+                }
+            }
+        }
+    }
+
+    fn remove_leaf(&mut self, id: &str) {
+        let Some(pos) = self.nodes.iter().position(|n| n.action.id == id) else {
+            return;
+        };
+        let node = self.nodes.remove(pos);
+        let parent_active = match node.parent.as_deref() {
+            Some(parent) => self.node(parent).and_then(|n| n.active_child.clone()),
+            None => self.root_active.clone(),
+        };
+        if parent_active.as_deref() == Some(id) {
+            self.set_active_child(node.parent.as_deref(), None);
+        }
+        self.checkpoints
+            .retain(|c| c.node_id.as_deref() != Some(id));
+    }
+
+    /// Make the state after `base` the new root: `base` and every node that is not one of
+    /// its descendants are removed. Returns how many nodes were removed.
+    fn rebase_onto(&mut self, base: &str) -> usize {
+        let keep: HashSet<String> = self
+            .nodes
+            .iter()
+            .filter(|n| {
+                let ancestors = self.ancestors(Some(&n.action.id));
+                ancestors.len() > 1 && ancestors.contains(&base.to_string())
+            })
+            .map(|n| n.action.id.clone())
+            .collect();
+
+        let root_active = self.node(base).and_then(|n| n.active_child.clone());
+        let before = self.nodes.len();
+        self.nodes.retain(|n| keep.contains(&n.action.id));
+        for node in &mut self.nodes {
+            if node.parent.as_deref() == Some(base) {
+                node.parent = None;
+            }
+        }
+        self.root_active = root_active;
+
+        if self.current.as_deref().is_some_and(|id| !keep.contains(id)) {
+            self.current = None;
+        }
+        self.checkpoints.retain_mut(|c| match c.node_id.as_deref() {
+            Some(id) if id == base => {
+                c.node_id = None;
+                true
+            }
+            Some(id) => keep.contains(id),
+            None => false,
+        });
+        before - self.nodes.len()
+    }
+
+    /// Remove `id` and all of its descendants. Returns how many nodes were removed.
+    fn remove_subtree(&mut self, id: &str) -> usize {
+        let doomed: HashSet<String> = self
+            .nodes
+            .iter()
+            .filter(|n| self.ancestors(Some(&n.action.id)).iter().any(|a| a == id))
+            .map(|n| n.action.id.clone())
+            .collect();
+        let parent = self.node(id).and_then(|n| n.parent.clone());
+        let before = self.nodes.len();
+        self.nodes.retain(|n| !doomed.contains(&n.action.id));
+        if self.next_id().is_none() {
+            self.set_active_child(parent.as_deref(), None);
+        }
+        self.checkpoints
+            .retain(|c| c.node_id.as_ref().is_none_or(|n| !doomed.contains(n)));
+        before - self.nodes.len()
+    }
+
+    /// Drop stale entries along the current undo path and redo chain. Entries that only
+    /// make sense on top of a dropped one are dropped with it. Returns how many were removed.
+    pub fn discard_stale(&mut self) -> usize {
+        let mut removed = 0;
+
+        let deepest_stale = self
+            .ancestors(self.current.as_deref())
+            .into_iter()
+            .find(|id| self.node(id).is_some_and(|n| n.action.is_stale()));
+        if let Some(base) = deepest_stale {
+            removed += self.rebase_onto(&base);
+        }
+
+        let first_stale = self
+            .redo_chain()
+            .into_iter()
+            .find(|a| a.is_stale())
+            .map(|a| a.id.clone());
+        if let Some(id) = first_stale {
+            removed += self.remove_subtree(&id);
+        }
+
+        removed
+    }
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(name: &str) -> UndoAction {
+        let mut action = UndoAction::new(name);
+        action.id = name.to_string();
+        action
+    }
+
+    /// root -> a -> b, then undo b and branch a -> c.
+    fn branched() -> UndoTree {
+        let mut tree = UndoTree::new();
+        tree.insert(action("a"));
+        tree.insert(action("b"));
+        tree.step_back();
+        tree.insert(action("c"));
+        tree
+    }
+
+    #[test]
+    fn new_action_after_undo_keeps_old_branch() {
+        let tree = branched();
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.current_id(), Some("c"));
+        assert!(tree.redo_chain().is_empty());
+        assert_eq!(tree.children(Some("a")).count(), 2);
+    }
+
+    #[test]
+    fn jump_goes_through_common_ancestor() {
+        let mut tree = branched();
+        let jump = tree.plan_jump(Some("b")).unwrap();
+        assert_eq!(jump.revert, vec!["c".to_string()]);
+        assert_eq!(jump.apply, vec!["b".to_string()]);
+
+        let to_root = tree.plan_jump(None).unwrap();
+        assert_eq!(to_root.revert, vec!["c".to_string(), "a".to_string()]);
+        assert!(to_root.apply.is_empty());
+
+        tree.step_back();
+        tree.step_into("b").unwrap();
+        assert!(tree.plan_jump(Some("b")).unwrap().is_empty());
+        // Redo from `a` now follows the branch visited last.
+        tree.step_back();
+        assert_eq!(tree.next_id(), Some("b"));
+    }
+
+    #[test]
+    fn checkpoints_survive_pruning_of_other_branches() {
+        let mut tree = branched();
+        tree.set_checkpoint("pre-mix").unwrap();
+        tree.set_checkpoint("PRE-MIX").unwrap();
+        assert_eq!(tree.checkpoints().len(), 1);
+
+        tree.prune(2);
+        assert!(!tree.contains("b"));
+        assert_eq!(
+            tree.checkpoint("pre-mix").unwrap().node_id.as_deref(),
+            Some("c")
+        );
+
+        // Dropping the root re-anchors a checkpoint on it to the root state.
+        tree.step_back();
+        tree.set_checkpoint("start").unwrap();
+        tree.step_forward();
+        tree.prune(1);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree.checkpoint("start").unwrap().node_id, None);
+    }
+
+    #[test]
+    fn linear_stacks_become_a_single_branch() {
+        let tree = UndoTree::from_stacks(
+            vec![action("a"), action("b")],
+            vec![action("d"), action("c")],
+        );
+        assert_eq!(tree.current_id(), Some("b"));
+        let redo: Vec<&str> = tree.redo_chain().iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(redo, vec!["c", "d"]);
+    }
+}